@@ -1,10 +1,20 @@
 use thiserror::Error;
+use toolkit::Status;
 
 #[derive(Error, Debug)]
 pub enum NtApiError {
     #[error("Buffer too small: needed {needed} bytes, available {available} bytes")]
     BufferTooSmall { needed: usize, available: usize },
     
-    #[error("NtQuerySystemInformation failed with status: {0:X}")]
-    QueryFailed(i32),
-}
\ No newline at end of file
+    #[error("NtQuerySystemInformation failed: {0}")]
+    QueryFailed(Status),
+}
+
+impl From<NtApiError> for Status {
+    fn from(err: NtApiError) -> Self {
+        match err {
+            NtApiError::BufferTooSmall { .. } => Status::INFO_LENGTH_MISMATCH,
+            NtApiError::QueryFailed(status) => status,
+        }
+    }
+}
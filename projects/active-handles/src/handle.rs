@@ -70,7 +70,7 @@ impl SystemHandleIterator {
             }
 
             if status != STATUS_SUCCESS {
-                return Err(NtApiError::QueryFailed(status));
+                return Err(NtApiError::QueryFailed(status.into()));
             }
 
             let info = BUFFER.0.as_ptr() as *const SYSTEM_HANDLE_INFORMATION_EX;
@@ -51,7 +51,7 @@ impl<'a> ObjectTypeIterator<'a> {
             }
 
             if status != STATUS_SUCCESS {
-                return Err(NtApiError::QueryFailed(status));
+                return Err(NtApiError::QueryFailed(status.into()));
             }
 
             let info_ptr = buffer_ptr as *const OBJECT_ALL_TYPES_INFORMATION;
@@ -5,16 +5,31 @@
 #![allow(unused)]
 
 use log::{LevelFilter, info};
-use toolkit::println;
+use toolkit::logger::{ConsoleLogger, LogFormat, LoggerError, MultiLogger, NtFileLogger, Rotation};
+use toolkit::{OnceLock, println};
 
-use crate::{etw_logger::EventViewerLogger, nt_file_logger::NtFileLogger};
+use crate::etw_logger::EventViewerLogger;
 
 mod etw_logger;
-mod nt_file_logger;
 mod time;
 
 extern crate builtins;
 
+static CONSOLE_LOGGER: ConsoleLogger = ConsoleLogger::new(LevelFilter::Debug, LogFormat::Logfmt);
+static FILE_LOGGER: OnceLock<NtFileLogger> = OnceLock::new();
+static ETW_LOGGER: OnceLock<EventViewerLogger> = OnceLock::new();
+static LOGGER: OnceLock<MultiLogger<3>> = OnceLock::new();
+
+/// Fans out to a rotating JSON file, the console and ETW.
+fn init_logging() -> Result<(), LoggerError> {
+    let file = NtFileLogger::new(LevelFilter::Debug, LogFormat::Json, Rotation::new(1024 * 1024, 3))?;
+    let file = FILE_LOGGER.get_or_init(|| file);
+    let etw = ETW_LOGGER.get_or_init(EventViewerLogger::new);
+
+    let logger = LOGGER.get_or_init(|| MultiLogger::new([file, &CONSOLE_LOGGER, etw]));
+    logger.init(LevelFilter::Debug).map_err(|_| LoggerError::SetLogger)
+}
+
 #[inline(never)]
 #[panic_handler]
 fn panic(info: &core::panic::PanicInfo) -> ! {
@@ -26,17 +41,18 @@ fn panic(info: &core::panic::PanicInfo) -> ! {
 pub extern "C" fn mainCRTStartup() -> i32 {
     // let logger = NtFileLogger::init(LevelFilter::Debug).unwrap();
     // println!("NtFileLogger::init");
-    if let Err(err) = EventViewerLogger::init() {
-        println!("Failed to initialize logger: {:?}", err);
+    if let Err(err) = init_logging() {
+        println!("Failed to initialize logger: {}", err);
         return 1;
     }
     
     // println!("test");
-    info!("Application started!");
+    info!(version = 1, mode = "multi"; "Application started!");
     log::warn!("This is a warning message");
     log::error!("An error occurred: file not found");
     // log::debug!("Debug message with value: {}", 42);
     
     // drop(logger);
+    log::logger().flush();
     0
 }
\ No newline at end of file
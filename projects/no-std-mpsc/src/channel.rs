@@ -14,10 +14,10 @@ pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
 
 use alloc::{boxed::Box, vec::Vec};
 use ntapi::winapi_local::um::winnt::NtCurrentTeb;
-use toolkit::{Arc, println};
+use toolkit::{Arc, println, time::Instant};
 use winapi::um::synchapi::{WaitOnAddress, WakeByAddressSingle};
 
-use crate::{backoff::Backoff, futex::{wait_on_address, wake_by_address_single}, instant::Instant, mutex::Mutex};
+use crate::{backoff::Backoff, futex::{wait_on_address, wake_by_address_single}, mutex::Mutex};
 
 const LAP: usize = 32;
 const BLOCK_CAP: usize = LAP - 1;
@@ -226,7 +226,7 @@ impl<T> Receiver<T> {
                 }
             }
 
-            let cx = Context::get_from_teb_or_create();
+            let cx = &Context::current();
             let oper = Operation::hook(token);
             channel.counter_receivers.register(oper, cx);
 
@@ -579,6 +579,10 @@ impl Into<usize> for Selected {
 #[derive(Clone)]
 pub struct Context(Arc<Inner>);
 
+toolkit::thread_local! {
+    static CONTEXT: Context = Context::new();
+}
+
 struct Inner {
     select: Atomic<usize>,
     packet: Atomic<*mut ()>,
@@ -659,7 +663,7 @@ impl Thread {
         }
         println!("{}", new_id);
         let inner = unsafe {
-            let mut arc = Arc::<ThreadInner>::new_uninit_in();
+            let mut arc = Arc::<ThreadInner>::new_uninit();
             let ptr = Arc::get_mut_unchecked(&mut arc).as_mut_ptr();
             (&raw mut (*ptr).name).write(None);
             (&raw mut (*ptr).id).write(NonZero::new(new_id).unwrap());
@@ -691,31 +695,20 @@ impl Thread {
 
 impl Context {
 
-    pub fn get_from_teb_or_create() -> &'static Self {
-        let teb = unsafe { NtCurrentTeb() };
-        let slot_ptr = unsafe { &(*teb).TlsSlots[0] };
-        
-        if slot_ptr.is_null() {
-            let thread = Thread::new();
-            let inner = Inner {
-                select: AtomicUsize::new(Selected::Waiting.into()),
-                packet: AtomicPtr::new(core::ptr::null_mut()),
-                thread,
-                thread_id: Context::current_thread_id(),
-            };
-            
-            let context = Context(Arc::new(inner));
-            let static_context: &'static mut Context = Box::leak(Box::new(context));
-            
-            unsafe {
-                (*teb).TlsSlots[0] = static_context as *mut Context as *mut () as _;
-            }
-            
-            static_context
-        } else {
-            let context_ptr = *slot_ptr as *mut Context;
-            unsafe { &mut *context_ptr }
-        }
+    /// The calling thread's context, created on first use.
+    pub fn current() -> Self {
+        CONTEXT.with(Context::clone)
+    }
+
+    fn new() -> Self {
+        let inner = Inner {
+            select: AtomicUsize::new(Selected::Waiting.into()),
+            packet: AtomicPtr::new(core::ptr::null_mut()),
+            thread: Thread::new(),
+            thread_id: Context::current_thread_id(),
+        };
+
+        Context(Arc::new(inner))
     }
 
     pub fn thread_id(&self) -> usize {
@@ -30,7 +30,6 @@ static ALLOCATOR: CustAllocator<8192> = CustAllocator::new();
 mod channel;
 mod mutex;
 mod backoff;
-mod instant;
 mod custalloc;
 mod futex;
 
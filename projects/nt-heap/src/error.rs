@@ -1,6 +1,10 @@
-use winapi::shared::ntdef::NTSTATUS;
+use core::fmt;
 
+use toolkit::Status;
+use winapi::shared::ntdef::NTSTATUS;
 
+/// A failed heap call. Calls that report no status, like a null
+/// `RtlCreateHeap`, carry `0`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct HeapError(pub NTSTATUS);
 
@@ -10,4 +14,30 @@ impl HeapError {
     }
 }
 
+impl fmt::Display for HeapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            0 => write!(f, "Heap operation failed"),
+            status => write!(f, "Heap operation failed: {}", Status(status)),
+        }
+    }
+}
+
+impl core::error::Error for HeapError {}
+
+impl From<Status> for HeapError {
+    fn from(status: Status) -> Self {
+        Self(status.code())
+    }
+}
+
+impl From<HeapError> for Status {
+    fn from(err: HeapError) -> Self {
+        match err.0 {
+            0 => Status::UNSUCCESSFUL,
+            status => Status(status),
+        }
+    }
+}
+
 pub type HeapResult<T> = Result<T, HeapError>;
\ No newline at end of file
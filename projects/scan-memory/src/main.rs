@@ -5,10 +5,16 @@
 use core::panic::PanicInfo;
 
 use ntapi::ntpsapi::NtCurrentProcess;
-use toolkit::{MemoryRegionIterator, MemoryRegionReverseIterator, Sleeper, maximum_user_address, println};
+use toolkit::{MemoryMap, maximum_user_address, println};
+use toolkit::scan::{DEFAULT_CHUNK_SIZE, Pattern, PatternSet};
 
 extern crate builtins;
 
+extern crate alloc;
+
+#[global_allocator]
+static ALLOCATOR: emballoc::Allocator<4194304> = emballoc::Allocator::new();
+
 #[inline(never)]
 #[panic_handler]
 fn panic(_info: &PanicInfo) -> ! {
@@ -26,11 +32,18 @@ pub extern "C" fn mainCRTStartup() -> i32 {
     //     Sleeper::sleep(100);
     // }
 
-    let iter = MemoryRegionIterator::new(NtCurrentProcess);
+    let map = MemoryMap::current().unwrap().coalesce();
+    println!("{} regions", map.len());
+
+    // mov r10, rcx; mov eax, <number>: the start of every ntdll syscall stub.
+    let stubs = PatternSet::from(Pattern::parse("4C 8B D1 B8 ?? ?? ?? ??").unwrap());
+    let mut process = NtCurrentProcess;
+    let matches = stubs.scan_map(&mut process, &map, DEFAULT_CHUNK_SIZE);
+    println!("{} syscall stubs", matches.len());
 
-    for region in iter {
-        println!("{region}");
-        Sleeper::sleep(100);
+    for found in matches.iter().take(10) {
+        let region = map.find(found.offset).unwrap();
+        println!("0x{:X} in {}", found.offset, region.file.as_deref().unwrap_or("?"));
     }
 
     0
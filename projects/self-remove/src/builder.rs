@@ -7,7 +7,7 @@ use crate::{encoder::EncoderError, injector::ProcessInjector, shellcode::Shellco
 
 #[derive(Debug)]
 pub enum InjectionError {
-    NtStatus(NTSTATUS),
+    NtStatus(Status),
     ShellcodeError(EncoderError),
     ProcessNotFound,
     ThreadError,
@@ -17,23 +17,35 @@ pub enum InjectionError {
 
 impl From<NTSTATUS> for InjectionError {
     fn from(status: NTSTATUS) -> Self {
+        InjectionError::NtStatus(Status(status))
+    }
+}
+
+impl From<Status> for InjectionError {
+    fn from(status: Status) -> Self {
         InjectionError::NtStatus(status)
     }
 }
 
-impl From<InjectionError> for i32 {
+impl From<InjectionError> for Status {
     fn from(err: InjectionError) -> Self {
         match err {
-            InjectionError::NtStatus(status) => status as i32,
-            InjectionError::ShellcodeError(_) => 0xC000000Du32 as i32,
-            InjectionError::ProcessNotFound => 0xC0000034u32 as i32,
-            InjectionError::ThreadError => 0xC000000Du32 as i32,
-            InjectionError::MemoryError => 0xC0000017u32 as i32,
-            InjectionError::InjectionFailed => 0xC0000005u32 as i32,
+            InjectionError::NtStatus(status) => status,
+            InjectionError::ShellcodeError(_) => Status::INVALID_PARAMETER,
+            InjectionError::ProcessNotFound => Status::OBJECT_NAME_NOT_FOUND,
+            InjectionError::ThreadError => Status::INVALID_PARAMETER,
+            InjectionError::MemoryError => Status::NO_MEMORY,
+            InjectionError::InjectionFailed => Status::ACCESS_VIOLATION,
         }
     }
 }
 
+impl From<InjectionError> for i32 {
+    fn from(err: InjectionError) -> Self {
+        Status::from(err).into()
+    }
+}
+
 pub struct InjectionBuilder<const N: usize> {
     target_path: U16CStackString<N>,
     executable_path: ExecutablePath,
@@ -1,5 +1,6 @@
 use alloc::boxed::Box;
 use ntapi::ntmmapi::{NtAllocateVirtualMemory, NtFreeVirtualMemory};
+use toolkit::Status;
 use winapi::{ctypes, shared::ntdef::{HANDLE, NT_SUCCESS}, um::winnt::{MEM_COMMIT, PAGE_EXECUTE_READWRITE, RUNTIME_FUNCTION, RtlAddFunctionTable, RtlDeleteFunctionTable, RtlLookupFunctionEntry}};
 
 use crate::{code_buffer::CodeBuffer, code_writer::{CodeWriter, FaultingCode}, types::*};
@@ -10,12 +11,24 @@ pub struct ExceptionData {
 
 #[derive(Debug, Clone, Copy)]
 pub enum SetupError {
-    NtAllocateVirtualMemory,
+    NtAllocateVirtualMemory(Status),
     NtWriteVirtualMemory,
     RtlAddFunctionTable,
     RtlLookupFunctionEntry
 }
 
+/// The `Rtl` calls only report success or failure, so they get the closest
+/// generic status.
+impl From<SetupError> for Status {
+    fn from(err: SetupError) -> Self {
+        match err {
+            SetupError::NtAllocateVirtualMemory(status) => status,
+            SetupError::NtWriteVirtualMemory | SetupError::RtlAddFunctionTable => Status::UNSUCCESSFUL,
+            SetupError::RtlLookupFunctionEntry => Status::NOT_FOUND,
+        }
+    }
+}
+
 #[repr(C)]
 pub struct DynamicSection {
     pub code: CodeBuffer<0x600>,
@@ -42,7 +55,7 @@ impl DynamicSection {
             );
 
             if !NT_SUCCESS(status) {
-                return Err(SetupError::NtAllocateVirtualMemory);
+                return Err(SetupError::NtAllocateVirtualMemory(Status(status)));
             }
 
             let base = base_ptr as *mut Self;
@@ -3,7 +3,7 @@ use crate::arena::{Arena, Pos, SIZE};
 use crate::snake::{Direction, Snake};
 use super::types::{BoundedMove, Node, Path, Queue};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct BfsPathfinder;
 
 impl Pathfinder for BfsPathfinder {
@@ -3,7 +3,7 @@ use crate::snake::{Direction, Snake};
 use crate::ai::Pathfinder;
 use super::types::{BoundedMove, Path};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct GreedyPathfinder;
 
 impl Pathfinder for GreedyPathfinder {
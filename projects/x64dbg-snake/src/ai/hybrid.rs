@@ -5,7 +5,7 @@ use super::types::Path;
 use super::bfs::BfsPathfinder;
 use super::greedy::GreedyPathfinder;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct HybridPathfinder {
     bfs: BfsPathfinder,
     greedy: GreedyPathfinder,
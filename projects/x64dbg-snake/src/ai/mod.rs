@@ -15,6 +15,7 @@ use crate::snake::{Direction, Snake};
 pub type Buf64 = Buf<u8, 64>;
 pub type StackedPathfinder = Stacked<dyn Pathfinder, Buf64>;
 
+#[derive(Clone)]
 pub struct Ai(StackedPathfinder);
 
 impl Ai {
@@ -22,6 +23,11 @@ impl Ai {
         Self(pathfinder)
     }
 
+    /// Switches strategy in place, returning the previous one.
+    pub fn replace_pathfinder(&mut self, pathfinder: StackedPathfinder) -> StackedPathfinder {
+        core::mem::replace(&mut self.0, pathfinder)
+    }
+
     pub fn next_move(&self, snake: &Snake, food: Pos, arena: &Arena) -> Direction {
         let head = snake.head();
 
@@ -1,4 +1,5 @@
 use heapless::Vec;
+use toolkit::stack_trait::DynClone;
 use crate::arena::{Arena, Pos, SIZE};
 use crate::snake::{Direction, Snake};
 
@@ -87,6 +88,6 @@ pub enum Algorithm {
     Hybrid,
 }
 
-pub trait Pathfinder {
+pub trait Pathfinder: DynClone {
     fn find_path(&self, snake: &Snake, target: Pos, arena: &Arena) -> Option<Path>;
 }
\ No newline at end of file
@@ -1,5 +1,7 @@
 use core::sync::atomic::{AtomicU64, Ordering};
 
+use toolkit::rand::RngCore;
+
 use crate::arena::Pos;
 
 #[repr(transparent)]
@@ -45,4 +47,10 @@ impl Rng {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+impl RngCore for Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.next()
+    }
+}
@@ -1,114 +1,281 @@
-use core::{alloc::Layout, mem::{self, offset_of}, ops::Deref, ptr::{self, NonNull}, sync::atomic::{AtomicUsize, Ordering}};
+use core::{alloc::Layout, fmt, hint, marker::Unsize, mem::{self, offset_of}, ops::{CoerceUnsized, Deref}, ptr::{self, NonNull}, sync::atomic::{self, AtomicUsize, Ordering}};
 
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
 #[cfg(feature = "alloc")]
-use alloc::alloc::AllocError;
+use alloc::alloc::{AllocError, Allocator, Global};
 
 #[cfg(feature = "alloc")]
 pub use alloc::boxed::Box;
 
-pub struct Arc<T: ?Sized>(*mut ArcInner<T>);
-
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec::Vec};
+
+/// Same limit as std: past it a leaked-clone loop would be close to overflowing the counter.
+const MAX_REFCOUNT: usize = isize::MAX as usize;
+
+/// Every strong reference owns a copy of the allocator handle, so `A` is
+/// usually a ZST like [`Global`] or a cheap reference to an arena. Only
+/// [`Arc::new_in`] and [`Arc::new_uninit_in`] take an allocator; slices,
+/// `str` and collected `Arc`s always live in [`Global`].
+pub struct Arc<T: ?Sized, A: Allocator = Global>(*mut ArcInner<T>, A);
+
+/// Like std, all strong references together hold one weak reference, so the
+/// allocation is released when the weak count drops to zero.
+///
+/// `repr(C)` keeps `data` after the counters, which `allocate_for_layout` relies on.
+#[repr(C)]
 pub struct ArcInner<T: ?Sized> {
-    count: AtomicUsize,
+    strong: AtomicUsize,
+    weak: AtomicUsize,
     data: T,
 }
 
-unsafe impl<T: Send + Sync + ?Sized> Send for Arc<T> {}
-unsafe impl<T: Send + Sync + ?Sized> Sync for Arc<T> {}
+/// Borrows only the counters, never `data`, which may be mutably borrowed or
+/// already dropped while `Weak`s are still around.
+struct Counts<'a> {
+    strong: &'a AtomicUsize,
+    weak: &'a AtomicUsize,
+}
+
+impl<'a> Counts<'a> {
+    unsafe fn of<T: ?Sized>(ptr: *mut ArcInner<T>) -> Self {
+        unsafe {
+            Self {
+                strong: &(*ptr).strong,
+                weak: &(*ptr).weak,
+            }
+        }
+    }
+}
+
+unsafe impl<T: Send + Sync + ?Sized, A: Allocator + Send> Send for Arc<T, A> {}
+unsafe impl<T: Send + Sync + ?Sized, A: Allocator + Sync> Sync for Arc<T, A> {}
 
 impl<T> Arc<T> {
     pub fn new(data: T) -> Self {
-        let inner = Box::new(ArcInner {
-            count: AtomicUsize::new(1),
-            data,
-        });
-        
-        Self(Box::into_raw(inner))
+        Self::new_in(data, Global)
     }
 
-    pub fn new_uninit() -> Arc<mem::MaybeUninit<T>> {
-        let layout = Layout::new::<T>();
-        let ptr = unsafe {
-            let raw = alloc::alloc::alloc(layout);
-            if raw.is_null() {
-                alloc::alloc::handle_alloc_error(layout);
-            }
-            raw as *mut ArcInner<mem::MaybeUninit<T>>
-        };
+    /// `data_fn` gets a `Weak` to the allocation under construction; upgrading it
+    /// fails until `new_cyclic` returns.
+    pub fn new_cyclic<F: FnOnce(&Weak<T>) -> T>(data_fn: F) -> Self {
+        let uninit = Arc::<T>::new_uninit();
+        let ptr = Arc::leak(uninit) as *mut ArcInner<T>;
+
         unsafe {
-            ptr.write(ArcInner {
-                count: AtomicUsize::new(1),
-                data: mem::MaybeUninit::uninit(),
-            });
+            (*ptr).strong.store(0, Ordering::Relaxed);
         }
-        Arc(ptr)
-    }
 
-    pub fn new_uninit_in() -> Arc<mem::MaybeUninit<T>> {
+        let weak = Weak(ptr, Global);
+        let data = data_fn(&weak);
+
         unsafe {
-            Arc::from_ptr_in(
-                Arc::allocate_for_layout(
-                    Layout::new::<T>(),
-                    |layout| {
-                        let ptr: *mut u8 = alloc::alloc::alloc(layout);
-                        let slice_ptr = core::ptr::slice_from_raw_parts_mut(ptr, layout.size());
-                        let non_null = NonNull::new(slice_ptr).unwrap();
-                        Ok(non_null)
-                    },
-                    <*mut u8>::cast,
-                )
-            )
+            (&raw mut (*ptr).data).write(data);
+            (*ptr).strong.store(1, Ordering::Release);
         }
-    }
 
-    unsafe fn from_ptr_in(ptr: *mut ArcInner<T>) -> Self {
-        unsafe { Self::from_inner_in(NonNull::new_unchecked(ptr)) }
+        mem::forget(weak);
+        Self(ptr, Global)
     }
 
-    unsafe fn from_inner_in(ptr: NonNull<ArcInner<T>>) -> Self {
-        Self(ptr.as_ptr())
+    pub fn new_uninit() -> Arc<mem::MaybeUninit<T>> {
+        Self::new_uninit_in(Global)
     }
 
+    /// # Safety
+    ///
+    /// `ptr` must come from [`Arc::into_raw_arcinner`] or [`Arc::leak`] and owns
+    /// the strong reference it carried.
     pub unsafe fn from_raw_arcinner(ptr: *const ArcInner<T>) -> Self {
-        Self(ptr as *mut ArcInner<T>)
+        Self(ptr as *mut ArcInner<T>, Global)
     }
 
+    /// # Safety
+    ///
+    /// `ptr` must come from [`Arc::into_raw`] and owns the strong reference it carried.
     pub unsafe fn from_raw(ptr: *const T) -> Self {
-        let data_ptr = ptr as *const u8;
-        let inner_ptr = data_ptr.sub(offset_of!(ArcInner<T>, data)) as *mut ArcInner<T>;
-        Self(inner_ptr)
+        unsafe { Self::from_raw_in(ptr, Global) }
+    }
+
+    pub fn into_raw(this: Self) -> *const T {
+        let ptr = Self::as_ptr(&this);
+        mem::forget(this);
+        ptr
+    }
+}
+
+impl<T, A: Allocator> Arc<T, A> {
+    pub fn new_in(data: T, alloc: A) -> Self {
+        unsafe { Self::new_uninit_in(alloc).assume_init_with(data) }
+    }
+
+    pub fn new_uninit_in(alloc: A) -> Arc<mem::MaybeUninit<T>, A> {
+        unsafe {
+            let ptr = Arc::<mem::MaybeUninit<T>>::allocate_for_layout(
+                Layout::new::<T>(),
+                |layout| alloc.allocate(layout),
+                <*mut u8>::cast,
+            );
+
+            Arc(ptr, alloc)
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` must come from [`Arc::into_raw`] on an `Arc` allocated by `alloc`.
+    pub unsafe fn from_raw_in(ptr: *const T, alloc: A) -> Self {
+        unsafe {
+            let data_ptr = ptr as *const u8;
+            let inner_ptr = data_ptr.sub(offset_of!(ArcInner<T>, data)) as *mut ArcInner<T>;
+            Self(inner_ptr, alloc)
+        }
     }
 
     pub fn as_ptr(this: &Self) -> *const T {
-        this.deref() as *const T
+        unsafe { &raw const (*this.0).data }
+    }
+
+    /// Returns the value if `this` is the only strong reference; outstanding
+    /// `Weak`s can no longer upgrade afterwards.
+    pub fn try_unwrap(this: Self) -> Result<T, Self> {
+        let inner = unsafe { Counts::of(this.0) };
+
+        if inner.strong.compare_exchange(1, 0, Ordering::Relaxed, Ordering::Relaxed).is_err() {
+            return Err(this);
+        }
+
+        atomic::fence(Ordering::Acquire);
+
+        let (ptr, alloc) = Self::into_parts(this);
+
+        unsafe {
+            let data = ptr::read(&(*ptr).data);
+            drop(Weak(ptr, alloc));
+            Ok(data)
+        }
+    }
+
+    /// Like [`Arc::try_unwrap`], but never gives the `Arc` back, so exactly one of
+    /// several racing callers gets the value.
+    pub fn into_inner(this: Self) -> Option<T> {
+        let (ptr, alloc) = Self::into_parts(this);
+
+        if unsafe { (*ptr).strong.fetch_sub(1, Ordering::Release) } != 1 {
+            return None;
+        }
+
+        atomic::fence(Ordering::Acquire);
+
+        unsafe {
+            let data = ptr::read(&(*ptr).data);
+            drop(Weak(ptr, alloc));
+            Some(data)
+        }
     }
 }
 
-impl<T> Arc<mem::MaybeUninit<T>> {
-    pub unsafe fn assume_init(self) -> Arc<T> {
-        let inner = self.0;
-        core::mem::forget(self);
-        Arc(inner as *mut ArcInner<T>)
+impl<T: Clone, A: Allocator + Clone> Arc<T, A> {
+    /// Clone-on-write: clones the value only if other strong references exist.
+    /// Outstanding `Weak`s are disassociated instead of cloned for.
+    pub fn make_mut(this: &mut Self) -> &mut T {
+        let inner = unsafe { Counts::of(this.0) };
+
+        if inner.strong.compare_exchange(1, 0, Ordering::Acquire, Ordering::Relaxed).is_err() {
+            *this = Arc::new_in((**this).clone(), this.1.clone());
+        } else if inner.weak.load(Ordering::Relaxed) != 1 {
+            let old = this.0;
+            let fresh = Arc::<T, A>::new_uninit_in(this.1.clone());
+            unsafe {
+                let fresh = fresh.assume_init_with(ptr::read(&(*old).data));
+                let alloc = ptr::read(&this.1);
+                ptr::write(this, fresh);
+                drop(Weak(old, alloc));
+            }
+        } else {
+            inner.strong.store(1, Ordering::Release);
+        }
+
+        unsafe { Self::get_mut_unchecked(this) }
     }
 }
 
+impl<T, A: Allocator> Arc<mem::MaybeUninit<T>, A> {
+    /// # Safety
+    ///
+    /// The value must have been initialized.
+    pub unsafe fn assume_init(self) -> Arc<T, A> {
+        let (ptr, alloc) = Self::into_parts(self);
+        Arc(ptr as *mut ArcInner<T>, alloc)
+    }
 
-impl<T: ?Sized> Arc<T> {
-    pub fn clone(&self) -> Self {
+    unsafe fn assume_init_with(mut self, data: T) -> Arc<T, A> {
         unsafe {
-            (*self.0).count.fetch_add(1, Ordering::Relaxed);
+            Arc::get_mut_unchecked(&mut self).write(data);
+            self.assume_init()
         }
+    }
+}
 
-        Self(self.0)
+impl<T> Arc<[T]> {
+    pub fn new_uninit_slice(len: usize) -> Arc<[mem::MaybeUninit<T>]> {
+        unsafe { Arc(Arc::<[mem::MaybeUninit<T>]>::allocate_for_slice(len), Global) }
     }
 
-    pub unsafe fn get_mut_unchecked(this: &mut Self) -> &mut T {
-        unsafe { &mut (*this.0).data }
+    unsafe fn allocate_for_slice(len: usize) -> *mut ArcInner<[T]> {
+        unsafe {
+            Self::allocate_for_layout(
+                Layout::array::<T>(len).unwrap(),
+                |layout| Global.allocate(layout),
+                |mem| ptr::slice_from_raw_parts_mut(mem.cast::<T>(), len) as *mut ArcInner<[T]>,
+            )
+        }
     }
 
+    /// `iter` must yield exactly `len` items.
+    fn from_iter_exact(iter: impl Iterator<Item = T>, len: usize) -> Self {
+        /// Drops the already written prefix if `iter` panics.
+        struct Guard<'a, T> {
+            slots: &'a mut [mem::MaybeUninit<T>],
+            written: usize,
+        }
+
+        impl<T> Drop for Guard<'_, T> {
+            fn drop(&mut self) {
+                unsafe { self.slots[..self.written].assume_init_drop() };
+            }
+        }
+
+        let mut uninit = Arc::<[T]>::new_uninit_slice(len);
+        let mut guard = Guard {
+            slots: unsafe { Arc::get_mut_unchecked(&mut uninit) },
+            written: 0,
+        };
+
+        for item in iter.take(len) {
+            guard.slots[guard.written].write(item);
+            guard.written += 1;
+        }
+
+        assert_eq!(guard.written, len, "iterator yielded fewer items than expected");
+        mem::forget(guard);
+
+        unsafe { uninit.assume_init() }
+    }
+}
+
+impl<T> Arc<[mem::MaybeUninit<T>]> {
+    /// # Safety
+    ///
+    /// Every element must have been initialized.
+    pub unsafe fn assume_init(self) -> Arc<[T]> {
+        Arc(Arc::leak(self) as *mut ArcInner<[T]>, Global)
+    }
+}
+
+impl<T: ?Sized> Arc<T> {
     unsafe fn initialize_arcinner(
         ptr: NonNull<[u8]>,
         layout: Layout,
@@ -117,7 +284,8 @@ impl<T: ?Sized> Arc<T> {
         let inner = mem_to_arcinner(ptr.as_non_null_ptr().as_ptr());
 
         unsafe {
-            (&raw mut (*inner).count).write(AtomicUsize::new(1));
+            (&raw mut (*inner).strong).write(AtomicUsize::new(1));
+            (&raw mut (*inner).weak).write(AtomicUsize::new(1));
         }
 
         inner
@@ -130,27 +298,79 @@ impl<T: ?Sized> Arc<T> {
     ) -> *mut ArcInner<T> {
         let layout = Layout::new::<ArcInner<()>>().extend(value_layout).unwrap().0.pad_to_align();
 
-        let ptr = allocate(layout).unwrap_or_else(|_| panic!("allocation failed"));
+        let ptr = allocate(layout).unwrap_or_else(|_| alloc::alloc::handle_alloc_error(layout));
 
         unsafe { Self::initialize_arcinner(ptr, layout, mem_to_arcinner) }
     }
+}
+
+impl<T: ?Sized, A: Allocator + Clone> Arc<T, A> {
+    pub fn downgrade(this: &Self) -> Weak<T, A> {
+        let inner = unsafe { Counts::of(this.0) };
+        let mut current = inner.weak.load(Ordering::Relaxed);
+
+        loop {
+            if current == usize::MAX {
+                hint::spin_loop();
+                current = inner.weak.load(Ordering::Relaxed);
+                continue;
+            }
+
+            if current > MAX_REFCOUNT {
+                panic!("Arc counter overflow");
+            }
+
+            match inner.weak.compare_exchange_weak(current, current + 1, Ordering::Acquire, Ordering::Relaxed) {
+                Ok(_) => return Weak(this.0, this.1.clone()),
+                Err(old) => current = old,
+            }
+        }
+    }
+}
+
+impl<T: ?Sized, A: Allocator> Arc<T, A> {
+    pub fn allocator(this: &Self) -> &A {
+        &this.1
+    }
+
+    /// # Safety
+    ///
+    /// No other `Arc` or `Weak` may access the value while the returned borrow lives.
+    pub unsafe fn get_mut_unchecked(this: &mut Self) -> &mut T {
+        unsafe { &mut (*this.0).data }
+    }
 
-    // pub fn leak(&self) -> *mut T {
-    //     // Box::leak(Box::new(self.clone()))
-    //     let cloned = self.clone();
-    //     let ptr = cloned.deref() as *const T as *mut T;
-    //     core::mem::forget(cloned);
-    //     ptr
-    // }
+    /// Forgets `this` without touching the counts.
+    fn into_parts(this: Self) -> (*mut ArcInner<T>, A) {
+        let this = mem::ManuallyDrop::new(this);
+        (this.0, unsafe { ptr::read(&this.1) })
+    }
+
+    unsafe fn deallocate(ptr: *mut ArcInner<T>, alloc: &A) {
+        unsafe {
+            let layout = Layout::for_value_raw(ptr);
+            alloc.deallocate(NonNull::new_unchecked(ptr as *mut u8), layout);
+        }
+    }
 
     pub fn leak(this: Self) -> *mut ArcInner<T> {
         let ptr = this.0;
         core::mem::forget(this);
         ptr
     }
-    
-    pub fn strong_count(&self) -> usize {
-        unsafe { (*self.0).count.load(Ordering::Relaxed) }
+
+    pub fn strong_count(this: &Self) -> usize {
+        unsafe { (*this.0).strong.load(Ordering::Relaxed) }
+    }
+
+    pub fn weak_count(this: &Self) -> usize {
+        let count = unsafe { (*this.0).weak.load(Ordering::Relaxed) };
+        // usize::MAX means get_mut holds the weak count locked, so there are no Weaks.
+        if count == usize::MAX { 0 } else { count - 1 }
+    }
+
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        ptr::addr_eq(this.0, other.0)
     }
 
     pub fn into_raw_arcinner(this: Self) -> *mut ArcInner<T> {
@@ -159,43 +379,596 @@ impl<T: ?Sized> Arc<T> {
         ptr
     }
 
-    pub fn get_mut(&mut self) -> Option<&mut T> {
-        if self.strong_count() == 1 {
-            unsafe { Some(&mut (*self.0).data) }
+    /// `None` while other `Arc`s or `Weak`s point to the same allocation.
+    pub fn get_mut(this: &mut Self) -> Option<&mut T> {
+        if this.is_unique() {
+            unsafe { Some(Self::get_mut_unchecked(this)) }
         } else {
             None
         }
     }
+
+    /// Locks the weak count so no `Weak` can upgrade while the strong count is checked.
+    fn is_unique(&mut self) -> bool {
+        let inner = unsafe { Counts::of(self.0) };
+
+        if inner.weak.compare_exchange(1, usize::MAX, Ordering::Acquire, Ordering::Relaxed).is_err() {
+            return false;
+        }
+
+        let unique = inner.strong.load(Ordering::Acquire) == 1;
+        inner.weak.store(1, Ordering::Release);
+        unique
+    }
+
+    #[inline(never)]
+    unsafe fn drop_slow(&mut self) {
+        unsafe {
+            ptr::drop_in_place(&mut (*self.0).data);
+            drop(Weak(self.0, &self.1));
+        }
+    }
 }
 
-impl<T: ?Sized> Clone for Arc<T> {
+impl<T: ?Sized, A: Allocator + Clone> Clone for Arc<T, A> {
     fn clone(&self) -> Self {
         unsafe {
-            (*self.0).count.fetch_add(1, Ordering::Relaxed);
+            let old = (*self.0).strong.fetch_add(1, Ordering::Relaxed);
+            if old > MAX_REFCOUNT {
+                panic!("Arc counter overflow");
+            }
         }
-        Self(self.0)
+
+        Self(self.0, self.1.clone())
     }
 }
 
-impl<T: ?Sized> Deref for Arc<T> {
+impl<T: ?Sized, A: Allocator> Deref for Arc<T, A> {
     type Target = T;
-    
+
     fn deref(&self) -> &T {
         unsafe { &(*self.0).data }
     }
 }
 
-impl<T: ?Sized> Drop for Arc<T> {
+impl<T: ?Sized + fmt::Debug, A: Allocator> fmt::Debug for Arc<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: ?Sized + fmt::Display, A: Allocator> fmt::Display for Arc<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+impl<T> From<T> for Arc<T> {
+    fn from(value: T) -> Self {
+        Arc::new(value)
+    }
+}
+
+impl<T: Clone> From<&[T]> for Arc<[T]> {
+    fn from(slice: &[T]) -> Self {
+        Arc::from_iter_exact(slice.iter().cloned(), slice.len())
+    }
+}
+
+impl<T> From<Vec<T>> for Arc<[T]> {
+    fn from(mut vec: Vec<T>) -> Self {
+        let len = vec.len();
+
+        unsafe {
+            let inner = Arc::<[T]>::allocate_for_slice(len);
+            ptr::copy_nonoverlapping(vec.as_ptr(), &raw mut (*inner).data as *mut T, len);
+            // The elements moved into the Arc, only the buffer is freed.
+            vec.set_len(0);
+            Arc(inner, Global)
+        }
+    }
+}
+
+impl From<&str> for Arc<str> {
+    fn from(value: &str) -> Self {
+        let bytes = Arc::<[u8]>::from(value.as_bytes());
+        Arc(Arc::leak(bytes) as *mut ArcInner<str>, Global)
+    }
+}
+
+impl From<String> for Arc<str> {
+    fn from(value: String) -> Self {
+        Arc::from(value.as_str())
+    }
+}
+
+impl<T> FromIterator<T> for Arc<[T]> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let iter = iter.into_iter();
+
+        match iter.size_hint() {
+            (lower, Some(upper)) if lower == upper => Arc::from_iter_exact(iter, lower),
+            _ => Arc::from(iter.collect::<Vec<T>>()),
+        }
+    }
+}
+
+impl<T: ?Sized + Unsize<U>, U: ?Sized, A: Allocator> CoerceUnsized<Arc<U, A>> for Arc<T, A> {}
+
+impl<T: ?Sized, A: Allocator> Drop for Arc<T, A> {
     fn drop(&mut self) {
         unsafe {
-            if (*self.0).count.fetch_sub(1, Ordering::Release) != 1 {
+            if (*self.0).strong.fetch_sub(1, Ordering::Release) != 1 {
                 return;
             }
 
-            core::sync::atomic::fence(Ordering::Acquire);
-            
-            ptr::drop_in_place(&mut (*self.0).data);
-            drop(Box::from_raw(self.0));
+            atomic::fence(Ordering::Acquire);
+            self.drop_slow();
+        }
+    }
+}
+
+/// Non-owning reference to an [`Arc`] allocation, see [`Arc::downgrade`].
+pub struct Weak<T: ?Sized, A: Allocator = Global>(*mut ArcInner<T>, A);
+
+unsafe impl<T: Send + Sync + ?Sized, A: Allocator + Send> Send for Weak<T, A> {}
+unsafe impl<T: Send + Sync + ?Sized, A: Allocator + Sync> Sync for Weak<T, A> {}
+
+impl<T> Weak<T> {
+    /// Points to nothing, `upgrade` always returns `None`.
+    pub const fn new() -> Self {
+        Self(ptr::without_provenance_mut(usize::MAX), Global)
+    }
+}
+
+impl<T> Default for Weak<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: ?Sized, A: Allocator + Clone> Weak<T, A> {
+    pub fn upgrade(&self) -> Option<Arc<T, A>> {
+        let inner = self.inner()?;
+        let mut current = inner.strong.load(Ordering::Relaxed);
+
+        loop {
+            if current == 0 {
+                return None;
+            }
+
+            if current > MAX_REFCOUNT {
+                panic!("Arc counter overflow");
+            }
+
+            match inner.strong.compare_exchange_weak(current, current + 1, Ordering::Acquire, Ordering::Relaxed) {
+                Ok(_) => return Some(Arc(self.0, self.1.clone())),
+                Err(old) => current = old,
+            }
+        }
+    }
+}
+
+impl<T: ?Sized, A: Allocator> Weak<T, A> {
+    fn inner(&self) -> Option<Counts<'_>> {
+        if self.is_dangling() {
+            None
+        } else {
+            Some(unsafe { Counts::of(self.0) })
+        }
+    }
+
+    fn is_dangling(&self) -> bool {
+        self.0 as *mut () as usize == usize::MAX
+    }
+
+    pub fn allocator(&self) -> &A {
+        &self.1
+    }
+
+    pub fn strong_count(&self) -> usize {
+        self.inner().map_or(0, |inner| inner.strong.load(Ordering::Relaxed))
+    }
+
+    /// Zero once all strong references are gone, matching std.
+    pub fn weak_count(&self) -> usize {
+        let Some(inner) = self.inner() else {
+            return 0;
+        };
+
+        let weak = inner.weak.load(Ordering::Acquire);
+        let strong = inner.strong.load(Ordering::Relaxed);
+
+        if strong == 0 { 0 } else { weak - 1 }
+    }
+
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        ptr::addr_eq(self.0, other.0)
+    }
+}
+
+impl<T: ?Sized, A: Allocator + Clone> Clone for Weak<T, A> {
+    fn clone(&self) -> Self {
+        if let Some(inner) = self.inner() {
+            let old = inner.weak.fetch_add(1, Ordering::Relaxed);
+            if old > MAX_REFCOUNT {
+                panic!("Arc counter overflow");
+            }
+        }
+
+        Self(self.0, self.1.clone())
+    }
+}
+
+impl<T: ?Sized + Unsize<U>, U: ?Sized, A: Allocator> CoerceUnsized<Weak<U, A>> for Weak<T, A> {}
+
+impl<T: ?Sized, A: Allocator> Drop for Weak<T, A> {
+    fn drop(&mut self) {
+        let Some(inner) = self.inner() else {
+            return;
+        };
+
+        if inner.weak.fetch_sub(1, Ordering::Release) == 1 {
+            atomic::fence(Ordering::Acquire);
+            unsafe { Arc::<T, A>::deallocate(self.0, &self.1) };
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use alloc::{format, string::ToString, vec};
+    use std::panic::{self, AssertUnwindSafe};
+
+    /// Counts its drops so tests can check nothing leaks or double-drops.
+    struct Tracked<'a>(u32, &'a AtomicUsize);
+
+    impl Clone for Tracked<'_> {
+        fn clone(&self) -> Self {
+            Tracked(self.0, self.1)
+        }
+    }
+
+    impl Drop for Tracked<'_> {
+        fn drop(&mut self) {
+            self.1.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Counts the allocations it has handed out and not yet freed.
+    #[derive(Clone, Copy)]
+    struct Counting<'a>(&'a AtomicUsize);
+
+    unsafe impl Allocator for Counting<'_> {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            Global.allocate(layout)
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            self.0.fetch_sub(1, Ordering::Relaxed);
+            unsafe { Global.deallocate(ptr, layout) };
+        }
+    }
+
+    fn load(counter: &AtomicUsize) -> usize {
+        counter.load(Ordering::Relaxed)
+    }
+
+    /// Reports an exact size hint of `len` but yields only `yields` items.
+    struct Short {
+        next: u32,
+        yields: u32,
+        len: usize,
+    }
+
+    impl Iterator for Short {
+        type Item = u32;
+
+        fn next(&mut self) -> Option<u32> {
+            (self.next < self.yields).then(|| {
+                self.next += 1;
+                self.next - 1
+            })
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (self.len, Some(self.len))
+        }
+    }
+
+    #[test]
+    fn from_slice_and_vec() {
+        let a: Arc<[u32]> = Arc::from(&[1, 2, 3][..]);
+        assert_eq!(&*a, &[1, 2, 3]);
+
+        let empty: Arc<[u64]> = Arc::from(&[][..]);
+        assert!(empty.is_empty());
+
+        let drops = AtomicUsize::new(0);
+        let v: Arc<[Tracked]> = Arc::from(vec![Tracked(1, &drops), Tracked(2, &drops)]);
+        assert_eq!(drops.load(Ordering::Relaxed), 0);
+        assert_eq!(v.iter().map(|t| t.0).sum::<u32>(), 3);
+        drop(v);
+        assert_eq!(drops.load(Ordering::Relaxed), 2);
+
+        let originals = [Tracked(5, &drops), Tracked(6, &drops)];
+        let cloned: Arc<[Tracked]> = Arc::from(&originals[..]);
+        let other = cloned.clone();
+        drop(cloned);
+        assert_eq!(drops.load(Ordering::Relaxed), 2);
+        drop(other);
+        assert_eq!(drops.load(Ordering::Relaxed), 4);
+    }
+
+    #[test]
+    fn from_str_and_string() {
+        let s: Arc<str> = Arc::from("hello");
+        let t: Arc<str> = Arc::from("wörld".to_string());
+        assert_eq!(&*s, "hello");
+        assert_eq!(&*t, "wörld");
+        assert_eq!(t.len(), 6);
+        assert_eq!(format!("{s} {t:?}"), "hello \"wörld\"");
+
+        let empty: Arc<str> = Arc::from("");
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn collect_exact_and_inexact() {
+        let exact: Arc<[u32]> = (0..4).collect();
+        assert_eq!(&*exact, &[0, 1, 2, 3]);
+
+        let filtered: Arc<[u32]> = (0..10).filter(|i| i % 3 == 0).collect();
+        assert_eq!(&*filtered, &[0, 3, 6, 9]);
+    }
+
+    #[test]
+    fn collect_short_iterator_drops_prefix() {
+        let drops = AtomicUsize::new(0);
+        let iter = Short { next: 0, yields: 2, len: 3 }.map(|i| Tracked(i, &drops));
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| iter.collect::<Arc<[Tracked]>>()));
+
+        assert!(result.is_err());
+        assert_eq!(drops.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn clone_panic_drops_prefix() {
+        struct Bomb<'a>(u32, &'a AtomicUsize);
+
+        impl Clone for Bomb<'_> {
+            fn clone(&self) -> Self {
+                assert_ne!(self.0, 2, "boom");
+                Bomb(self.0, self.1)
+            }
+        }
+
+        impl Drop for Bomb<'_> {
+            fn drop(&mut self) {
+                self.1.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let drops = AtomicUsize::new(0);
+        let source = [Bomb(0, &drops), Bomb(1, &drops), Bomb(2, &drops)];
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| Arc::<[Bomb]>::from(&source[..])));
+
+        assert!(result.is_err());
+        assert_eq!(drops.load(Ordering::Relaxed), 2);
+        drop(source);
+        assert_eq!(drops.load(Ordering::Relaxed), 5);
+    }
+
+    #[test]
+    fn uninit_slice() {
+        let mut u = Arc::<[u8]>::new_uninit_slice(3);
+        for (i, slot) in Arc::get_mut(&mut u).unwrap().iter_mut().enumerate() {
+            slot.write(i as u8 * 2);
+        }
+
+        let u = unsafe { u.assume_init() };
+        assert_eq!(&*u, &[0, 2, 4]);
+
+        let empty = unsafe { Arc::<[String]>::new_uninit_slice(0).assume_init() };
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn unsizing() {
+        let array: Arc<[u8; 2]> = Arc::new([7, 8]);
+        let weak = Arc::downgrade(&array);
+        let slice: Arc<[u8]> = array;
+        let weak_slice: Weak<[u8]> = weak;
+        assert_eq!(&*slice, &[7, 8]);
+        assert!(Arc::ptr_eq(&weak_slice.upgrade().unwrap(), &slice));
+
+        let drops = AtomicUsize::new(0);
+        let value: Arc<dyn Send + Sync> = Arc::new(Tracked(3, &drops));
+        let weak: Weak<dyn Send + Sync> = Arc::downgrade(&value);
+        assert_eq!(Arc::strong_count(&value), 1);
+        assert_eq!(Arc::weak_count(&value), 1);
+        drop(value);
+        assert_eq!(drops.load(Ordering::Relaxed), 1);
+        assert!(weak.upgrade().is_none());
+
+        let display: Arc<dyn fmt::Display> = Arc::new(5u8);
+        assert_eq!(format!("{display}"), "5");
+    }
+
+    #[test]
+    fn weak_outlives_the_value() {
+        let (drops, live) = (AtomicUsize::new(0), AtomicUsize::new(0));
+        let a = Arc::new_in(Tracked(1, &drops), Counting(&live));
+        let weak = Arc::downgrade(&a);
+        assert_eq!((Arc::strong_count(&a), Arc::weak_count(&a)), (1, 1));
+        assert_eq!((weak.strong_count(), weak.weak_count()), (1, 1));
+
+        let b = weak.upgrade().unwrap();
+        assert!(Arc::ptr_eq(&a, &b));
+        assert_eq!((Arc::strong_count(&a), Arc::weak_count(&a)), (2, 1));
+
+        drop(a);
+        assert_eq!(load(&drops), 0);
+        drop(b);
+        // The value is gone, the allocation stays for the Weak.
+        assert_eq!((load(&drops), load(&live)), (1, 1));
+        assert!(weak.upgrade().is_none());
+        assert_eq!((weak.strong_count(), weak.weak_count()), (0, 0));
+
+        let other = weak.clone();
+        drop(weak);
+        assert_eq!(load(&live), 1);
+        drop(other);
+        assert_eq!((load(&drops), load(&live)), (1, 0));
+    }
+
+    #[test]
+    fn dangling_weak() {
+        let weak: Weak<u32> = Weak::new();
+        assert!(weak.upgrade().is_none());
+        assert_eq!((weak.strong_count(), weak.weak_count()), (0, 0));
+        assert!(weak.clone().ptr_eq(&Weak::default()));
+    }
+
+    #[test]
+    fn new_cyclic() {
+        struct Node<'a> {
+            me: Weak<Node<'a>>,
+            value: Tracked<'a>,
+        }
+
+        let drops = AtomicUsize::new(0);
+        let node = Arc::new_cyclic(|me| {
+            assert!(me.upgrade().is_none());
+            assert_eq!(me.strong_count(), 0);
+            Node { me: me.clone(), value: Tracked(7, &drops) }
+        });
+
+        assert_eq!((Arc::strong_count(&node), Arc::weak_count(&node)), (1, 1));
+        assert!(Arc::ptr_eq(&node.me.upgrade().unwrap(), &node));
+        assert_eq!(node.value.0, 7);
+
+        drop(node);
+        assert_eq!(load(&drops), 1);
+    }
+
+    #[test]
+    fn make_mut_clones_when_shared() {
+        let (drops, live) = (AtomicUsize::new(0), AtomicUsize::new(0));
+        let mut a = Arc::new_in(Tracked(1, &drops), Counting(&live));
+        let b = a.clone();
+
+        Arc::make_mut(&mut a).0 = 2;
+        assert!(!Arc::ptr_eq(&a, &b));
+        assert_eq!(((*a).0, (*b).0), (2, 1));
+        assert_eq!((Arc::strong_count(&a), Arc::strong_count(&b)), (1, 1));
+        assert_eq!((load(&drops), load(&live)), (0, 2));
+
+        drop(b);
+        assert_eq!((load(&drops), load(&live)), (1, 1));
+        drop(a);
+        assert_eq!((load(&drops), load(&live)), (2, 0));
+    }
+
+    #[test]
+    fn make_mut_disassociates_weaks() {
+        let (drops, live) = (AtomicUsize::new(0), AtomicUsize::new(0));
+        let mut a = Arc::new_in(Tracked(1, &drops), Counting(&live));
+        let weak = Arc::downgrade(&a);
+
+        // The value moves to a fresh allocation without a clone or a drop.
+        Arc::make_mut(&mut a).0 = 2;
+        assert!(weak.upgrade().is_none());
+        assert_eq!(Arc::weak_count(&a), 0);
+        assert_eq!((load(&drops), load(&live)), (0, 2));
+
+        drop(weak);
+        assert_eq!(load(&live), 1);
+
+        // Unique and no Weaks: mutated in place.
+        let before = Arc::as_ptr(&a);
+        Arc::make_mut(&mut a).0 = 3;
+        assert_eq!(Arc::as_ptr(&a), before);
+        assert_eq!(((*a).0, load(&drops), load(&live)), (3, 0, 1));
+
+        drop(a);
+        assert_eq!((load(&drops), load(&live)), (1, 0));
+    }
+
+    #[test]
+    fn unwrap_with_other_owners() {
+        let (drops, live) = (AtomicUsize::new(0), AtomicUsize::new(0));
+        let a = Arc::new_in(Tracked(1, &drops), Counting(&live));
+        let b = a.clone();
+        let weak = Arc::downgrade(&a);
+
+        let a = Arc::try_unwrap(a).err().unwrap();
+        assert_eq!(Arc::strong_count(&a), 2);
+        assert!(Arc::into_inner(a).is_none());
+        assert_eq!((weak.strong_count(), load(&drops)), (1, 0));
+
+        let value = Arc::try_unwrap(b).ok().unwrap();
+        assert!(weak.upgrade().is_none());
+        assert_eq!((load(&drops), load(&live)), (0, 1));
+        drop(value);
+        assert_eq!(load(&drops), 1);
+        drop(weak);
+        assert_eq!(load(&live), 0);
+
+        let c = Arc::new_in(Tracked(2, &drops), Counting(&live));
+        let value = Arc::into_inner(c).unwrap();
+        assert_eq!((value.0, load(&drops), load(&live)), (2, 1, 0));
+    }
+
+    #[test]
+    fn get_mut_needs_unique_ownership() {
+        let mut a = Arc::new(5u32);
+        let weak = Arc::downgrade(&a);
+        assert!(Arc::get_mut(&mut a).is_none());
+        drop(weak);
+
+        let b = a.clone();
+        assert!(Arc::get_mut(&mut a).is_none());
+        drop(b);
+
+        *Arc::get_mut(&mut a).unwrap() += 1;
+        assert_eq!(*a, 6);
+    }
+
+    #[test]
+    fn custom_allocator() {
+        let (drops, live) = (AtomicUsize::new(0), AtomicUsize::new(0));
+
+        let mut uninit = Arc::<Tracked, _>::new_uninit_in(Counting(&live));
+        assert_eq!(load(&live), 1);
+        Arc::get_mut(&mut uninit).unwrap().write(Tracked(4, &drops));
+        let a = unsafe { uninit.assume_init() };
+        assert!(ptr::eq(Arc::allocator(&a).0, &live));
+
+        let b = a.clone();
+        let weak = Arc::downgrade(&b);
+        assert!(ptr::eq(weak.allocator().0, &live));
+        drop((a, b));
+        assert_eq!((load(&drops), load(&live)), (1, 1));
+
+        let upgraded = weak.clone().upgrade();
+        assert!(upgraded.is_none());
+        drop(weak);
+        assert_eq!(load(&live), 0);
+
+        let dynamic: Arc<dyn fmt::Debug, Counting> = Arc::new_in(9u64, Counting(&live));
+        let weak: Weak<dyn fmt::Debug, Counting> = Arc::downgrade(&dynamic);
+        assert_eq!(format!("{dynamic:?}"), "9");
+        assert_eq!(format!("{:?}", weak.upgrade().unwrap()), "9");
+        drop(dynamic);
+        assert_eq!(load(&live), 1);
+        drop(weak);
+        assert_eq!(load(&live), 0);
+    }
+}
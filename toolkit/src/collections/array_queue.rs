@@ -0,0 +1,246 @@
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::mem::MaybeUninit;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+#[repr(align(64))]
+struct CachePadded<T>(T);
+
+struct Slot<T> {
+    /// Equals the position for an empty slot and position + 1 for a full one;
+    /// bumped by `N` when the slot is released for the next lap.
+    sequence: AtomicUsize,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+/// Bounded lock-free MPMC queue (Dmitry Vyukov's sequence-counter ring buffer).
+/// Can live in a `static`, no allocation is involved.
+pub struct ArrayQueue<T, const N: usize> {
+    head: CachePadded<AtomicUsize>,
+    tail: CachePadded<AtomicUsize>,
+    slots: [Slot<T>; N],
+}
+
+unsafe impl<T: Send, const N: usize> Send for ArrayQueue<T, N> {}
+unsafe impl<T: Send, const N: usize> Sync for ArrayQueue<T, N> {}
+
+impl<T> Slot<T> {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const EMPTY: Self = Self {
+        sequence: AtomicUsize::new(0),
+        value: UnsafeCell::new(MaybeUninit::uninit()),
+    };
+}
+
+impl<T, const N: usize> ArrayQueue<T, N> {
+    /// With a single slot a full slot would look empty to the next lap.
+    const AT_LEAST_TWO: () = assert!(N >= 2, "ArrayQueue capacity must be at least 2");
+
+    pub const fn new() -> Self {
+        let () = Self::AT_LEAST_TWO;
+
+        let mut slots = [Slot::EMPTY; N];
+
+        let mut i = 0;
+        while i < N {
+            slots[i].sequence = AtomicUsize::new(i);
+            i += 1;
+        }
+
+        Self {
+            head: CachePadded(AtomicUsize::new(0)),
+            tail: CachePadded(AtomicUsize::new(0)),
+            slots,
+        }
+    }
+
+    /// Gives the value back when the queue is full.
+    pub fn push(&self, value: T) -> Result<(), T> {
+        let mut pos = self.tail.0.load(Ordering::Relaxed);
+
+        loop {
+            let slot = &self.slots[pos % N];
+            let sequence = slot.sequence.load(Ordering::Acquire);
+            let diff = sequence.wrapping_sub(pos) as isize;
+
+            if diff == 0 {
+                match self.tail.0.compare_exchange_weak(pos, pos.wrapping_add(1), Ordering::Relaxed, Ordering::Relaxed) {
+                    Ok(_) => {
+                        unsafe { (*slot.value.get()).write(value) };
+                        slot.sequence.store(pos.wrapping_add(1), Ordering::Release);
+                        return Ok(());
+                    }
+                    Err(current) => pos = current,
+                }
+            } else if diff < 0 {
+                return Err(value);
+            } else {
+                pos = self.tail.0.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    pub fn pop(&self) -> Option<T> {
+        let mut pos = self.head.0.load(Ordering::Relaxed);
+
+        loop {
+            let slot = &self.slots[pos % N];
+            let sequence = slot.sequence.load(Ordering::Acquire);
+            let diff = sequence.wrapping_sub(pos.wrapping_add(1)) as isize;
+
+            if diff == 0 {
+                match self.head.0.compare_exchange_weak(pos, pos.wrapping_add(1), Ordering::Relaxed, Ordering::Relaxed) {
+                    Ok(_) => {
+                        let value = unsafe { (*slot.value.get()).assume_init_read() };
+                        slot.sequence.store(pos.wrapping_add(N), Ordering::Release);
+                        return Some(value);
+                    }
+                    Err(current) => pos = current,
+                }
+            } else if diff < 0 {
+                return None;
+            } else {
+                pos = self.head.0.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Snapshot only, other threads may change it right after.
+    pub fn len(&self) -> usize {
+        loop {
+            let tail = self.tail.0.load(Ordering::SeqCst);
+            let head = self.head.0.load(Ordering::SeqCst);
+
+            if self.tail.0.load(Ordering::SeqCst) == tail {
+                return tail.wrapping_sub(head).min(N);
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len() == N
+    }
+
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+}
+
+impl<T, const N: usize> Default for ArrayQueue<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Drop for ArrayQueue<T, N> {
+    fn drop(&mut self) {
+        while self.pop().is_some() {}
+    }
+}
+
+impl<T, const N: usize> fmt::Debug for ArrayQueue<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArrayQueue")
+            .field("len", &self.len())
+            .field("capacity", &N)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use core::cell::Cell;
+    use core::sync::atomic::AtomicBool;
+
+    #[test]
+    fn fifo_until_full() {
+        let queue = ArrayQueue::<u32, 3>::new();
+        assert!(queue.is_empty());
+        assert_eq!(queue.pop(), None);
+
+        for lap in 0..4 {
+            for i in 0..3 {
+                queue.push(lap * 10 + i).unwrap();
+            }
+            assert!(queue.is_full());
+            assert_eq!(queue.push(99), Err(99));
+
+            for i in 0..3 {
+                assert_eq!(queue.pop(), Some(lap * 10 + i));
+            }
+            assert_eq!(queue.len(), 0);
+        }
+    }
+
+    #[test]
+    fn drops_remaining_values() {
+        struct Counted<'a>(&'a Cell<usize>);
+
+        impl Drop for Counted<'_> {
+            fn drop(&mut self) {
+                self.0.set(self.0.get() + 1);
+            }
+        }
+
+        let drops = Cell::new(0);
+        let queue = ArrayQueue::<Counted<'_>, 4>::new();
+        for _ in 0..3 {
+            assert!(queue.push(Counted(&drops)).is_ok());
+        }
+        drop(queue.pop());
+        assert_eq!(drops.get(), 1);
+
+        drop(queue);
+        assert_eq!(drops.get(), 3);
+    }
+
+    #[test]
+    fn mpmc_delivers_every_value_once() {
+        const PRODUCERS: usize = 4;
+        const CONSUMERS: usize = 4;
+        const PER_PRODUCER: usize = if cfg!(miri) { 50 } else { 10_000 };
+
+        let queue = ArrayQueue::<usize, 8>::new();
+        let seen: std::vec::Vec<AtomicBool> = (0..PRODUCERS * PER_PRODUCER).map(|_| AtomicBool::new(false)).collect();
+        let received = AtomicUsize::new(0);
+
+        std::thread::scope(|scope| {
+            for producer in 0..PRODUCERS {
+                let queue = &queue;
+                scope.spawn(move || {
+                    for i in 0..PER_PRODUCER {
+                        let mut value = producer * PER_PRODUCER + i;
+                        while let Err(back) = queue.push(value) {
+                            value = back;
+                            std::thread::yield_now();
+                        }
+                    }
+                });
+            }
+
+            for _ in 0..CONSUMERS {
+                scope.spawn(|| {
+                    while received.load(Ordering::Relaxed) < PRODUCERS * PER_PRODUCER {
+                        match queue.pop() {
+                            Some(value) => {
+                                assert!(!seen[value].swap(true, Ordering::Relaxed), "{} popped twice", value);
+                                received.fetch_add(1, Ordering::Relaxed);
+                            }
+                            None => std::thread::yield_now(),
+                        }
+                    }
+                });
+            }
+        });
+
+        assert!(queue.is_empty());
+        assert!(seen.iter().all(|flag| flag.load(Ordering::Relaxed)));
+    }
+}
@@ -0,0 +1,5 @@
+pub mod static_vec;
+pub mod array_queue;
+
+pub use static_vec::StaticVec;
+pub use array_queue::ArrayQueue;
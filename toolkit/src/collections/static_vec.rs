@@ -0,0 +1,551 @@
+use core::mem::MaybeUninit;
+use core::ops::{Bound, Deref, DerefMut, RangeBounds};
+use core::{fmt, ptr, slice};
+
+/// Fixed-capacity vector stored inline, `N` is the maximum number of elements.
+pub struct StaticVec<T, const N: usize> {
+    buf: [MaybeUninit<T>; N],
+    len: usize,
+}
+
+impl<T, const N: usize> StaticVec<T, N> {
+    pub const fn new() -> Self {
+        Self {
+            buf: [const { MaybeUninit::uninit() }; N],
+            len: 0,
+        }
+    }
+
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub const fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.buf.as_ptr() as *const T, self.len) }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.buf.as_mut_ptr() as *mut T, self.len) }
+    }
+
+    pub fn as_ptr(&self) -> *const T {
+        self.buf.as_ptr() as *const T
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.buf.as_mut_ptr() as *mut T
+    }
+
+    /// Gives the value back when the vector is full.
+    pub fn push(&mut self, value: T) -> Result<(), T> {
+        if self.len == N {
+            return Err(value);
+        }
+
+        self.buf[self.len].write(value);
+        self.len += 1;
+        Ok(())
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.len -= 1;
+        Some(unsafe { self.buf[self.len].assume_init_read() })
+    }
+
+    /// Panics if `index > len`, gives the value back when the vector is full.
+    pub fn insert(&mut self, index: usize, value: T) -> Result<(), T> {
+        assert!(index <= self.len, "insertion index {} out of bounds (len {})", index, self.len);
+
+        if self.len == N {
+            return Err(value);
+        }
+
+        unsafe {
+            let p = self.as_mut_ptr().add(index);
+            ptr::copy(p, p.add(1), self.len - index);
+            ptr::write(p, value);
+        }
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Panics if `index >= len`.
+    pub fn remove(&mut self, index: usize) -> T {
+        assert!(index < self.len, "removal index {} out of bounds (len {})", index, self.len);
+
+        unsafe {
+            let p = self.as_mut_ptr().add(index);
+            let value = ptr::read(p);
+            ptr::copy(p.add(1), p, self.len - index - 1);
+            self.len -= 1;
+            value
+        }
+    }
+
+    /// Panics if `index >= len`. Does not preserve ordering.
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        assert!(index < self.len, "swap_remove index {} out of bounds (len {})", index, self.len);
+
+        let last = self.len - 1;
+        self.as_mut_slice().swap(index, last);
+        self.pop().unwrap()
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        while self.len > len {
+            self.len -= 1;
+            unsafe { self.buf[self.len].assume_init_drop() };
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut f: F) {
+        self.retain_mut(|value| f(value));
+    }
+
+    pub fn retain_mut<F: FnMut(&mut T) -> bool>(&mut self, mut f: F) {
+        let len = self.len;
+        let mut deleted = 0;
+
+        for i in 0..len {
+            if !f(&mut self.as_mut_slice()[i]) {
+                deleted += 1;
+            } else if deleted > 0 {
+                self.as_mut_slice().swap(i - deleted, i);
+            }
+        }
+
+        self.truncate(len - deleted);
+    }
+
+    /// Removes `range` and yields its elements; the tail is shifted down when the iterator is dropped.
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> Drain<'_, T, N> {
+        let start = match range.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&n) => n + 1,
+            Bound::Excluded(&n) => n,
+            Bound::Unbounded => self.len,
+        };
+        assert!(start <= end && end <= self.len, "drain range {}..{} out of bounds (len {})", start, end, self.len);
+
+        let len = self.len;
+        self.len = start;
+
+        Drain {
+            vec: self,
+            next: start,
+            end,
+            tail_start: end,
+            tail_len: len - end,
+        }
+    }
+
+    /// Stable insertion sort, allocation-free. Use `sort_unstable` for larger vectors.
+    pub fn sort(&mut self)
+    where
+        T: Ord,
+    {
+        self.sort_by(T::cmp);
+    }
+
+    pub fn sort_by<F: FnMut(&T, &T) -> core::cmp::Ordering>(&mut self, mut compare: F) {
+        let items = self.as_mut_slice();
+        for i in 1..items.len() {
+            let mut j = i;
+            while j > 0 && compare(&items[j - 1], &items[j]).is_gt() {
+                items.swap(j - 1, j);
+                j -= 1;
+            }
+        }
+    }
+
+    pub fn sort_by_key<K: Ord, F: FnMut(&T) -> K>(&mut self, mut f: F) {
+        self.sort_by(|a, b| f(a).cmp(&f(b)));
+    }
+
+    /// Returns `false` without modifying the vector if `other` does not fit.
+    pub fn extend_from_slice(&mut self, other: &[T]) -> bool
+    where
+        T: Clone,
+    {
+        if other.len() > N - self.len {
+            return false;
+        }
+
+        for value in other {
+            self.buf[self.len].write(value.clone());
+            self.len += 1;
+        }
+        true
+    }
+}
+
+impl<T, const N: usize> Drop for StaticVec<T, N> {
+    fn drop(&mut self) {
+        unsafe { ptr::drop_in_place(self.as_mut_slice()) };
+    }
+}
+
+impl<T, const N: usize> Default for StaticVec<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Deref for StaticVec<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, const N: usize> DerefMut for StaticVec<T, N> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T, const N: usize> AsRef<[T]> for StaticVec<T, N> {
+    fn as_ref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for StaticVec<T, N> {
+    fn clone(&self) -> Self {
+        let mut vec = Self::new();
+        vec.extend_from_slice(self.as_slice());
+        vec
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for StaticVec<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq, const N: usize, const M: usize> PartialEq<StaticVec<T, M>> for StaticVec<T, N> {
+    fn eq(&self, other: &StaticVec<T, M>) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for StaticVec<T, N> {}
+
+impl<'a, T, const N: usize> IntoIterator for &'a StaticVec<T, N> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut StaticVec<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T, const N: usize> IntoIterator for StaticVec<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<T, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { vec: self, next: 0 }
+    }
+}
+
+pub struct IntoIter<T, const N: usize> {
+    vec: StaticVec<T, N>,
+    next: usize,
+}
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.next == self.vec.len {
+            return None;
+        }
+
+        let value = unsafe { self.vec.buf[self.next].assume_init_read() };
+        self.next += 1;
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.vec.len - self.next;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T, const N: usize> Drop for IntoIter<T, N> {
+    fn drop(&mut self) {
+        let remaining = &mut self.vec.buf[self.next..self.vec.len];
+        self.vec.len = 0;
+        for value in remaining {
+            unsafe { value.assume_init_drop() };
+        }
+    }
+}
+
+pub struct Drain<'a, T, const N: usize> {
+    vec: &'a mut StaticVec<T, N>,
+    next: usize,
+    end: usize,
+    tail_start: usize,
+    tail_len: usize,
+}
+
+impl<T, const N: usize> Iterator for Drain<'_, T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.next == self.end {
+            return None;
+        }
+
+        let value = unsafe { self.vec.buf[self.next].assume_init_read() };
+        self.next += 1;
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.next;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for Drain<'_, T, N> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.next == self.end {
+            return None;
+        }
+
+        self.end -= 1;
+        Some(unsafe { self.vec.buf[self.end].assume_init_read() })
+    }
+}
+
+impl<T, const N: usize> Drop for Drain<'_, T, N> {
+    fn drop(&mut self) {
+        for value in &mut self.vec.buf[self.next..self.end] {
+            unsafe { value.assume_init_drop() };
+        }
+
+        let start = self.vec.len;
+        unsafe {
+            let base = self.vec.as_mut_ptr();
+            ptr::copy(base.add(self.tail_start), base.add(start), self.tail_len);
+        }
+        self.vec.len = start + self.tail_len;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::Cell;
+
+    /// Counts its drops in a shared cell.
+    #[derive(Debug)]
+    struct Tracked<'a>(u32, &'a Cell<usize>);
+
+    impl Drop for Tracked<'_> {
+        fn drop(&mut self) {
+            self.1.set(self.1.get() + 1);
+        }
+    }
+
+    impl PartialEq<u32> for Tracked<'_> {
+        fn eq(&self, other: &u32) -> bool {
+            self.0 == *other
+        }
+    }
+
+    fn tracked<const N: usize>(drops: &Cell<usize>, values: impl IntoIterator<Item = u32>) -> StaticVec<Tracked<'_>, N> {
+        let mut vec = StaticVec::new();
+        for value in values {
+            vec.push(Tracked(value, drops)).unwrap();
+        }
+        vec
+    }
+
+    fn values<const N: usize>(vec: &StaticVec<Tracked<'_>, N>) -> StaticVec<u32, N> {
+        let mut out = StaticVec::new();
+        for item in vec {
+            out.push(item.0).unwrap();
+        }
+        out
+    }
+
+    #[test]
+    fn push_pop_until_full() {
+        let mut vec = StaticVec::<u8, 3>::new();
+        assert!(vec.is_empty());
+        for i in 0..3 {
+            vec.push(i).unwrap();
+        }
+        assert!(vec.is_full());
+        assert_eq!(vec.push(9), Err(9));
+        assert_eq!(vec.pop(), Some(2));
+        assert_eq!(vec.as_slice(), [0, 1]);
+    }
+
+    #[test]
+    fn insert_and_remove_at_the_ends() {
+        let mut vec = StaticVec::<u8, 4>::new();
+        vec.insert(0, 2).unwrap();
+        vec.insert(0, 1).unwrap();
+        vec.insert(2, 4).unwrap();
+        vec.insert(2, 3).unwrap();
+        assert_eq!(vec.as_slice(), [1, 2, 3, 4]);
+        assert_eq!(vec.insert(4, 5), Err(5));
+        assert_eq!(vec.insert(0, 5), Err(5));
+
+        assert_eq!(vec.remove(3), 4);
+        assert_eq!(vec.remove(0), 1);
+        assert_eq!(vec.as_slice(), [2, 3]);
+        assert_eq!(vec.swap_remove(0), 2);
+        assert_eq!(vec.remove(0), 3);
+        assert!(vec.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "insertion index 2 out of bounds")]
+    fn insert_past_the_end_panics() {
+        StaticVec::<u8, 4>::new().insert(2, 0).ok();
+    }
+
+    #[test]
+    #[should_panic(expected = "removal index 0 out of bounds")]
+    fn remove_from_empty_panics() {
+        StaticVec::<u8, 4>::new().remove(0);
+    }
+
+    #[test]
+    fn drain_partially_consumed() {
+        let drops = Cell::new(0);
+        let mut vec = tracked::<8>(&drops, 0..6);
+
+        let mut drain = vec.drain(1..4);
+        assert_eq!(drain.size_hint(), (3, Some(3)));
+        assert_eq!(drain.next().unwrap(), 1);
+        assert_eq!(drops.get(), 1);
+        drop(drain);
+
+        // 2 and 3 were never yielded and are dropped with the iterator; the tail moves down.
+        assert_eq!(drops.get(), 3);
+        assert_eq!(values(&vec).as_slice(), [0, 4, 5]);
+
+        drop(vec);
+        assert_eq!(drops.get(), 6);
+    }
+
+    #[test]
+    fn drain_from_both_ends() {
+        let mut vec: StaticVec<u8, 8> = StaticVec::new();
+        assert!(vec.extend_from_slice(&[0, 1, 2, 3, 4, 5]));
+
+        let mut drain = vec.drain(2..=4);
+        assert_eq!(drain.next_back(), Some(4));
+        assert_eq!(drain.next(), Some(2));
+        assert_eq!(drain.next_back(), Some(3));
+        assert_eq!(drain.next(), None);
+        drop(drain);
+        assert_eq!(vec.as_slice(), [0, 1, 5]);
+
+        assert_eq!(vec.drain(..).count(), 3);
+        assert!(vec.is_empty());
+        assert_eq!(vec.drain(0..0).count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "drain range 2..5 out of bounds")]
+    fn drain_out_of_bounds_panics() {
+        let mut vec: StaticVec<u8, 8> = StaticVec::new();
+        assert!(vec.extend_from_slice(&[0, 1, 2]));
+        vec.drain(2..5);
+    }
+
+    #[test]
+    fn retain_mut_keeps_order_and_drops_rejects() {
+        let drops = Cell::new(0);
+        let mut vec = tracked::<8>(&drops, 0..8);
+
+        vec.retain_mut(|item| {
+            item.0 *= 10;
+            item.0 % 20 != 0
+        });
+
+        assert_eq!(drops.get(), 4);
+        assert_eq!(values(&vec).as_slice(), [10, 30, 50, 70]);
+
+        vec.retain(|_| false);
+        assert_eq!(drops.get(), 8);
+        assert!(vec.is_empty());
+    }
+
+    #[test]
+    fn into_iter_drops_the_rest() {
+        let drops = Cell::new(0);
+        let vec = tracked::<4>(&drops, 0..4);
+
+        let mut iter = vec.into_iter();
+        assert_eq!(iter.size_hint(), (4, Some(4)));
+        let first = iter.next().unwrap();
+        assert_eq!(first, 0);
+        drop(iter);
+        assert_eq!(drops.get(), 3);
+
+        drop(first);
+        assert_eq!(drops.get(), 4);
+    }
+
+    #[test]
+    fn truncate_clone_and_sort() {
+        let mut vec: StaticVec<i32, 8> = StaticVec::new();
+        assert!(vec.extend_from_slice(&[5, -1, 3, 3, 0]));
+        assert!(!vec.extend_from_slice(&[1, 2, 3, 4]));
+        assert_eq!(vec.len(), 5);
+
+        let copy = vec.clone();
+        vec.sort();
+        assert_eq!(vec.as_slice(), [-1, 0, 3, 3, 5]);
+        vec.sort_by_key(|value| value.abs());
+        assert_eq!(vec.as_slice(), [0, -1, 3, 3, 5]);
+
+        vec.truncate(2);
+        assert_eq!(vec.as_slice(), [0, -1]);
+        assert_eq!(copy.as_slice(), [5, -1, 3, 3, 0]);
+    }
+}
@@ -0,0 +1,52 @@
+use core::fmt::{self, Write};
+
+use crate::sys;
+
+/// Platform-neutral standard output used by `print!` and `println!`.
+pub struct Console;
+
+impl Console {
+    pub const NEWLINE: &'static str = sys::console::NEWLINE;
+
+    pub fn write(text: &str) -> Result<usize, i32> {
+        if text.is_empty() {
+            return Ok(0);
+        }
+        sys::console::write(text)
+    }
+
+    pub fn writeln(text: &str) -> Result<usize, i32> {
+        let written = Self::write(text)?;
+        let newline_written = Self::write(Self::NEWLINE)?;
+        Ok(written + newline_written)
+    }
+}
+
+impl Write for Console {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        Self::write(s).map_err(|_| fmt::Error)?;
+        Ok(())
+    }
+}
+
+#[macro_export]
+macro_rules! print {
+    ($($arg:tt)*) => {{
+        use core::fmt::Write;
+        let mut console = $crate::Console;
+        let _ = core::fmt::Write::write_fmt(&mut console, core::format_args!($($arg)*));
+    }};
+}
+
+#[macro_export]
+macro_rules! println {
+    () => {
+        $crate::print!("{}", $crate::Console::NEWLINE)
+    };
+    ($($arg:tt)*) => {{
+        use core::fmt::Write;
+        let mut console = $crate::Console;
+        let _ = core::fmt::Write::write_fmt(&mut console, core::format_args!($($arg)*));
+        let _ = core::fmt::Write::write_str(&mut console, $crate::Console::NEWLINE);
+    }};
+}
@@ -1,5 +1,13 @@
+#[cfg(windows)]
 use winapi::shared::ntdef::NTSTATUS;
 
+use crate::Status;
+
+/// Raw OS status code: an NTSTATUS on Windows, an errno value on Linux.
+#[cfg(not(windows))]
+#[allow(clippy::upper_case_acronyms)]
+type NTSTATUS = i32;
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ThreadError {
     /// The thread creation failed with the given NTSTATUS
@@ -27,13 +35,13 @@ impl core::fmt::Display for ThreadError {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             ThreadError::CreationFailed(status) => {
-                write!(f, "Thread creation failed with NTSTATUS: 0x{:08X}", status)
+                write!(f, "Thread creation failed: {}", Status::from_os(*status))
             }
             ThreadError::InvalidHandle => {
                 write!(f, "Thread handle is invalid or null")
             }
             ThreadError::WaitFailed(status) => {
-                write!(f, "Failed to wait for thread with NTSTATUS: 0x{:08X}", status)
+                write!(f, "Failed to wait for thread: {}", Status::from_os(*status))
             }
             ThreadError::NoResult => {
                 write!(f, "Thread exited but did not return a result")
@@ -53,11 +61,234 @@ impl core::fmt::Display for ThreadError {
             ThreadError::InvalidArgument => {
                 write!(f, "Invalid argument provided")
             }
-            ThreadError::QueryFailed(_) => write!(f, "Query failed"),
+            ThreadError::QueryFailed(status) => write!(f, "Query failed: {}", Status::from_os(*status)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DistributionError {
+    /// Probability outside `0.0..=1.0` or NaN
+    InvalidProbability,
+    /// A scale or rate parameter that is negative, zero where not allowed, or not finite
+    InvalidParameter,
+    /// `low >= high` for a half-open range, `low > high` for an inclusive one
+    EmptyRange,
+    /// More weights than the fixed capacity
+    TooManyWeights,
+    /// A weight that is negative or not finite
+    InvalidWeight,
+    /// No weights, or all of them zero
+    AllWeightsZero,
+}
+
+impl core::error::Error for DistributionError {}
+
+impl core::fmt::Display for DistributionError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let msg = match self {
+            Self::InvalidProbability => "Probability must be in 0..=1",
+            Self::InvalidParameter => "Invalid distribution parameter",
+            Self::EmptyRange => "Empty range",
+            Self::TooManyWeights => "Too many weights",
+            Self::InvalidWeight => "Weight is negative or not finite",
+            Self::AllWeightsZero => "All weights are zero",
+        };
+        write!(f, "{}", msg)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntropyError {
+    /// The OS generator failed with the given NTSTATUS/errno
+    Os(NTSTATUS),
+    /// The CPU does not implement the instruction
+    Unsupported,
+    /// The instruction kept reporting an empty entropy pool
+    Exhausted,
+}
+
+impl core::error::Error for EntropyError {}
+
+impl core::fmt::Display for EntropyError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Os(status) => write!(f, "OS entropy source failed: {}", Status::from_os(*status)),
+            Self::Unsupported => write!(f, "Entropy instruction is not supported by this CPU"),
+            Self::Exhausted => write!(f, "Entropy instruction did not return data"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessError {
+    /// The thread is running or has run its thread-local destructors
+    Destroyed,
+    /// Every one of the `MAX_KEYS` keys is taken
+    KeysExhausted,
+    /// No OS slot was left to hold the thread's values
+    NoSlot,
+}
+
+impl core::error::Error for AccessError {}
+
+impl core::fmt::Display for AccessError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let msg = match self {
+            Self::Destroyed => "Thread-local values were already destroyed",
+            Self::KeysExhausted => "No thread-local keys left",
+            Self::NoSlot => "No TLS slot left for thread-local values",
+        };
+        write!(f, "{}", msg)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeError {
+    /// A header or table runs past the end of the buffer
+    Truncated,
+    /// No `MZ` at the start
+    BadDosSignature,
+    /// No `PE\0\0` at `e_lfanew`
+    BadNtSignature,
+    /// Optional header is neither PE32 nor PE32+
+    UnsupportedFormat,
+    /// The image has no export directory
+    NoExports,
+    /// An RVA points outside every section
+    BadRva,
+    /// An export name is not valid UTF-8
+    BadName,
+}
+
+impl core::error::Error for PeError {}
+
+impl core::fmt::Display for PeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let msg = match self {
+            Self::Truncated => "PE image is truncated",
+            Self::BadDosSignature => "Missing MZ signature",
+            Self::BadNtSignature => "Missing PE signature",
+            Self::UnsupportedFormat => "Unsupported optional header format",
+            Self::NoExports => "PE image has no export directory",
+            Self::BadRva => "RVA outside of the image",
+            Self::BadName => "Export name is not valid UTF-8",
+        };
+        write!(f, "{}", msg)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyscallError {
+    /// The ntdll image could not be read
+    Image(PeError),
+    /// No `Zw*` export matches the name
+    NotExported,
+    /// The export does not start with `mov r10, rcx; mov eax, imm32`
+    NotAStub,
+}
+
+impl core::error::Error for SyscallError {}
+
+impl core::fmt::Display for SyscallError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Image(err) => write!(f, "Invalid ntdll image: {}", err),
+            Self::NotExported => write!(f, "Syscall is not exported"),
+            Self::NotAStub => write!(f, "Export is not an unpatched syscall stub"),
+        }
+    }
+}
+
+impl From<PeError> for SyscallError {
+    fn from(err: PeError) -> Self {
+        Self::Image(err)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateTimeError {
+    /// Month or day outside the calendar, or a year beyond `-9999..=9999`
+    InvalidDate,
+    /// Hour, minute, second or nanosecond out of range
+    InvalidTime,
+    /// UTC offset of a day or more
+    InvalidOffset,
+    /// Arithmetic or conversion left the supported range
+    OutOfRange,
+    /// Input did not match the RFC 3339 grammar
+    Parse,
+}
+
+impl core::error::Error for DateTimeError {}
+
+impl core::fmt::Display for DateTimeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let msg = match self {
+            Self::InvalidDate => "Invalid date",
+            Self::InvalidTime => "Invalid time of day",
+            Self::InvalidOffset => "Invalid UTC offset",
+            Self::OutOfRange => "Date-time out of range",
+            Self::Parse => "Malformed RFC 3339 date-time",
+        };
+        write!(f, "{}", msg)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatternError {
+    /// The signature has no bytes
+    Empty,
+    /// A token, or a mask character, at this index is not valid
+    InvalidToken(usize),
+    /// Mask and bytes differ in length
+    MaskLength,
+}
+
+impl core::error::Error for PatternError {}
+
+impl core::fmt::Display for PatternError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "Empty signature"),
+            Self::InvalidToken(index) => write!(f, "Invalid signature token at index {}", index),
+            Self::MaskLength => write!(f, "Mask length does not match the pattern"),
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryMapError {
+    /// A line that could not be parsed, 1-based
+    Syntax(usize),
+    /// The snapshot header is missing or of another version
+    UnsupportedVersion,
+    /// A region starting at this address overlaps the one before it
+    Overlap(usize),
+    /// Reading the OS memory map failed
+    Io(FileError),
+}
+
+impl core::error::Error for MemoryMapError {}
+
+impl core::fmt::Display for MemoryMapError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Syntax(line) => write!(f, "Malformed memory map on line {}", line),
+            Self::UnsupportedVersion => write!(f, "Unsupported memory map snapshot version"),
+            Self::Overlap(base) => write!(f, "Region at {:#x} overlaps the previous one", base),
+            Self::Io(err) => write!(f, "Failed to read the memory map: {}", err),
+        }
+    }
+}
+
+impl From<FileError> for MemoryMapError {
+    fn from(err: FileError) -> Self {
+        Self::Io(err)
+    }
+}
+
+#[repr(u32)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FileError {
     Success = 0,
@@ -77,6 +308,11 @@ pub enum FileError {
     UnexpectedEof = 0x99999991,
     Cancelled = 0x99999992,
     InvalidState = 0x99999993,
+    InvalidData = 0x99999995,
+    DiskFull = 0xC000007F,
+    DirectoryNotEmpty = 0xC0000101,
+    TooManyOpenFiles = 0xC000011F,
+    IoError = 0xC0000185,
 }
 
 impl core::error::Error for FileError{}
@@ -100,19 +336,54 @@ impl core::fmt::Display for FileError {
             Self::NameCollision => "Name coliision",
             Self::Cancelled => "Cancelled",
             Self::InvalidState => "Invalid state",
+            Self::InvalidData => "Invalid data",
+            Self::DiskFull => "Disk full",
+            Self::DirectoryNotEmpty => "Directory not empty",
+            Self::TooManyOpenFiles => "Too many open files",
+            Self::IoError => "I/O error",
             Self::Unspecified => "Unspecified error",
         };
         write!(f, "{}", msg)
     }
 }
 
+impl FileError {
+    /// The closest variant to a Linux errno; [`Status::from_errno`] keeps
+    /// errno values without one.
+    pub fn from_errno(errno: i32) -> Self {
+        match errno {
+            1 | 13 => Self::AccessDenied,
+            2 => Self::FileNotFound,
+            5 => Self::IoError,
+            16 | 26 => Self::SharingViolation,
+            17 => Self::NameCollision,
+            20 => Self::PathNotFound,
+            21 => Self::FileIsDirectory,
+            22 => Self::InvalidParameter,
+            24 => Self::TooManyOpenFiles,
+            28 => Self::DiskFull,
+            36 => Self::PathSyntaxBad,
+            39 => Self::DirectoryNotEmpty,
+            125 => Self::Cancelled,
+            _ => Self::Unspecified,
+        }
+    }
+}
+
+#[cfg(windows)]
 impl From<NTSTATUS> for FileError {
     fn from(status: NTSTATUS) -> Self {
-        match status as u32 {
+        Self::from(Status(status))
+    }
+}
+
+impl From<Status> for FileError {
+    fn from(status: Status) -> Self {
+        match status.code() as u32 {
             0 => Self::Success,
             0xC0000034 => Self::ObjectNameNotFound,
             0xC0000022 => Self::AccessDenied,
-            0xC00000CF => Self::FileIsDirectory,
+            0xC00000CF | 0xC00000BA => Self::FileIsDirectory,
             0xC000000D => Self::InvalidParameter,
             0xC000026E => Self::VolumeDismounted,
             0xC000000F => Self::FileNotFound,
@@ -122,9 +393,16 @@ impl From<NTSTATUS> for FileError {
             0xC0000011 => Self::EndOfFile,
             0xC000003B => Self::PathSyntaxBad,
             0xC0000035 => Self::NameCollision,
-            value => {
-                Self::Unspecified
-            },
+            0xC0000120 => Self::Cancelled,
+            0xC000003E => Self::InvalidData,
+            0xC000007F => Self::DiskFull,
+            0xC0000101 => Self::DirectoryNotEmpty,
+            0xC000011F => Self::TooManyOpenFiles,
+            0xC0000185 => Self::IoError,
+            0xC0000001 => Self::Unspecified,
+            // Known codes and wrapped errnos with an errno close to a variant.
+            _ if status.name().is_some() || status.is_customer() => Self::from_errno(status.to_errno()),
+            _ => Self::Unspecified,
         }
     }
-}
\ No newline at end of file
+}
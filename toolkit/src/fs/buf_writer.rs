@@ -1,7 +1,6 @@
 use core::{fmt, ptr};
 use crate::io::Write;
 use crate::error::FileError;
-use crate::println;
 
 pub const DEFAULT_BUF_SIZE: usize = 8192;
 
@@ -24,9 +23,7 @@ impl<W: Write> BufWriter<W> {
 
     pub fn flush_buf(&mut self) -> Result<(), FileError> {
         let mut written = 0;
-        println!("flush_buf {} {}", written, self.len);
         while written < self.len {
-            println!("written < self.len");
             self.panicked = true;
             let result = self.inner.write(&self.buf[written..self.len]);
             self.panicked = false;
@@ -65,14 +62,11 @@ impl<W: Write> BufWriter<W> {
     unsafe fn write_to_buffer_unchecked(&mut self, buf: &[u8]) {
         let dst = self.buf.as_mut_ptr().add(self.len);
         ptr::copy_nonoverlapping(buf.as_ptr(), dst, buf.len());
-        println!("buf.len() {}", buf.len());
         self.len += buf.len();
-        println!("len {}", self.len)
     }
 
     fn write_cold(&mut self, buf: &[u8]) -> Result<usize, FileError> {
         if buf.len() > self.spare_capacity() {
-            println!("write_cold");
             self.flush_buf()?;
         }
 
@@ -103,7 +97,6 @@ impl<W: Write> Write for BufWriter<W> {
     }
 
     fn write_all(&mut self, mut buf: &[u8]) -> Result<(), FileError> {
-
         if buf.len() < self.spare_capacity() {
             unsafe {
                 self.write_to_buffer_unchecked(buf);
@@ -117,10 +110,9 @@ impl<W: Write> Write for BufWriter<W> {
                     }
                     break;
                 }
-                
-                println!("!buf.is_empty() ");
+
                 self.flush_buf()?;
-                
+
                 if buf.len() >= self.buf.len() {
                     self.panicked = true;
                     let n = self.inner.write(buf)?;
@@ -144,7 +136,6 @@ impl<W: Write> Write for BufWriter<W> {
 impl<W: Write> Drop for BufWriter<W> {
     fn drop(&mut self) {
         if !self.panicked {
-            println!("drop");
             let _ = self.flush_buf();
         }
     }
@@ -1,15 +1,15 @@
 mod types;
-mod options;
 mod buf_writer;
-mod file;
 
 use core::ptr::null_mut;
 
 use heapless::Vec;
+#[cfg(windows)]
 use ntapi::ntrtl::{RtlDosPathNameToNtPathName_U, RtlFreeUnicodeString};
 pub use types::*;
 pub use buf_writer::*;
-pub use file::*;
+pub use crate::sys::fs::{File, FileOptions, rename};
+#[cfg(windows)]
 use winapi::shared::ntdef::UNICODE_STRING;
 
 use crate::{U16CStackString, error::FileError, io::{Read, Seek, Write}};
@@ -45,6 +45,7 @@ pub fn manual_copy(src: &str, dest: &str) -> Result<u64, FileError> {
     Ok(total)
 }
 
+#[cfg(windows)]
 pub fn canonicalize<const N: usize, const M: usize>(mut path: U16CStackString<N>) -> Option<U16CStackString<M>> {
     
     let mut nt_path: UNICODE_STRING = UNICODE_STRING {
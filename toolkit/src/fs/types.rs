@@ -1,5 +1,7 @@
 use core::fmt;
 
+use crate::time::DateTime;
+
 #[repr(C)]
 pub struct FileMetadata {
     pub size: FileSize,
@@ -45,58 +47,18 @@ impl fmt::Display for FileSize {
     }
 }
 
-fn is_leap_year(year: u64) -> bool {
-    (year % 4 == 0 && year % 100 != 0) || (year % 400 == 0)
-}
-
 impl fmt::Display for FileTime {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let time = self.0;
-        let seconds = time / 10_000_000;
-        const EPOCH_DIFF: u64 = 11644473600;
-        
-        let unix_time = if seconds >= EPOCH_DIFF {
-            seconds - EPOCH_DIFF
-        } else {
-            0
-        };
-        
-        let days = unix_time / 86400;
-        let seconds_in_day = unix_time % 86400;
-        
-        let hours = seconds_in_day / 3600;
-        let minutes = (seconds_in_day % 3600) / 60;
-        let secs = seconds_in_day % 60;
-        
-        let mut year = 1970;
-        let mut remaining_days = days;
-        let month_days = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
-        
-        loop {
-            let days_in_year = if is_leap_year(year) { 366 } else { 365 };
-            if remaining_days < days_in_year {
-                break;
-            }
-            remaining_days -= days_in_year;
-            year += 1;
-        }
-        
-        let mut month = 1;
-        for &days_in_month in month_days.iter() {
-            let days_in_month = if month == 2 && is_leap_year(year) { 29 } else { days_in_month };
-            if remaining_days < days_in_month {
-                break;
-            }
-            remaining_days -= days_in_month;
-            month += 1;
-        }
-        
-        let day = remaining_days + 1;
-        
+        let datetime = DateTime::from_filetime(self.0)
+            .ok()
+            .filter(|datetime| datetime.unix_timestamp() >= 0)
+            .unwrap_or(DateTime::UNIX_EPOCH);
+        let (date, time) = (datetime.date(), datetime.time());
+
         write!(
             f,
-            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
-            year, month, day, hours, minutes, secs
+            "{} {:02}:{:02}:{:02}",
+            date, time.hour(), time.minute(), time.second()
         )
     }
 }
@@ -4,16 +4,31 @@ use core::time::Duration;
 use core::sync::atomic::*;
 use core::{ffi::c_void, sync::atomic::AtomicU32};
 
-use winapi::um::synchapi::{WaitOnAddress, WakeByAddressSingle};
+use crate::sys::futex as imp;
 
 pub const INFINITE: u32 = 4294967295u32;
 
+/// A value that can be waited on through its atomic counterpart.
+///
+/// # Safety
+///
+/// `Futex` must be the atomic with the same size and layout as `Self`, and
+/// `Self` must be a size the platform backend can compare: 1, 2 or 4 bytes,
+/// and 8 on Windows.
 pub unsafe trait Waitable {
     type Futex;
 }
 
+/// An atomic whose address can be woken.
+///
+/// # Safety
+///
+/// Must be the `Futex` of a [`Waitable`], so a wake on its address reaches
+/// the waiters that compared it at that size.
 pub unsafe trait Futexable {}
 
+/// Blocks while `*address == compare`, until woken or `timeout` elapses.
+/// Returns `false` only when the wait timed out; spurious wake-ups return `true`.
 pub fn wait_on_address<W: Waitable>(
     address: &W::Futex,
     compare: W,
@@ -23,8 +38,7 @@ pub fn wait_on_address<W: Waitable>(
         let addr = core::ptr::from_ref(address).cast::<c_void>();
         let size = size_of::<W>();
         let compare_addr = (&raw const compare).cast::<c_void>();
-        let timeout = timeout.map(dur2timeout).unwrap_or(INFINITE);
-        WaitOnAddress(addr as _, compare_addr as _, size, timeout) == 1
+        imp::wait_on_address(addr, compare_addr, size, timeout)
     }
 }
 
@@ -44,30 +58,39 @@ unsafe_waitable_int! {
     (i8, AtomicI8),
     (i16, AtomicI16),
     (i32, AtomicI32),
-    (i64, AtomicI64),
-    (isize, AtomicIsize),
     (u8, AtomicU8),
     (u16, AtomicU16),
     (u32, AtomicU32),
+}
+
+// The Linux futex word is always 32 bits wide, so 64-bit values cannot be
+// waited on without losing wake-ups that only change the upper half.
+#[cfg(windows)]
+unsafe_waitable_int! {
+    (i64, AtomicI64),
+    (isize, AtomicIsize),
     (u64, AtomicU64),
     (usize, AtomicUsize),
 }
 
+#[cfg(windows)]
 unsafe impl<T> Waitable for *const T {
     type Futex = Atomic<*mut T>;
 }
 
+#[cfg(windows)]
 unsafe impl<T> Waitable for *mut T {
     type Futex = Atomic<*mut T>;
 }
 
+#[cfg(windows)]
 unsafe impl<T> Futexable for AtomicPtr<T> {}
 
 pub fn dur2timeout(dur: Duration) -> u32 {
     dur.as_secs()
         .checked_mul(1000)
         .and_then(|ms| ms.checked_add((dur.subsec_nanos() as u64) / 1_000_000))
-        .and_then(|ms| ms.checked_add(if dur.subsec_nanos() % 1_000_000 > 0 { 1 } else { 0 }))
+        .and_then(|ms| ms.checked_add(if dur.subsec_nanos().is_multiple_of(1_000_000) { 0 } else { 1 }))
         .map(|ms| if ms > <u32>::MAX as u64 { INFINITE } else { ms as u32 })
         .unwrap_or(INFINITE)
 }
@@ -75,6 +98,90 @@ pub fn dur2timeout(dur: Duration) -> u32 {
 pub fn wake_by_address_single<T: Futexable>(address: &T) {
     unsafe {
         let addr = core::ptr::from_ref(address).cast::<c_void>();
-        WakeByAddressSingle(addr as _);
+        imp::wake_by_address_single(addr, size_of::<T>());
+    }
+}
+
+pub fn wake_by_address_all<T: Futexable>(address: &T) {
+    unsafe {
+        let addr = core::ptr::from_ref(address).cast::<c_void>();
+        imp::wake_by_address_all(addr);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn wait_returns_on_mismatch_and_timeout() {
+        let word = AtomicU32::new(1);
+        assert!(wait_on_address(&word, 0u32, None));
+        assert!(!wait_on_address(&word, 1u32, Some(Duration::from_millis(10))));
+
+        let byte = AtomicU8::new(7);
+        assert!(!wait_on_address(&byte, 7u8, Some(Duration::from_millis(10))));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn narrow_values_are_woken() {
+        let flags = [AtomicU8::new(0), AtomicU8::new(0), AtomicU8::new(0), AtomicU8::new(0)];
+
+        thread::scope(|scope| {
+            for flag in &flags {
+                scope.spawn(move || {
+                    while flag.load(Ordering::Acquire) == 0 {
+                        wait_on_address(flag, 0u8, None);
+                    }
+                });
+            }
+
+            for flag in &flags {
+                thread::sleep(Duration::from_millis(5));
+                flag.store(1, Ordering::Release);
+                wake_by_address_single(flag);
+            }
+        });
+    }
+
+    #[test]
+    fn wake_single_wakes_one_waiter() {
+        // The value never changes, so only a wake ends a wait before its timeout.
+        let word = AtomicU32::new(0);
+        let (waiting, woken) = (AtomicUsize::new(0), AtomicUsize::new(0));
+
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    waiting.fetch_add(1, Ordering::SeqCst);
+                    if wait_on_address(&word, 0u32, Some(Duration::from_secs(10))) {
+                        woken.fetch_add(1, Ordering::SeqCst);
+                    }
+                });
+            }
+
+            while waiting.load(Ordering::SeqCst) < 4 {
+                thread::yield_now();
+            }
+            thread::sleep(Duration::from_millis(50));
+
+            wake_by_address_single(&word);
+            thread::sleep(Duration::from_millis(50));
+            assert_eq!(woken.load(Ordering::SeqCst), 1);
+
+            wake_by_address_all(&word);
+        });
+
+        assert_eq!(woken.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn timeout_rounds_up_to_milliseconds() {
+        assert_eq!(dur2timeout(Duration::from_millis(5)), 5);
+        assert_eq!(dur2timeout(Duration::from_micros(5001)), 6);
+        assert_eq!(dur2timeout(Duration::from_secs(u64::MAX)), INFINITE);
+    }
+}
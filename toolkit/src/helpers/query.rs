@@ -3,19 +3,7 @@ use core::fmt::{self, Display, Formatter};
 use ntapi::{ntmmapi::{MemoryBasicInformation, MemoryMappedFilenameInformation}, ntpebteb::PEB, ntpsapi::NtCurrentProcess, ntrtl::{HEAP_INFORMATION, RTL_USER_PROCESS_PARAMETERS}};
 use winapi::{ctypes::c_void, shared::ntdef::{HANDLE, LIST_ENTRY, NT_SUCCESS, NTSTATUS, PVOID, UNICODE_STRING}, um::winnt::{LARGE_INTEGER, MEMORY_BASIC_INFORMATION, PAGE_EXECUTE_READWRITE, RTL_RUN_ONCE}};
 
-use crate::{MemoryRegionIterator, U16CStackString, print, println, syscalls::{NtDelayExecution, NtQueryVirtualMemory}, types::{ByteBlock, HEAP}};
-
-pub struct Sleeper;
-
-impl Sleeper {
-    pub fn sleep(milliseconds: u32) {
-        let mut delay: LARGE_INTEGER = unsafe { core::mem::zeroed() };
-        unsafe {
-            *delay.QuadPart_mut() = -(milliseconds as i64) * 10_000;
-            NtDelayExecution(0, &mut delay);
-        }
-    }
-}
+use crate::{MemoryRegionIterator, U16CStackString, print, println, syscalls::NtQueryVirtualMemory, types::{ByteBlock, HEAP}};
 
 pub struct ProcessMemoryQuery;
 
@@ -50,6 +50,28 @@ impl ProcessMemoryReader {
         }
     }
 
+    /// Reads up to `buf.len()` bytes. A read crossing into an unreadable
+    /// page returns the bytes before it.
+    pub fn read_remote_into(handle: *mut c_void, address: PVOID, buf: &mut [u8]) -> Result<usize, NTSTATUS> {
+        let mut bytes_read: usize = 0;
+
+        let status = unsafe {
+            crate::syscalls::NtReadVirtualMemory(
+                handle,
+                address,
+                buf.as_mut_ptr() as _,
+                buf.len(),
+                &mut bytes_read,
+            )
+        };
+
+        if NT_SUCCESS(status) || bytes_read > 0 {
+            Ok(bytes_read)
+        } else {
+            Err(status)
+        }
+    }
+
     pub fn read_bytes_fixed<const N: usize>(address: PVOID) -> Result<ByteBlock<N>, NTSTATUS> {
         let handle = NtCurrentProcess;
         let mut buffer = ByteBlock::<N>::new();
@@ -0,0 +1,207 @@
+use crate::error::FileError;
+
+use super::{BufRead, Read};
+
+/// Reader adapter returned by [`Read::take`].
+pub struct Take<R> {
+    inner: R,
+    limit: u64,
+}
+
+impl<R> Take<R> {
+    pub(crate) fn new(inner: R, limit: u64) -> Self {
+        Self { inner, limit }
+    }
+
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    pub fn set_limit(&mut self, limit: u64) {
+        self.limit = limit;
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for Take<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, FileError> {
+        if self.limit == 0 {
+            return Ok(0);
+        }
+
+        let max = (buf.len() as u64).min(self.limit) as usize;
+        let n = match self.inner.read(&mut buf[..max]) {
+            Err(FileError::EndOfFile) => 0,
+            result => result?,
+        };
+        self.limit -= n as u64;
+        Ok(n)
+    }
+}
+
+impl<R: BufRead> BufRead for Take<R> {
+    fn fill_buf(&mut self) -> Result<&[u8], FileError> {
+        if self.limit == 0 {
+            return Ok(&[]);
+        }
+
+        let buf = self.inner.fill_buf()?;
+        let n = (buf.len() as u64).min(self.limit) as usize;
+        Ok(&buf[..n])
+    }
+
+    fn consume(&mut self, amount: usize) {
+        let amount = (amount as u64).min(self.limit) as usize;
+        self.limit -= amount as u64;
+        self.inner.consume(amount);
+    }
+}
+
+/// Reader adapter returned by [`Read::chain`].
+pub struct Chain<A, B> {
+    first: A,
+    second: B,
+    done_first: bool,
+}
+
+impl<A, B> Chain<A, B> {
+    pub(crate) fn new(first: A, second: B) -> Self {
+        Self {
+            first,
+            second,
+            done_first: false,
+        }
+    }
+
+    pub fn get_ref(&self) -> (&A, &B) {
+        (&self.first, &self.second)
+    }
+
+    pub fn get_mut(&mut self) -> (&mut A, &mut B) {
+        (&mut self.first, &mut self.second)
+    }
+
+    pub fn into_inner(self) -> (A, B) {
+        (self.first, self.second)
+    }
+}
+
+impl<A: Read, B: Read> Read for Chain<A, B> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, FileError> {
+        if !self.done_first {
+            match self.first.read(buf) {
+                Ok(0) | Err(FileError::EndOfFile) if !buf.is_empty() => self.done_first = true,
+                result => return result,
+            }
+        }
+
+        match self.second.read(buf) {
+            Err(FileError::EndOfFile) => Ok(0),
+            result => result,
+        }
+    }
+}
+
+impl<A: BufRead, B: BufRead> BufRead for Chain<A, B> {
+    fn fill_buf(&mut self) -> Result<&[u8], FileError> {
+        if !self.done_first {
+            match self.first.fill_buf()? {
+                [] => self.done_first = true,
+                buf => return Ok(buf),
+            }
+        }
+
+        self.second.fill_buf()
+    }
+
+    fn consume(&mut self, amount: usize) {
+        if !self.done_first {
+            self.first.consume(amount)
+        } else {
+            self.second.consume(amount)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use heapless::{String, Vec};
+
+    use super::*;
+    use crate::io::{BufReader, Cursor};
+
+    #[test]
+    fn take_limits_reads() {
+        let mut cursor = Cursor::new(b"0123456789".as_slice());
+        let mut take = cursor.by_ref().take(4);
+        let mut buf = [0u8; 3];
+
+        assert_eq!(take.read(&mut buf).unwrap(), 3);
+        assert_eq!(take.limit(), 1);
+        assert_eq!(take.read(&mut buf).unwrap(), 1);
+        assert_eq!(take.read(&mut buf).unwrap(), 0);
+
+        take.set_limit(2);
+        assert_eq!(take.read(&mut buf).unwrap(), 2);
+        assert_eq!(&buf[..2], b"45");
+        assert_eq!(cursor.position(), 6);
+    }
+
+    #[test]
+    fn take_limits_buffered_reads() {
+        let mut take = Cursor::new(b"line one\nline two\n".as_slice()).take(6);
+        assert_eq!(take.fill_buf().unwrap(), b"line o");
+
+        let mut line = String::<32>::new();
+        assert_eq!(take.read_line(&mut line).unwrap(), 6);
+        assert_eq!(line, "line o");
+        assert_eq!(take.limit(), 0);
+        assert!(take.fill_buf().unwrap().is_empty());
+        assert_eq!(take.into_inner().position(), 6);
+    }
+
+    #[test]
+    fn chain_reads_both() {
+        let mut chain = Cursor::new(b"ab".as_slice()).chain(Cursor::new(b"cde".as_slice()));
+        let mut buf = [0u8; 4];
+
+        // A read never spans both readers.
+        assert_eq!(chain.read(&mut buf).unwrap(), 2);
+        assert_eq!(chain.read(&mut buf).unwrap(), 3);
+        assert_eq!(&buf[..3], b"cde");
+        assert_eq!(chain.read(&mut buf).unwrap(), 0);
+        assert_eq!(chain.read(&mut []).unwrap(), 0);
+    }
+
+    #[test]
+    fn chain_buffered_lines_cross_the_seam() {
+        let first = BufReader::<_, 4>::new(Cursor::new(b"first\nsec".as_slice()));
+        let mut chain = first.chain(Cursor::new(b"ond\n".as_slice()));
+        let mut bytes = Vec::<u8, 16>::new();
+
+        assert_eq!(chain.read_until(b'\n', &mut bytes).unwrap(), 6);
+        assert_eq!(chain.read_until(b'\n', &mut bytes).unwrap(), 7);
+        assert_eq!(bytes, b"first\nsecond\n");
+        let (_, second) = chain.get_ref();
+        assert!(second.is_empty());
+    }
+
+    #[test]
+    fn empty_first_reader() {
+        let mut chain = Cursor::new(b"".as_slice()).chain(Cursor::new(b"x".as_slice()));
+        let mut buf = [0u8; 2];
+        assert_eq!(chain.read(&mut buf).unwrap(), 1);
+        assert_eq!(buf[0], b'x');
+    }
+}
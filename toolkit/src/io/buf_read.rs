@@ -0,0 +1,102 @@
+use heapless::{String, Vec};
+
+use crate::error::FileError;
+
+use super::Read;
+
+pub trait BufRead: Read {
+    fn fill_buf(&mut self) -> Result<&[u8], FileError>;
+
+    fn consume(&mut self, amount: usize);
+
+    fn has_data_left(&mut self) -> Result<bool, FileError> {
+        self.fill_buf().map(|buf| !buf.is_empty())
+    }
+
+    /// Appends bytes up to and including `delim`. Fails with `BufferOverflow`
+    /// once `buf` is full; the bytes that fit stay appended and consumed.
+    fn read_until<const N: usize>(&mut self, delim: u8, buf: &mut Vec<u8, N>) -> Result<usize, FileError> {
+        let mut total = 0;
+
+        loop {
+            let (done, used) = {
+                let available = self.fill_buf()?;
+                if available.is_empty() {
+                    return Ok(total);
+                }
+
+                let (done, wanted) = match available.iter().position(|&b| b == delim) {
+                    Some(i) => (true, i + 1),
+                    None => (false, available.len()),
+                };
+
+                let used = wanted.min(buf.capacity() - buf.len());
+                buf.extend_from_slice(&available[..used]).unwrap();
+
+                if used < wanted {
+                    self.consume(used);
+                    return Err(FileError::BufferOverflow);
+                }
+
+                (done, used)
+            };
+
+            self.consume(used);
+            total += used;
+
+            if done {
+                return Ok(total);
+            }
+        }
+    }
+
+    /// Appends a line including its `\n`. Invalid UTF-8 is rejected with
+    /// `InvalidData` and leaves `buf` as it was.
+    fn read_line<const N: usize>(&mut self, buf: &mut String<N>) -> Result<usize, FileError> {
+        let start = buf.len();
+        let bytes = unsafe { buf.as_mut_vec() };
+        let result = self.read_until(b'\n', bytes);
+
+        if core::str::from_utf8(&bytes[start..]).is_err() {
+            bytes.truncate(start);
+            return Err(FileError::InvalidData);
+        }
+
+        result
+    }
+
+    fn skip_until(&mut self, delim: u8) -> Result<usize, FileError> {
+        let mut total = 0;
+
+        loop {
+            let (done, used) = {
+                let available = self.fill_buf()?;
+                if available.is_empty() {
+                    return Ok(total);
+                }
+
+                match available.iter().position(|&b| b == delim) {
+                    Some(i) => (true, i + 1),
+                    None => (false, available.len()),
+                }
+            };
+
+            self.consume(used);
+            total += used;
+
+            if done {
+                return Ok(total);
+            }
+        }
+    }
+}
+
+impl<B: BufRead + ?Sized> BufRead for &mut B {
+    fn fill_buf(&mut self) -> Result<&[u8], FileError> {
+        (**self).fill_buf()
+    }
+
+    fn consume(&mut self, amount: usize) {
+        (**self).consume(amount)
+    }
+}
@@ -0,0 +1,233 @@
+use crate::error::FileError;
+use crate::fs::DEFAULT_BUF_SIZE;
+
+use super::{BufRead, Read, Seek, SeekFrom};
+
+pub struct BufReader<R: Read, const N: usize = DEFAULT_BUF_SIZE> {
+    inner: R,
+    buf: [u8; N],
+    pos: usize,
+    filled: usize,
+}
+
+impl<R: Read, const N: usize> BufReader<R, N> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            buf: [0u8; N],
+            pos: 0,
+            filled: 0,
+        }
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    pub fn buffer(&self) -> &[u8] {
+        &self.buf[self.pos..self.filled]
+    }
+
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    fn discard_buffer(&mut self) {
+        self.pos = 0;
+        self.filled = 0;
+    }
+}
+
+impl<R: Read, const N: usize> Read for BufReader<R, N> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, FileError> {
+        if self.pos == self.filled && buf.len() >= N {
+            self.discard_buffer();
+            return match self.inner.read(buf) {
+                Err(FileError::EndOfFile) => Ok(0),
+                result => result,
+            };
+        }
+
+        let available = self.fill_buf()?;
+        let n = available.len().min(buf.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl<R: Read, const N: usize> BufRead for BufReader<R, N> {
+    fn fill_buf(&mut self) -> Result<&[u8], FileError> {
+        if self.pos >= self.filled {
+            let n = match self.inner.read(&mut self.buf) {
+                Ok(n) => n,
+                Err(FileError::EndOfFile) => 0,
+                Err(err) => return Err(err),
+            };
+            self.pos = 0;
+            self.filled = n;
+        }
+
+        Ok(&self.buf[self.pos..self.filled])
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.pos = (self.pos + amount).min(self.filled);
+    }
+}
+
+impl<R: Read + Seek, const N: usize> Seek for BufReader<R, N> {
+    /// Any buffered data is discarded. `SeekFrom::Current` is relative to the
+    /// logical position, not to where the inner reader stopped.
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, FileError> {
+        let result = match pos {
+            SeekFrom::Current(offset) => {
+                let remainder = (self.filled - self.pos) as i64;
+                self.inner.seek(SeekFrom::Current(offset - remainder))?
+            }
+            pos => self.inner.seek(pos)?,
+        };
+
+        self.discard_buffer();
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use heapless::{String, Vec};
+
+    use super::*;
+    use crate::io::Cursor;
+
+    /// Hands out at most `chunk` bytes per read and ends with `EndOfFile`, as NT files do.
+    struct Trickle<'a> {
+        data: &'a [u8],
+        chunk: usize,
+        reads: usize,
+    }
+
+    impl<'a> Trickle<'a> {
+        fn new(data: &'a [u8], chunk: usize) -> Self {
+            Self { data, chunk, reads: 0 }
+        }
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize, FileError> {
+            self.reads += 1;
+            if self.data.is_empty() {
+                return Err(FileError::EndOfFile);
+            }
+
+            let n = self.data.len().min(buf.len()).min(self.chunk);
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn reads_through_the_buffer() {
+        let mut reader = BufReader::<_, 4>::new(Trickle::new(b"abcdefghij", 3));
+        let mut buf = [0u8; 2];
+
+        assert_eq!(reader.read(&mut buf).unwrap(), 2);
+        assert_eq!(&buf, b"ab");
+        assert_eq!(reader.buffer(), b"c");
+        assert_eq!(reader.get_ref().reads, 1);
+
+        // Large reads skip the buffer once it is drained.
+        assert_eq!(reader.read(&mut buf).unwrap(), 1);
+        let mut large = [0u8; 8];
+        assert_eq!(reader.read(&mut large).unwrap(), 3);
+        assert_eq!(&large[..3], b"def");
+
+        let mut rest = Vec::<u8, 16>::new();
+        assert_eq!(reader.read_to_end_fixed(&mut rest).unwrap(), 4);
+        assert_eq!(rest, b"ghij");
+        assert_eq!(reader.read(&mut buf).unwrap(), 0);
+    }
+
+    #[test]
+    fn read_until_and_lines() {
+        let mut reader = BufReader::<_, 4>::new(Trickle::new(b"one\ntwo words\n\nlast", 3));
+        let mut line = String::<32>::new();
+
+        assert_eq!(reader.read_line(&mut line).unwrap(), 4);
+        assert_eq!(line, "one\n");
+        assert_eq!(reader.read_line(&mut line).unwrap(), 10);
+        assert_eq!(line, "one\ntwo words\n");
+
+        line.clear();
+        assert_eq!(reader.read_line(&mut line).unwrap(), 1);
+        assert_eq!(reader.read_line(&mut line).unwrap(), 4);
+        assert_eq!(line, "\nlast");
+        assert_eq!(reader.read_line(&mut line).unwrap(), 0);
+        assert!(!reader.has_data_left().unwrap());
+    }
+
+    #[test]
+    fn read_until_overflow_keeps_what_fit() {
+        let mut reader = BufReader::<_, 8>::new(Cursor::new(b"abcdef;gh".as_slice()));
+        let mut small = Vec::<u8, 4>::new();
+
+        assert_eq!(reader.read_until(b';', &mut small), Err(FileError::BufferOverflow));
+        assert_eq!(small, b"abcd");
+
+        let mut rest = Vec::<u8, 8>::new();
+        assert_eq!(reader.read_until(b';', &mut rest).unwrap(), 3);
+        assert_eq!(rest, b"ef;");
+    }
+
+    #[test]
+    fn read_line_rejects_invalid_utf8() {
+        let mut reader = BufReader::<_, 8>::new(Cursor::new(b"ok\n\xff\xfe\nafter".as_slice()));
+        let mut line = String::<16>::new();
+
+        reader.read_line(&mut line).unwrap();
+        assert_eq!(reader.read_line(&mut line), Err(FileError::InvalidData));
+        assert_eq!(line, "ok\n");
+        assert_eq!(reader.read_line(&mut line).unwrap(), 5);
+        assert_eq!(line, "ok\nafter");
+    }
+
+    #[test]
+    fn skip_until_crosses_refills() {
+        let mut reader = BufReader::<_, 2>::new(Trickle::new(b"header;body", 1));
+        assert_eq!(reader.skip_until(b';').unwrap(), 7);
+
+        let mut rest = Vec::<u8, 8>::new();
+        reader.read_to_end_fixed(&mut rest).unwrap();
+        assert_eq!(rest, b"body");
+        assert_eq!(reader.skip_until(b';').unwrap(), 0);
+    }
+
+    #[test]
+    fn seek_discards_the_buffer() {
+        let mut reader = BufReader::<_, 4>::new(Cursor::new(b"0123456789".as_slice()));
+        let mut byte = [0u8; 1];
+
+        reader.read_exact(&mut byte).unwrap();
+        assert_eq!(reader.buffer(), b"123");
+
+        // Relative to the logical position 1, not to the inner cursor at 4.
+        assert_eq!(reader.seek(SeekFrom::Current(2)).unwrap(), 3);
+        assert!(reader.buffer().is_empty());
+        reader.read_exact(&mut byte).unwrap();
+        assert_eq!(&byte, b"3");
+
+        assert_eq!(reader.seek(SeekFrom::End(-1)).unwrap(), 9);
+        reader.read_exact(&mut byte).unwrap();
+        assert_eq!(&byte, b"9");
+        assert_eq!(reader.read_exact(&mut byte), Err(FileError::UnexpectedEof));
+    }
+}
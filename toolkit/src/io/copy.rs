@@ -0,0 +1,73 @@
+use crate::error::FileError;
+use crate::fs::DEFAULT_BUF_SIZE;
+
+use super::{Read, Write};
+
+pub fn copy<R: Read + ?Sized, W: Write + ?Sized>(reader: &mut R, writer: &mut W) -> Result<u64, FileError> {
+    copy_with_buffer::<DEFAULT_BUF_SIZE, R, W>(reader, writer)
+}
+
+/// Same as [`copy`] with an `N`-byte stack buffer.
+pub fn copy_with_buffer<const N: usize, R: Read + ?Sized, W: Write + ?Sized>(
+    reader: &mut R,
+    writer: &mut W,
+) -> Result<u64, FileError> {
+    let mut buf = [0u8; N];
+    let mut total = 0;
+
+    loop {
+        let n = match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(FileError::EndOfFile) => break,
+            Err(err) => return Err(err),
+        };
+
+        writer.write_all(&buf[..n])?;
+        total += n as u64;
+    }
+
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::Cursor;
+
+    /// Fails with `EndOfFile` instead of returning `Ok(0)`, as NT files do.
+    struct EofError<'a>(&'a [u8]);
+
+    impl Read for EofError<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize, FileError> {
+            if self.0.is_empty() {
+                return Err(FileError::EndOfFile);
+            }
+            let n = self.0.len().min(buf.len());
+            buf[..n].copy_from_slice(&self.0[..n]);
+            self.0 = &self.0[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn copies_in_chunks() {
+        let data = [7u8; 100];
+        let mut out = [0u8; 128];
+        let mut writer = Cursor::new(out.as_mut_slice());
+
+        assert_eq!(copy_with_buffer::<16, _, _>(&mut EofError(&data), &mut writer).unwrap(), 100);
+        assert_eq!(writer.position(), 100);
+        assert_eq!(copy(&mut Cursor::new(b"".as_slice()), &mut writer).unwrap(), 0);
+        assert!(out[..100].iter().all(|&b| b == 7));
+    }
+
+    #[test]
+    fn short_writer_overflows() {
+        let mut out = [0u8; 4];
+        let mut writer = Cursor::new(out.as_mut_slice());
+        let result = copy_with_buffer::<3, _, _>(&mut Cursor::new(b"abcdef".as_slice()), &mut writer);
+        assert_eq!(result, Err(FileError::BufferOverflow));
+        assert_eq!(&out, b"abcd");
+    }
+}
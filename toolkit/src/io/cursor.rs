@@ -0,0 +1,157 @@
+use crate::error::FileError;
+
+use super::{BufRead, Read, Seek, SeekFrom, Write};
+
+/// In-memory reader/writer over a byte slice, e.g. `Cursor<&[u8]>` or `Cursor<&mut [u8]>`.
+pub struct Cursor<T> {
+    inner: T,
+    pos: u64,
+}
+
+impl<T> Cursor<T> {
+    pub const fn new(inner: T) -> Self {
+        Self { inner, pos: 0 }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    pub const fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    pub const fn position(&self) -> u64 {
+        self.pos
+    }
+
+    pub fn set_position(&mut self, pos: u64) {
+        self.pos = pos;
+    }
+}
+
+impl<T: AsRef<[u8]>> Cursor<T> {
+    pub fn remaining_slice(&self) -> &[u8] {
+        let data = self.inner.as_ref();
+        let start = (self.pos as usize).min(data.len());
+        &data[start..]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.remaining_slice().is_empty()
+    }
+}
+
+impl<T: AsRef<[u8]>> Read for Cursor<T> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, FileError> {
+        let remaining = self.remaining_slice();
+        let n = remaining.len().min(buf.len());
+        buf[..n].copy_from_slice(&remaining[..n]);
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl<T: AsRef<[u8]>> BufRead for Cursor<T> {
+    fn fill_buf(&mut self) -> Result<&[u8], FileError> {
+        Ok(self.remaining_slice())
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.pos += amount as u64;
+    }
+}
+
+impl<T: AsRef<[u8]>> Seek for Cursor<T> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, FileError> {
+        let (base, offset) = match pos {
+            SeekFrom::Start(offset) => {
+                self.pos = offset;
+                return Ok(offset);
+            }
+            SeekFrom::End(offset) => (self.inner.as_ref().len() as u64, offset),
+            SeekFrom::Current(offset) => (self.pos, offset),
+        };
+
+        self.pos = base.checked_add_signed(offset).ok_or(FileError::InvalidParameter)?;
+        Ok(self.pos)
+    }
+}
+
+/// Writes past the end of the slice return `Ok(0)`, which `write_all`
+/// reports as `BufferOverflow`.
+impl Write for Cursor<&mut [u8]> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, FileError> {
+        let start = (self.pos as usize).min(self.inner.len());
+        let space = &mut self.inner[start..];
+        let n = space.len().min(buf.len());
+        space[..n].copy_from_slice(&buf[..n]);
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl<const N: usize> Write for Cursor<[u8; N]> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, FileError> {
+        let start = (self.pos as usize).min(N);
+        let space = &mut self.inner[start..];
+        let n = space.len().min(buf.len());
+        space[..n].copy_from_slice(&buf[..n]);
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_and_seeks() {
+        let mut cursor = Cursor::new(b"hello world".as_slice());
+        let mut buf = [0u8; 5];
+
+        cursor.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"hello");
+        assert_eq!(cursor.position(), 5);
+        assert_eq!(cursor.fill_buf().unwrap(), b" world");
+        cursor.consume(1);
+        assert_eq!(cursor.remaining_slice(), b"world");
+
+        assert_eq!(cursor.seek(SeekFrom::End(-3)).unwrap(), 8);
+        assert_eq!(cursor.read(&mut buf).unwrap(), 3);
+        assert!(cursor.is_empty());
+        assert_eq!(cursor.seek(SeekFrom::Current(-11)).unwrap(), 0);
+        assert_eq!(cursor.seek(SeekFrom::Current(-1)), Err(FileError::InvalidParameter));
+    }
+
+    #[test]
+    fn past_the_end() {
+        let mut cursor = Cursor::new([1u8, 2, 3]);
+        assert_eq!(cursor.seek(SeekFrom::Start(10)).unwrap(), 10);
+        assert_eq!(cursor.read(&mut [0u8; 4]).unwrap(), 0);
+        assert!(cursor.remaining_slice().is_empty());
+        assert_eq!(cursor.write(b"x").unwrap(), 0);
+        assert_eq!(cursor.into_inner(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn writes_into_slices_and_arrays() {
+        let mut storage = [0u8; 6];
+        let mut cursor = Cursor::new(storage.as_mut_slice());
+        cursor.write_all(b"abc").unwrap();
+        cursor.set_position(1);
+        cursor.write_all(b"XY").unwrap();
+        assert_eq!(cursor.write_all(b"long tail"), Err(FileError::BufferOverflow));
+        assert_eq!(cursor.position(), 6);
+        assert_eq!(&storage, b"aXYlon");
+
+        let mut cursor = Cursor::new([0u8; 4]);
+        assert_eq!(cursor.write(b"12345").unwrap(), 4);
+        assert_eq!(cursor.get_ref(), b"1234");
+    }
+}
@@ -1,7 +1,17 @@
 mod read;
 mod write;
 mod seek;
+mod buf_read;
+mod buf_reader;
+mod cursor;
+mod adapters;
+mod copy;
 
 pub use read::Read;
 pub use write::Write;
-pub use seek::{Seek, SeekFrom};
\ No newline at end of file
+pub use seek::{Seek, SeekFrom};
+pub use buf_read::BufRead;
+pub use buf_reader::BufReader;
+pub use cursor::Cursor;
+pub use adapters::{Chain, Take};
+pub use copy::{copy, copy_with_buffer};
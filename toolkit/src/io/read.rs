@@ -1,5 +1,7 @@
 use crate::error::FileError;
 
+use super::{Chain, Take};
+
 pub trait Read {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize, FileError>;
 
@@ -58,4 +60,31 @@ pub trait Read {
         }
         Ok(())
     }
-}
\ No newline at end of file
+
+    fn by_ref(&mut self) -> &mut Self
+    where
+        Self: Sized,
+    {
+        self
+    }
+
+    fn take(self, limit: u64) -> Take<Self>
+    where
+        Self: Sized,
+    {
+        Take::new(self, limit)
+    }
+
+    fn chain<R: Read>(self, next: R) -> Chain<Self, R>
+    where
+        Self: Sized,
+    {
+        Chain::new(self, next)
+    }
+}
+
+impl<R: Read + ?Sized> Read for &mut R {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, FileError> {
+        (**self).read(buf)
+    }
+}
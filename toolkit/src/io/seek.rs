@@ -8,4 +8,10 @@ pub enum SeekFrom {
 
 pub trait Seek {
     fn seek(&mut self, pos: SeekFrom) -> Result<u64, FileError>;
-}
\ No newline at end of file
+}
+
+impl<S: Seek + ?Sized> Seek for &mut S {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, FileError> {
+        (**self).seek(pos)
+    }
+}
@@ -13,4 +13,14 @@ pub trait Write {
         }
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+impl<W: Write + ?Sized> Write for &mut W {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, FileError> {
+        (**self).write(buf)
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), FileError> {
+        (**self).write_all(buf)
+    }
+}
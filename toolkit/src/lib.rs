@@ -15,21 +15,32 @@
 #![feature(slice_ptr_get)]
 #![feature(generic_atomic)]
 #![feature(utf16_extra)]
+#![feature(layout_for_ptr)]
+#![feature(coerce_unsized)]
+#![feature(linkage)]
 
 mod fs;
 mod io;
-mod time;
+pub mod time;
 mod error;
+mod status;
 pub mod syscalls;
+pub mod pe;
+mod console;
+#[cfg(windows)]
 mod nt_console;
 mod u16_stack_string;
 mod u8_stack_string;
+#[cfg(windows)]
 mod helpers;
+#[cfg(windows)]
 mod memory;
+#[cfg(windows)]
 mod ntdll;
 pub mod futex;
-mod mutex;
-mod static_vec;
+mod sync;
+pub mod collections;
+pub mod sys;
 pub mod types;
 pub mod stack_trait;
 pub mod rand;
@@ -40,21 +51,35 @@ pub mod logger;
 pub use fs::*;
 pub use io::*;
 pub use error::*;
+pub use status::*;
+pub use console::*;
+#[cfg(windows)]
 pub use nt_console::*;
 pub use u16_stack_string::*;
 pub use u8_stack_string::*;
+#[cfg(windows)]
 pub use helpers::*;
+#[cfg(windows)]
 pub use memory::*;
-pub use mutex::*;
+pub use sync::*;
+pub use collections::{ArrayQueue, StaticVec};
+#[cfg(windows)]
 pub use ntdll::*;
+pub use time::Sleeper;
 
 #[cfg(feature = "alloc")]
-mod thread;
+pub mod thread;
 
 #[cfg(feature = "alloc")]
 mod arc;
 
 #[cfg(feature = "alloc")]
+mod memory_map;
+
+#[cfg(feature = "alloc")]
+pub mod scan;
+
+#[cfg(all(feature = "alloc", windows))]
 pub mod hook;
 
 #[cfg(feature = "alloc")]
@@ -65,3 +90,6 @@ pub use thread::*;
 
 #[cfg(feature = "alloc")]
 pub use arc::*;
+
+#[cfg(feature = "alloc")]
+pub use memory_map::*;
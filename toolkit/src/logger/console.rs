@@ -0,0 +1,35 @@
+use log::{LevelFilter, Log, Metadata, Record};
+
+use crate::Console;
+use crate::logger::{LineBuffer, LogFormat};
+use crate::time::SystemTime;
+
+/// Writes each record to standard output, one line per record.
+pub struct ConsoleLogger {
+    level: LevelFilter,
+    format: LogFormat,
+}
+
+impl ConsoleLogger {
+    pub const fn new(level: LevelFilter, format: LogFormat) -> Self {
+        Self { level, format }
+    }
+}
+
+impl Log for ConsoleLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let mut line = LineBuffer::<1024>::new();
+        let _ = self.format.write(&mut line, record, &SystemTime::now());
+        let _ = Console::writeln(line.as_str());
+    }
+
+    fn flush(&self) {}
+}
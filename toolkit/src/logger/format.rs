@@ -0,0 +1,399 @@
+use core::fmt::{self, Write};
+use core::str;
+
+use log::kv::{self, Key, Value, VisitSource};
+use log::{Level, Record};
+
+use crate::time::SystemTime;
+
+/// Layout of one log line. Key-value pairs attached with `log`'s `key = value;`
+/// syntax are rendered by all three.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    /// `2025-01-01 12:00:00.000 [INFO] [target] [file:line] message key=value`
+    #[default]
+    Text,
+    /// `ts=2025-01-01T12:00:00.000Z level=info target=app msg="message" key=value`
+    Logfmt,
+    /// `{"ts":"2025-01-01T12:00:00.000Z","level":"info","target":"app","msg":"message","key":"value"}`
+    Json,
+}
+
+impl LogFormat {
+    /// Writes `record` without a trailing newline.
+    pub fn write<W: Write>(self, out: &mut W, record: &Record, time: &SystemTime) -> fmt::Result {
+        match self {
+            LogFormat::Text => write_text(out, record, time),
+            LogFormat::Logfmt => write_logfmt(out, record, time),
+            LogFormat::Json => write_json(out, record, time),
+        }
+    }
+}
+
+/// Fixed-size line buffer. Output past `N` bytes is cut at a char boundary
+/// instead of failing the record, so a cut JSON line is not valid JSON.
+pub struct LineBuffer<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+    truncated: bool,
+}
+
+impl<const N: usize> LineBuffer<N> {
+    pub const fn new() -> Self {
+        Self { buf: [0; N], len: 0, truncated: false }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+
+    pub fn as_str(&self) -> &str {
+        // Only whole chars are ever copied in.
+        unsafe { str::from_utf8_unchecked(self.as_bytes()) }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Appends `\n`, dropping the last char if the buffer is full.
+    pub fn finish_line(&mut self) -> &[u8] {
+        if self.len == N {
+            let mut len = N - 1;
+            while !self.as_str().is_char_boundary(len) {
+                len -= 1;
+            }
+            self.len = len;
+            self.truncated = true;
+        }
+
+        self.buf[self.len] = b'\n';
+        self.len += 1;
+        self.as_bytes()
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+        self.truncated = false;
+    }
+}
+
+impl<const N: usize> Default for LineBuffer<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Write for LineBuffer<N> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut take = s.len().min(N - self.len);
+        while !s.is_char_boundary(take) {
+            take -= 1;
+        }
+
+        self.buf[self.len..self.len + take].copy_from_slice(&s.as_bytes()[..take]);
+        self.len += take;
+        self.truncated |= take < s.len();
+        Ok(())
+    }
+}
+
+fn level_name(level: Level) -> &'static str {
+    match level {
+        Level::Error => "error",
+        Level::Warn => "warn",
+        Level::Info => "info",
+        Level::Debug => "debug",
+        Level::Trace => "trace",
+    }
+}
+
+/// RFC 3339 in UTC with millisecond precision.
+fn write_timestamp<W: Write>(out: &mut W, time: &SystemTime) -> fmt::Result {
+    let (year, month, day, hour, minute, second, millis) = time.to_date_components();
+    write!(
+        out,
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year, month, day, hour, minute, second, millis
+    )
+}
+
+fn write_text<W: Write>(out: &mut W, record: &Record, time: &SystemTime) -> fmt::Result {
+    write!(out, "{} [{}] [{}]", time, record.level(), record.target())?;
+
+    if let Some(file) = record.file() {
+        write!(out, " [{}", file)?;
+        if let Some(line) = record.line() {
+            write!(out, ":{}]", line)?;
+        } else {
+            write!(out, "]")?;
+        }
+    }
+
+    write!(out, " {}", record.args())?;
+    write_pairs(out, record, LogFormat::Logfmt)
+}
+
+fn write_logfmt<W: Write>(out: &mut W, record: &Record, time: &SystemTime) -> fmt::Result {
+    out.write_str("ts=")?;
+    write_timestamp(out, time)?;
+    write!(out, " level={} target=", level_name(record.level()))?;
+    logfmt_value(out, &record.target())?;
+
+    if let Some(file) = record.file() {
+        out.write_str(" file=")?;
+        logfmt_value(out, &file)?;
+    }
+    if let Some(line) = record.line() {
+        write!(out, " line={}", line)?;
+    }
+
+    out.write_str(" msg=")?;
+    logfmt_value(out, record.args())?;
+    write_pairs(out, record, LogFormat::Logfmt)
+}
+
+fn write_json<W: Write>(out: &mut W, record: &Record, time: &SystemTime) -> fmt::Result {
+    out.write_str("{\"ts\":\"")?;
+    write_timestamp(out, time)?;
+    write!(out, "\",\"level\":\"{}\",\"target\":", level_name(record.level()))?;
+    json_string(out, &record.target())?;
+
+    if let Some(file) = record.file() {
+        out.write_str(",\"file\":")?;
+        json_string(out, &file)?;
+    }
+    if let Some(line) = record.line() {
+        write!(out, ",\"line\":{}", line)?;
+    }
+
+    out.write_str(",\"msg\":")?;
+    json_string(out, record.args())?;
+    write_pairs(out, record, LogFormat::Json)?;
+    out.write_str("}")
+}
+
+fn write_pairs<W: Write>(out: &mut W, record: &Record, format: LogFormat) -> fmt::Result {
+    let mut pairs = Pairs { out, format };
+    record.key_values().visit(&mut pairs).map_err(|_| fmt::Error)
+}
+
+struct Pairs<'a, W> {
+    out: &'a mut W,
+    format: LogFormat,
+}
+
+impl<'kvs, W: Write> VisitSource<'kvs> for Pairs<'_, W> {
+    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), kv::Error> {
+        match self.format {
+            LogFormat::Json => {
+                self.out.write_str(",")?;
+                json_string(self.out, &key)?;
+                self.out.write_str(":")?;
+                json_value(self.out, &value)?;
+            }
+            LogFormat::Text | LogFormat::Logfmt => {
+                write!(self.out, " {}=", key)?;
+                logfmt_value(self.out, &value)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Quotes the value only when it is empty or contains spaces, `=`, `"` or control characters.
+fn logfmt_value<W: Write>(out: &mut W, value: &dyn fmt::Display) -> fmt::Result {
+    let mut scan = NeedsQuotes { empty: true, quote: false };
+    write!(scan, "{}", value)?;
+
+    if scan.empty || scan.quote {
+        out.write_str("\"")?;
+        write!(Escape { out, json: false }, "{}", value)?;
+        out.write_str("\"")
+    } else {
+        write!(out, "{}", value)
+    }
+}
+
+/// Numbers and booleans stay bare, everything else becomes a string.
+fn json_value<W: Write>(out: &mut W, value: &Value) -> fmt::Result {
+    if let Some(value) = value.to_bool() {
+        write!(out, "{}", value)
+    } else if let Some(value) = value.to_i64() {
+        write!(out, "{}", value)
+    } else if let Some(value) = value.to_u64() {
+        write!(out, "{}", value)
+    } else if let Some(value) = value.to_f64().filter(|value| value.is_finite()) {
+        write!(out, "{}", value)
+    } else {
+        json_string(out, value)
+    }
+}
+
+fn json_string<W: Write>(out: &mut W, value: &dyn fmt::Display) -> fmt::Result {
+    out.write_str("\"")?;
+    write!(Escape { out, json: true }, "{}", value)?;
+    out.write_str("\"")
+}
+
+struct NeedsQuotes {
+    empty: bool,
+    quote: bool,
+}
+
+impl Write for NeedsQuotes {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.empty &= s.is_empty();
+        self.quote |= s.chars().any(|ch| ch <= ' ' || ch == '=' || ch == '"' || ch == '\u{7f}');
+        Ok(())
+    }
+}
+
+struct Escape<'a, W> {
+    out: &'a mut W,
+    json: bool,
+}
+
+impl<W: Write> Write for Escape<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut start = 0;
+
+        for (index, ch) in s.char_indices() {
+            if ch != '"' && ch != '\\' && ch >= ' ' {
+                continue;
+            }
+
+            self.out.write_str(&s[start..index])?;
+            match ch {
+                '"' => self.out.write_str("\\\"")?,
+                '\\' => self.out.write_str("\\\\")?,
+                '\n' => self.out.write_str("\\n")?,
+                '\r' => self.out.write_str("\\r")?,
+                '\t' => self.out.write_str("\\t")?,
+                ch if self.json => write!(self.out, "\\u{:04x}", ch as u32)?,
+                ch => self.out.write_char(ch)?,
+            }
+            start = index + ch.len_utf8();
+        }
+
+        self.out.write_str(&s[start..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use log::kv::Source;
+
+    /// 2025-01-01T12:00:00.250Z
+    const TIME: SystemTime = SystemTime::from_ticks(133_802_064_002_500_000);
+
+    fn render(format: LogFormat, message: fmt::Arguments, pairs: &dyn Source) -> LineBuffer<512> {
+        let record = Record::builder()
+            .level(Level::Warn)
+            .target("app::net")
+            .file(Some("src/net.rs"))
+            .line(Some(42))
+            .args(message)
+            .key_values(pairs)
+            .build();
+
+        let mut line = LineBuffer::new();
+        format.write(&mut line, &record, &TIME).unwrap();
+        assert!(!line.is_truncated());
+        line
+    }
+
+    #[test]
+    fn text() {
+        let line = render(LogFormat::Text, format_args!("connected"), &[("peer", "10.0.0.1")]);
+        assert_eq!(line.as_str(), "2025-01-01 12:00:00.250 [WARN] [app::net] [src/net.rs:42] connected peer=10.0.0.1");
+    }
+
+    #[test]
+    fn logfmt_quotes_only_when_needed() {
+        let line = render(LogFormat::Logfmt, format_args!("plain"), &[("empty", ""), ("n", "3")]);
+        assert_eq!(
+            line.as_str(),
+            "ts=2025-01-01T12:00:00.250Z level=warn target=app::net file=src/net.rs line=42 msg=plain empty=\"\" n=3"
+        );
+
+        let line = render(LogFormat::Logfmt, format_args!("a \"quoted\"\tword\\"), &[("k", "x=y")]);
+        assert!(line.as_str().ends_with(r#" msg="a \"quoted\"\tword\\" k="x=y""#), "{}", line.as_str());
+    }
+
+    #[test]
+    fn logfmt_keeps_other_control_chars() {
+        let line = render(LogFormat::Logfmt, format_args!("bell\u{7}"), &[("id", "1")]);
+        assert!(line.as_str().ends_with(" msg=\"bell\u{7}\" id=1"), "{}", line.as_str());
+    }
+
+    #[test]
+    fn json_escapes_and_types() {
+        let line = render(
+            LogFormat::Json,
+            format_args!("line\nbreak \"q\" \\ \u{1}"),
+            &[("count", -7i64)],
+        );
+        assert_eq!(
+            line.as_str(),
+            r#"{"ts":"2025-01-01T12:00:00.250Z","level":"warn","target":"app::net","file":"src/net.rs","line":42,"msg":"line\nbreak \"q\" \\ \u0001","count":-7}"#
+        );
+
+        let line = render(LogFormat::Json, format_args!("x"), &[("ok", true)]);
+        assert!(line.as_str().ends_with(r#","ok":true}"#));
+        let line = render(LogFormat::Json, format_args!("x"), &[("ratio", 0.5)]);
+        assert!(line.as_str().ends_with(r#","ratio":0.5}"#));
+        let line = render(LogFormat::Json, format_args!("x"), &[("nan", f64::NAN)]);
+        assert!(line.as_str().ends_with(r#","nan":"NaN"}"#), "{}", line.as_str());
+        let line = render(LogFormat::Json, format_args!("x"), &[("key \"q\"", "v\r")]);
+        assert!(line.as_str().ends_with(r#","key \"q\"":"v\r"}"#));
+    }
+
+    #[test]
+    fn line_buffer_truncates_at_char_boundary() {
+        let mut line = LineBuffer::<8>::new();
+        write!(line, "abcdé").unwrap();
+        assert_eq!(line.len(), 6);
+        assert!(!line.is_truncated());
+
+        // Only one byte is left after `x`, and `ü` needs two.
+        write!(line, "xü").unwrap();
+        assert_eq!(line.as_str(), "abcdéx");
+        assert!(line.is_truncated());
+
+        write!(line, "yz").unwrap();
+        assert_eq!(line.as_str(), "abcdéxy");
+        assert_eq!(line.len(), 8);
+    }
+
+    #[test]
+    fn finish_line_makes_room_for_newline() {
+        let mut line = LineBuffer::<4>::new();
+        write!(line, "abc").unwrap();
+        assert_eq!(line.finish_line(), b"abc\n");
+        assert!(!line.is_truncated());
+
+        line.clear();
+        assert!(line.is_empty());
+        write!(line, "aé").unwrap();
+        assert_eq!(line.len(), 3);
+        write!(line, "b").unwrap();
+        assert_eq!(line.len(), 4);
+        assert_eq!(line.finish_line(), "aé\n".as_bytes());
+        assert!(line.is_truncated());
+
+        line.clear();
+        write!(line, "a€").unwrap();
+        assert_eq!(line.finish_line(), b"a\n");
+    }
+}
@@ -1,3 +1,15 @@
+mod console;
+mod format;
+mod multi;
+#[cfg(windows)]
 mod nt_file_logger;
+mod ring;
+mod rotating;
 
-pub use nt_file_logger::*;
\ No newline at end of file
+pub use console::*;
+pub use format::*;
+pub use multi::*;
+#[cfg(windows)]
+pub use nt_file_logger::*;
+pub use ring::*;
+pub use rotating::*;
@@ -0,0 +1,108 @@
+use log::{LevelFilter, Log, Metadata, Record, SetLoggerError};
+
+/// Fans each record out to every logger that has it enabled.
+///
+/// The inner loggers must not be registered with `log` themselves; build them
+/// with their `new` constructors and register only the `MultiLogger`.
+pub struct MultiLogger<const N: usize> {
+    loggers: [&'static dyn Log; N],
+}
+
+impl<const N: usize> MultiLogger<N> {
+    pub const fn new(loggers: [&'static dyn Log; N]) -> Self {
+        Self { loggers }
+    }
+
+    /// `level` caps what `log` hands over; each inner logger still filters by its own level.
+    pub fn init(&'static self, level: LevelFilter) -> Result<(), SetLoggerError> {
+        log::set_logger(self)?;
+        log::set_max_level(level);
+        Ok(())
+    }
+
+    pub fn loggers(&self) -> &[&'static dyn Log] {
+        &self.loggers
+    }
+}
+
+impl<const N: usize> Log for MultiLogger<N> {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.loggers.iter().any(|logger| logger.enabled(metadata))
+    }
+
+    fn log(&self, record: &Record) {
+        for logger in &self.loggers {
+            if logger.enabled(record.metadata()) {
+                logger.log(record);
+            }
+        }
+    }
+
+    fn flush(&self) {
+        for logger in &self.loggers {
+            logger.flush();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    use log::Level;
+
+    use super::*;
+
+    /// Counts what reaches it.
+    struct Counting {
+        level: LevelFilter,
+        logged: AtomicUsize,
+        flushed: AtomicUsize,
+    }
+
+    impl Counting {
+        const fn new(level: LevelFilter) -> Self {
+            Self { level, logged: AtomicUsize::new(0), flushed: AtomicUsize::new(0) }
+        }
+
+        fn logged(&self) -> usize {
+            self.logged.load(Ordering::Relaxed)
+        }
+    }
+
+    impl Log for Counting {
+        fn enabled(&self, metadata: &Metadata) -> bool {
+            metadata.level() <= self.level
+        }
+
+        fn log(&self, _record: &Record) {
+            self.logged.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn flush(&self) {
+            self.flushed.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn forwards_by_level() {
+        static WARN: Counting = Counting::new(LevelFilter::Warn);
+        static DEBUG: Counting = Counting::new(LevelFilter::Debug);
+        let multi = MultiLogger::new([&WARN, &DEBUG]);
+
+        let metadata = |level| Metadata::builder().level(level).build();
+        assert!(multi.enabled(&metadata(Level::Error)));
+        assert!(multi.enabled(&metadata(Level::Debug)));
+        assert!(!multi.enabled(&metadata(Level::Trace)));
+
+        for level in [Level::Error, Level::Warn, Level::Info, Level::Debug, Level::Trace] {
+            multi.log(&Record::builder().level(level).args(format_args!("x")).build());
+        }
+        assert_eq!((WARN.logged(), DEBUG.logged()), (2, 4));
+
+        multi.flush();
+        assert_eq!(WARN.flushed.load(Ordering::Relaxed), 1);
+        assert_eq!(DEBUG.flushed.load(Ordering::Relaxed), 1);
+        assert_eq!(multi.loggers().len(), 2);
+    }
+}
@@ -1,6 +1,7 @@
 use log::{LevelFilter, Log, Metadata, Record};
 use ntapi::ntexapi::KUSER_SHARED_DATA;
-use crate::{BufWriter, File, FileError, Mutex, ProcessEnvironmentBlock, U16CStackString, Write as IoWrite, canonicalize, println};
+use crate::{BufWriter, FileError, Mutex, ProcessEnvironmentBlock, U16CStackString, Write as IoWrite};
+use crate::logger::{LineBuffer, LogFormat, RotatingFile, Rotation};
 use winapi::shared::ntdef::UNICODE_STRING;
 use core::fmt::{self, Write};
 
@@ -30,43 +31,13 @@ impl fmt::Display for LoggerError {
 }
 
 static mut LOGGER: Option<NtFileLogger> = None;
-static mut BUFFER: [u8; 1024] = [0; 1024];
-
-struct Formatter {
-    buf: &'static mut [u8],
-    pos: usize,
-}
-
-impl Formatter {
-    const fn new() -> Self {
-        unsafe { BUFFER = core::mem::zeroed() };
-        Self {
-            buf: unsafe { BUFFER.as_mut_slice() },
-            pos: 0,
-        }
-    }
-
-    fn as_bytes(&self) -> &[u8] {
-        &self.buf[..self.pos]
-    }
-}
-
-impl core::fmt::Write for Formatter {
-    fn write_str(&mut self, s: &str) -> core::fmt::Result {
-        let bytes = s.as_bytes();
-        let remaining = self.buf.len() - self.pos;
-        if bytes.len() > remaining {
-            return Err(core::fmt::Error);
-        }
-        self.buf[self.pos..self.pos + bytes.len()].copy_from_slice(bytes);
-        self.pos += bytes.len();
-        Ok(())
-    }
-}
 
+/// Logs to `<exe dir>\\<exe name>_<hhmmss>.log`. Records are buffered until
+/// the buffer fills or [`Log::flush`] is called.
 pub struct NtFileLogger {
     level: LevelFilter,
-    writer: Mutex<BufWriter<File>>
+    format: LogFormat,
+    writer: Mutex<BufWriter<RotatingFile>>,
 }
 
 unsafe impl Send for NtFileLogger {}
@@ -74,6 +45,23 @@ unsafe impl Sync for NtFileLogger {}
 
 impl NtFileLogger {
     pub fn init(level: LevelFilter) -> Result<&'static NtFileLogger, LoggerError> {
+        Self::init_with(level, LogFormat::Text, Rotation::NEVER)
+    }
+
+    pub fn init_with(level: LevelFilter, format: LogFormat, rotation: Rotation) -> Result<&'static NtFileLogger, LoggerError> {
+        let logger = Self::new(level, format, rotation)?;
+
+        unsafe {
+            LOGGER = Some(logger);
+            let logger_ref: &'static NtFileLogger = &*(&*LOGGER.as_ref().unwrap() as *const _);
+            log::set_logger(logger_ref).map_err(|_| LoggerError::SetLogger)?;
+            log::set_max_level(level);
+            Ok(logger_ref)
+        }
+    }
+
+    /// Opens the log file without registering with `log`, e.g. for a [`MultiLogger`](super::MultiLogger).
+    pub fn new(level: LevelFilter, format: LogFormat, rotation: Rotation) -> Result<Self, LoggerError> {
         let peb = ProcessEnvironmentBlock::current_process();
         let path = peb.executable_path();
         let parent_dir = path.parent();
@@ -82,20 +70,13 @@ impl NtFileLogger {
         
         let mut target_path = U16CStackString::<260>::new();
         write!(&mut target_path, "\\??\\{}\\{}_{}.log", parent_dir.display::<120>(), file_name.display::<120>(), system_time.to_hh_mm_ss());
-        let file = File::create(target_path).map_err(LoggerError::FileCreate)?;
+        let file = RotatingFile::open(target_path, rotation).map_err(LoggerError::FileCreate)?;
 
-        let logger = NtFileLogger {
+        Ok(NtFileLogger {
             level,
-            writer: Mutex::new(BufWriter::new(file))
-        };
-
-        unsafe {
-            LOGGER = Some(logger);
-            let logger_ref: &'static NtFileLogger = &*(&*LOGGER.as_ref().unwrap() as *const _);
-            log::set_logger(logger_ref).map_err(|_| LoggerError::SetLogger)?;
-            log::set_max_level(level);
-            Ok(logger_ref)
-        }
+            format,
+            writer: Mutex::new(BufWriter::new(file)),
+        })
     }
 
     fn log_inner(&self, record: &Record) -> Result<(), LoggerError> {
@@ -103,31 +84,13 @@ impl NtFileLogger {
             return Ok(());
         }
 
-        let system_time = SystemTime::now();
-        let message = Self::format_message(record, system_time).map_err(LoggerError::Formatter)?;;
+        let mut line = LineBuffer::<1024>::new();
+        self.format.write(&mut line, record, &SystemTime::now()).map_err(LoggerError::Formatter)?;
 
         let mut guard = self.writer.lock();
-        guard.write_all(message.as_bytes()).map_err(|_| LoggerError::Write)?;
-        guard.write_all(b"\n").map_err(|_| LoggerError::Write)?;
+        guard.write_all(line.finish_line()).map_err(|_| LoggerError::Write)?;
         Ok(())
     }
-
-    fn format_message(record: &Record, system_time: SystemTime) -> Result<Formatter, core::fmt::Error> {
-        let mut f = Formatter::new();
-        write!(f, "{} [{}] [{}]", system_time, record.level(), record.target())?;
-        
-        if let Some(file) = record.file() {
-            write!(f, " [{}", file)?;
-            if let Some(line) = record.line() {
-                write!(f, ":{}]", line)?;
-            } else {
-                write!(f, "]")?;
-            }
-        }
-        
-        write!(f, " {}", record.args())?;
-        Ok(f)
-    }
 }
 
 impl log::Log for NtFileLogger {
@@ -146,11 +109,3 @@ impl log::Log for NtFileLogger {
         let _ = guard.flush_buf();
     }
 }
-
-// pub struct NtFileLoggerGuard<'a>(&'a NtFileLogger);
-
-// impl<'a> Drop for NtFileLoggerGuard<'a> {
-//     fn drop(&mut self) {
-//         self.0.flush();
-//     }
-// }
\ No newline at end of file
@@ -0,0 +1,310 @@
+use core::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+#[cfg(feature = "alloc")]
+use core::time::Duration;
+
+use log::{LevelFilter, Log, Metadata, Record, SetLoggerError};
+
+use crate::collections::ArrayQueue;
+use crate::error::FileError;
+use crate::futex::{wait_on_address, wake_by_address_single};
+use crate::io::Write;
+use crate::logger::{LineBuffer, LogFormat};
+use crate::time::SystemTime;
+#[cfg(feature = "alloc")]
+use crate::{JoinHandle, Thread, ThreadError};
+
+/// Bytes [`RingLogger::drain`] collects before each write to its output.
+const DRAIN_CHUNK: usize = 4096;
+
+/// Logger that only formats records into a lock-free ring of `SLOTS` lines of
+/// up to `LINE` bytes, leaving the I/O to whoever calls [`RingLogger::drain`],
+/// usually the thread from `spawn_flusher`. Records arriving while the ring is
+/// full are dropped and counted.
+pub struct RingLogger<const SLOTS: usize, const LINE: usize> {
+    level: LevelFilter,
+    format: LogFormat,
+    ring: ArrayQueue<LineBuffer<LINE>, SLOTS>,
+    dropped: AtomicU64,
+    wake: AtomicU32,
+    stopped: AtomicBool,
+}
+
+impl<const SLOTS: usize, const LINE: usize> RingLogger<SLOTS, LINE> {
+    pub const fn new(level: LevelFilter, format: LogFormat) -> Self {
+        Self {
+            level,
+            format,
+            ring: ArrayQueue::new(),
+            dropped: AtomicU64::new(0),
+            wake: AtomicU32::new(0),
+            stopped: AtomicBool::new(false),
+        }
+    }
+
+    pub fn init(&'static self) -> Result<(), SetLoggerError> {
+        log::set_logger(self)?;
+        log::set_max_level(self.level);
+        Ok(())
+    }
+
+    /// Lines waiting to be drained; a snapshot under concurrent use.
+    pub fn queued(&self) -> usize {
+        self.ring.len()
+    }
+
+    /// Records lost because the ring was full.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Writes every queued line to `out`, newline-terminated, and returns how
+    /// many were written. Lines taken before an error are lost.
+    pub fn drain<W: Write>(&self, out: &mut W) -> Result<usize, FileError> {
+        let mut batch = Batch { out, buf: [0; DRAIN_CHUNK], len: 0 };
+        let mut lines = 0;
+
+        while let Some(mut line) = self.ring.pop() {
+            batch.push(line.finish_line())?;
+            lines += 1;
+        }
+
+        batch.flush()?;
+        Ok(lines)
+    }
+
+    /// Starts a thread that drains into `out` every `interval`, or sooner when
+    /// [`Log::flush`] is called.
+    #[cfg(feature = "alloc")]
+    pub fn spawn_flusher<W>(&'static self, mut out: W, interval: Duration) -> Result<Flusher<SLOTS, LINE, W>, ThreadError>
+    where
+        W: Write + Send + 'static,
+    {
+        self.stopped.store(false, Ordering::Release);
+
+        let handle = Thread::spawn(move || {
+            loop {
+                let epoch = self.wake.load(Ordering::Acquire);
+                let _ = self.drain(&mut out);
+
+                if self.stopped.load(Ordering::Acquire) {
+                    let _ = self.drain(&mut out);
+                    return out;
+                }
+
+                wait_on_address(&self.wake, epoch, Some(interval));
+            }
+        })?;
+
+        Ok(Flusher { logger: self, handle })
+    }
+
+    fn wake(&self) {
+        self.wake.fetch_add(1, Ordering::Release);
+        wake_by_address_single(&self.wake);
+    }
+}
+
+impl<const SLOTS: usize, const LINE: usize> Log for RingLogger<SLOTS, LINE> {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let mut line = LineBuffer::<LINE>::new();
+        let _ = self.format.write(&mut line, record, &SystemTime::now());
+
+        if self.ring.push(line).is_err() {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Wakes the flusher without waiting for it.
+    fn flush(&self) {
+        self.wake();
+    }
+}
+
+/// The draining thread of a [`RingLogger`].
+#[cfg(feature = "alloc")]
+pub struct Flusher<const SLOTS: usize, const LINE: usize, W> {
+    logger: &'static RingLogger<SLOTS, LINE>,
+    handle: JoinHandle<W>,
+}
+
+#[cfg(feature = "alloc")]
+impl<const SLOTS: usize, const LINE: usize, W> Flusher<SLOTS, LINE, W> {
+    /// Drains what is left and hands back the output.
+    pub fn stop(self) -> Result<W, ThreadError> {
+        self.logger.stopped.store(true, Ordering::Release);
+        self.logger.wake();
+        self.handle.join()
+    }
+}
+
+struct Batch<'a, W> {
+    out: &'a mut W,
+    buf: [u8; DRAIN_CHUNK],
+    len: usize,
+}
+
+impl<W: Write> Batch<'_, W> {
+    fn push(&mut self, bytes: &[u8]) -> Result<(), FileError> {
+        if self.len + bytes.len() > DRAIN_CHUNK {
+            self.flush()?;
+        }
+
+        if bytes.len() > DRAIN_CHUNK {
+            return self.out.write_all(bytes);
+        }
+
+        self.buf[self.len..self.len + bytes.len()].copy_from_slice(bytes);
+        self.len += bytes.len();
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), FileError> {
+        let len = core::mem::take(&mut self.len);
+        self.out.write_all(&self.buf[..len])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use core::fmt;
+    use log::Level;
+    use std::string::String;
+    use std::vec::Vec;
+
+    /// Records every write and counts the newlines that went through it.
+    struct Sink<'a> {
+        bytes: Vec<u8>,
+        writes: usize,
+        lines: &'a AtomicU64,
+    }
+
+    impl<'a> Sink<'a> {
+        fn new(lines: &'a AtomicU64) -> Self {
+            Self { bytes: Vec::new(), writes: 0, lines }
+        }
+
+        fn lines(&self) -> Vec<&str> {
+            let text = core::str::from_utf8(&self.bytes).unwrap();
+            assert!(text.is_empty() || text.ends_with('\n'));
+            text.lines().collect()
+        }
+    }
+
+    impl Write for Sink<'_> {
+        fn write(&mut self, buf: &[u8]) -> Result<usize, FileError> {
+            self.bytes.extend_from_slice(buf);
+            self.writes += 1;
+            self.lines.fetch_add(buf.iter().filter(|&&byte| byte == b'\n').count() as u64, Ordering::Relaxed);
+            Ok(buf.len())
+        }
+    }
+
+    fn log<const SLOTS: usize, const LINE: usize>(logger: &RingLogger<SLOTS, LINE>, level: Level, message: fmt::Arguments) {
+        logger.log(&Record::builder().level(level).target("test").args(message).build());
+    }
+
+    #[test]
+    fn full_ring_drops_and_counts() {
+        let logger = RingLogger::<4, 128>::new(LevelFilter::Info, LogFormat::Text);
+
+        for i in 0..6 {
+            log(&logger, Level::Info, format_args!("line {i}"));
+        }
+        // Filtered out, so neither queued nor dropped.
+        log(&logger, Level::Debug, format_args!("hidden"));
+
+        assert_eq!((logger.queued(), logger.dropped()), (4, 2));
+
+        let counter = AtomicU64::new(0);
+        let mut sink = Sink::new(&counter);
+        assert_eq!(logger.drain(&mut sink).unwrap(), 4);
+        assert_eq!(sink.writes, 1);
+
+        let lines = sink.lines();
+        assert_eq!(lines.len(), 4);
+        for (i, line) in lines.iter().enumerate() {
+            assert!(line.ends_with(&std::format!("[INFO] [test] line {i}")), "{line}");
+        }
+
+        assert_eq!(logger.queued(), 0);
+        assert_eq!(logger.drain(&mut sink).unwrap(), 0);
+        log(&logger, Level::Warn, format_args!("after"));
+        assert_eq!((logger.queued(), logger.dropped()), (1, 2));
+    }
+
+    #[test]
+    fn drain_batches_past_the_chunk_size() {
+        let logger = RingLogger::<64, 256>::new(LevelFilter::Trace, LogFormat::Text);
+        let padding = String::from_iter(core::iter::repeat_n('x', 150));
+
+        for i in 0..64 {
+            log(&logger, Level::Info, format_args!("{i:02} {padding}"));
+        }
+
+        let counter = AtomicU64::new(0);
+        let mut sink = Sink::new(&counter);
+        assert_eq!(logger.drain(&mut sink).unwrap(), 64);
+        assert!(sink.bytes.len() > 2 * DRAIN_CHUNK);
+        assert!(sink.writes > 2 && sink.writes < 64, "{} writes", sink.writes);
+
+        for (i, line) in sink.lines().iter().enumerate() {
+            assert!(line.ends_with(&std::format!("{i:02} {padding}")), "{line}");
+        }
+    }
+
+    #[test]
+    fn truncated_lines_are_still_terminated() {
+        let logger = RingLogger::<2, 48>::new(LevelFilter::Info, LogFormat::Text);
+        log(&logger, Level::Info, format_args!("{}", "y".repeat(100)));
+
+        let counter = AtomicU64::new(0);
+        let mut sink = Sink::new(&counter);
+        logger.drain(&mut sink).unwrap();
+        assert_eq!(sink.bytes.len(), 48);
+        assert_eq!(sink.lines().len(), 1);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn flusher_wakes_on_flush_and_drains_on_stop() {
+        static LOGGER: RingLogger<16, 128> = RingLogger::new(LevelFilter::Info, LogFormat::Text);
+        static LINES: AtomicU64 = AtomicU64::new(0);
+
+        let flusher = LOGGER.spawn_flusher(Sink::new(&LINES), Duration::from_secs(3600)).unwrap();
+        // Let the first drain pass so only `flush` can wake the thread.
+        std::thread::sleep(Duration::from_millis(50));
+
+        log(&LOGGER, Level::Info, format_args!("first"));
+        LOGGER.flush();
+
+        let start = std::time::Instant::now();
+        while LINES.load(Ordering::Relaxed) < 1 {
+            assert!(start.elapsed() < Duration::from_secs(5), "flush did not wake the flusher");
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        log(&LOGGER, Level::Info, format_args!("second"));
+        log(&LOGGER, Level::Error, format_args!("third"));
+
+        let sink = flusher.stop().unwrap();
+        assert_eq!(LINES.load(Ordering::Relaxed), 3);
+        assert_eq!(LOGGER.queued(), 0);
+
+        let lines = sink.lines();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].ends_with("first"));
+        assert!(lines[1].ends_with("second"));
+        assert!(lines[2].ends_with("[ERROR] [test] third"));
+    }
+}
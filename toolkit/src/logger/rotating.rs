@@ -0,0 +1,209 @@
+use core::fmt::Write as _;
+
+use crate::error::FileError;
+use crate::fs::{File, FileOptions, rename};
+use crate::io::{Seek, SeekFrom, Write};
+use crate::U16CStackString;
+
+/// Size and count limits for a [`RotatingFile`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rotation {
+    pub max_size: u64,
+    pub max_files: u32,
+}
+
+impl Rotation {
+    /// Grows without bound.
+    pub const NEVER: Rotation = Rotation { max_size: u64::MAX, max_files: 0 };
+
+    pub const fn new(max_size: u64, max_files: u32) -> Self {
+        Self { max_size, max_files }
+    }
+}
+
+/// File that appends to `path` until a write would take it past `max_size`,
+/// then shifts `path.1` .. `path.{max_files - 1}` up by one, moves `path` to
+/// `path.1` and starts over. With `max_files` zero the old contents are discarded.
+///
+/// A write that is larger than `max_size` on its own still goes to a single file.
+pub struct RotatingFile {
+    path: U16CStackString<260>,
+    rotation: Rotation,
+    file: Option<File>,
+    size: u64,
+}
+
+impl RotatingFile {
+    pub fn open(path: U16CStackString<260>, rotation: Rotation) -> Result<Self, FileError> {
+        let mut file = Self { path, rotation, file: None, size: 0 };
+        file.reopen()?;
+        Ok(file)
+    }
+
+    pub fn path(&self) -> &U16CStackString<260> {
+        &self.path
+    }
+
+    /// Bytes in the current file.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    pub fn rotate(&mut self) -> Result<(), FileError> {
+        self.file = None;
+
+        if self.rotation.max_files == 0 {
+            self.open_file(true)?;
+            return Ok(());
+        }
+
+        for n in (1..self.rotation.max_files).rev() {
+            ignore_missing(rename(self.numbered(n)?, self.numbered(n + 1)?, true))?;
+        }
+        ignore_missing(rename(self.path.clone(), self.numbered(1)?, true))?;
+
+        self.reopen()?;
+        Ok(())
+    }
+
+    fn reopen(&mut self) -> Result<&mut File, FileError> {
+        self.open_file(false)
+    }
+
+    /// Both paths share delete access, so readers holding the log open never block a rotation.
+    fn open_file(&mut self, truncate: bool) -> Result<&mut File, FileError> {
+        let mut opts = FileOptions::new();
+        opts.write().share_read().share_delete().synchronous();
+        if truncate {
+            opts.truncate_always();
+        } else {
+            opts.open_always();
+        }
+
+        let mut file = File::create_with_options(self.path.clone(), &opts)?;
+        self.size = file.seek(SeekFrom::End(0))?;
+        Ok(self.file.insert(file))
+    }
+
+    fn numbered(&self, n: u32) -> Result<U16CStackString<260>, FileError> {
+        let mut path = self.path.clone();
+        write!(path, ".{}", n).map_err(|_| FileError::PathSyntaxBad)?;
+        Ok(path)
+    }
+}
+
+fn ignore_missing(result: Result<(), FileError>) -> Result<(), FileError> {
+    match result {
+        Err(FileError::ObjectNameNotFound | FileError::FileNotFound) => Ok(()),
+        result => result,
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, FileError> {
+        if self.size > 0 && self.size.saturating_add(buf.len() as u64) > self.rotation.max_size {
+            self.rotate()?;
+        }
+
+        // A failed rotation leaves no file open; try again on the next write.
+        let file = match self.file {
+            Some(ref mut file) => file,
+            None => self.reopen()?,
+        };
+
+        let written = file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::format;
+    use std::string::String;
+    use std::vec::Vec;
+
+    /// Removes `base` and its numbered siblings before and after the test.
+    struct Cleanup(String);
+
+    impl Cleanup {
+        fn new(name: &str) -> Self {
+            let cleanup = Self(format!("/tmp/toolkit-rotating-{}-{}", std::process::id(), name));
+            cleanup.remove();
+            cleanup
+        }
+
+        fn path(&self) -> U16CStackString<260> {
+            U16CStackString::from_str(&self.0).unwrap()
+        }
+
+        fn read(&self, suffix: &str) -> Option<Vec<u8>> {
+            std::fs::read(format!("{}{}", self.0, suffix)).ok()
+        }
+
+        fn remove(&self) {
+            for suffix in ["", ".1", ".2", ".3"] {
+                let _ = std::fs::remove_file(format!("{}{}", self.0, suffix));
+            }
+        }
+    }
+
+    impl Drop for Cleanup {
+        fn drop(&mut self) {
+            self.remove();
+        }
+    }
+
+    #[test]
+    fn shifts_numbered_files() {
+        let files = Cleanup::new("shift");
+        let mut log = RotatingFile::open(files.path(), Rotation::new(10, 2)).unwrap();
+
+        log.write_all(b"first\n").unwrap();
+        log.write_all(b"second\n").unwrap();
+        assert_eq!(log.size(), 7);
+        log.write_all(b"third\n").unwrap();
+        log.write_all(b"fourth\n").unwrap();
+
+        assert_eq!(files.read("").unwrap(), b"fourth\n");
+        assert_eq!(files.read(".1").unwrap(), b"third\n");
+        assert_eq!(files.read(".2").unwrap(), b"second\n");
+        assert_eq!(files.read(".3"), None);
+    }
+
+    #[test]
+    fn zero_files_truncates_in_place() {
+        let files = Cleanup::new("truncate");
+        let mut log = RotatingFile::open(files.path(), Rotation::new(8, 0)).unwrap();
+
+        log.write_all(b"12345").unwrap();
+        log.write_all(b"6789").unwrap();
+        assert_eq!(log.size(), 4);
+
+        assert_eq!(files.read("").unwrap(), b"6789");
+        assert_eq!(files.read(".1"), None);
+    }
+
+    #[test]
+    fn oversized_write_and_reopen() {
+        let files = Cleanup::new("reopen");
+        let mut log = RotatingFile::open(files.path(), Rotation::new(4, 1)).unwrap();
+
+        log.write_all(b"too long for one file").unwrap();
+        assert_eq!(files.read(".1"), None);
+        drop(log);
+
+        // An existing file is appended to, and rotates on the next write.
+        let mut log = RotatingFile::open(files.path(), Rotation::new(4, 1)).unwrap();
+        assert_eq!(log.size(), 21);
+        log.write_all(b"ab").unwrap();
+        log.rotate().unwrap();
+        log.write_all(b"cd").unwrap();
+
+        assert_eq!(files.read(".1").unwrap(), b"ab");
+        assert_eq!(files.read("").unwrap(), b"cd");
+    }
+}
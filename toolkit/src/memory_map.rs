@@ -0,0 +1,609 @@
+use core::{fmt, str::FromStr};
+
+use alloc::{string::String, vec::Vec};
+
+use crate::MemoryMapError;
+
+/// First line of a serialized [`MemoryMap`].
+const HEADER: &str = "memory-map v1";
+
+/// Page protection, independent of the OS encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Protection(pub u32);
+
+impl Protection {
+    pub const NONE: Self = Self(0);
+    pub const READ: Self = Self(0x1);
+    pub const WRITE: Self = Self(0x2);
+    pub const EXECUTE: Self = Self(0x4);
+    /// Writes go to a private copy, `PAGE_WRITECOPY` or a private writable file mapping.
+    pub const COPY_ON_WRITE: Self = Self(0x8);
+    pub const GUARD: Self = Self(0x10);
+
+    const FLAGS: [(Self, char); 5] = [
+        (Self::READ, 'r'),
+        (Self::WRITE, 'w'),
+        (Self::EXECUTE, 'x'),
+        (Self::COPY_ON_WRITE, 'c'),
+        (Self::GUARD, 'g'),
+    ];
+
+    /// Every flag of `other` is set.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+impl core::ops::BitOr for Protection {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        self.union(rhs)
+    }
+}
+
+/// `rwxcg`, with `-` for a flag that is not set.
+impl fmt::Display for Protection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (flag, ch) in Self::FLAGS {
+            write!(f, "{}", if self.contains(flag) { ch } else { '-' })?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Protection {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        if s.len() != Self::FLAGS.len() {
+            return Err(());
+        }
+
+        let mut protection = Self::NONE;
+        for ((flag, ch), found) in Self::FLAGS.into_iter().zip(s.chars()) {
+            match found {
+                '-' => {}
+                found if found == ch => protection = protection | flag,
+                _ => return Err(()),
+            }
+        }
+        Ok(protection)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RegionState {
+    Committed,
+    Reserved,
+    Free,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RegionType {
+    /// Anonymous memory of the process
+    Private,
+    /// A file or shared section. Linux reports every file-backed mapping as this.
+    Mapped,
+    /// An executable image mapped by the Windows loader
+    Image,
+    /// Free regions have no type
+    None,
+}
+
+impl RegionState {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Committed => "commit",
+            Self::Reserved => "reserve",
+            Self::Free => "free",
+        }
+    }
+}
+
+impl RegionType {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Private => "private",
+            Self::Mapped => "mapped",
+            Self::Image => "image",
+            Self::None => "none",
+        }
+    }
+}
+
+/// One range of pages with the same attributes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Region {
+    pub base: usize,
+    pub size: usize,
+    pub protection: Protection,
+    pub state: RegionState,
+    pub kind: RegionType,
+    /// Backing file, or a pseudo-path such as `[stack]` on Linux.
+    pub file: Option<String>,
+}
+
+impl Region {
+    pub fn end(&self) -> usize {
+        self.base.saturating_add(self.size)
+    }
+
+    pub fn contains(&self, address: usize) -> bool {
+        self.base <= address && address < self.end()
+    }
+
+    /// Same attributes, ignoring where the region is.
+    pub fn same_attributes(&self, other: &Region) -> bool {
+        self.protection == other.protection
+            && self.state == other.state
+            && self.kind == other.kind
+            && self.file == other.file
+    }
+}
+
+/// Difference between two snapshots, regions matched by base address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegionChange<'a> {
+    Added(&'a Region),
+    Removed(&'a Region),
+    /// Same base, different size or attributes
+    Changed { before: &'a Region, after: &'a Region },
+}
+
+/// Snapshot of an address space: non-overlapping regions sorted by base.
+///
+/// [`Display`](fmt::Display) writes a line-based text form that
+/// [`MemoryMap::parse`] reads back on any platform:
+///
+/// ```text
+/// memory-map v1
+/// 7ffd3a1c0000 21000 rw--- commit private [stack]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemoryMap {
+    regions: Vec<Region>,
+}
+
+impl MemoryMap {
+    /// Sorts `regions` by base. Fails if two of them overlap.
+    pub fn from_regions(mut regions: Vec<Region>) -> Result<Self, MemoryMapError> {
+        regions.sort_by_key(|region| region.base);
+
+        if let Some(pair) = regions.windows(2).find(|pair| pair[0].end() > pair[1].base) {
+            return Err(MemoryMapError::Overlap(pair[1].base));
+        }
+
+        Ok(Self { regions })
+    }
+
+    /// Every region of the process behind `process`, free ones included.
+    #[cfg(windows)]
+    pub fn from_process(process: winapi::shared::ntdef::HANDLE) -> Result<Self, MemoryMapError> {
+        Self::from_regions(crate::sys::memory_map::regions(process))
+    }
+
+    #[cfg(windows)]
+    pub fn current() -> Result<Self, MemoryMapError> {
+        Self::from_process(ntapi::ntpsapi::NtCurrentProcess)
+    }
+
+    /// Reads `/proc/<pid>/maps`.
+    #[cfg(target_os = "linux")]
+    pub fn from_pid(pid: u32) -> Result<Self, MemoryMapError> {
+        let maps = crate::sys::memory_map::read_maps(Some(pid))?;
+        Self::parse_proc_maps(&String::from_utf8_lossy(&maps))
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn current() -> Result<Self, MemoryMapError> {
+        let maps = crate::sys::memory_map::read_maps(None)?;
+        Self::parse_proc_maps(&String::from_utf8_lossy(&maps))
+    }
+
+    /// Parses the text of a Linux `/proc/<pid>/maps` file. Every mapping is
+    /// committed; file-backed and shared ones are [`RegionType::Mapped`].
+    pub fn parse_proc_maps(text: &str) -> Result<Self, MemoryMapError> {
+        let mut regions = Vec::new();
+
+        for (index, line) in text.lines().enumerate() {
+            if line.is_empty() {
+                continue;
+            }
+            let region = parse_maps_line(line).ok_or(MemoryMapError::Syntax(index + 1))?;
+            regions.push(region);
+        }
+
+        Self::from_regions(regions)
+    }
+
+    /// Reads the [`Display`](fmt::Display) form back.
+    pub fn parse(text: &str) -> Result<Self, MemoryMapError> {
+        let mut lines = text.lines().enumerate();
+        if lines.next().map(|(_, line)| line.trim_end()) != Some(HEADER) {
+            return Err(MemoryMapError::UnsupportedVersion);
+        }
+
+        let mut regions = Vec::new();
+        for (index, line) in lines {
+            if line.is_empty() {
+                continue;
+            }
+            let region = parse_region_line(line).ok_or(MemoryMapError::Syntax(index + 1))?;
+            regions.push(region);
+        }
+
+        Self::from_regions(regions)
+    }
+
+    pub fn regions(&self) -> &[Region] {
+        &self.regions
+    }
+
+    pub fn iter(&self) -> core::slice::Iter<'_, Region> {
+        self.regions.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.regions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+
+    /// The region holding `address`.
+    pub fn find(&self, address: usize) -> Option<&Region> {
+        let index = self.regions.partition_point(|region| region.end() <= address);
+        self.regions.get(index).filter(|region| region.contains(address))
+    }
+
+    /// Merges neighbours that touch and have the same attributes.
+    pub fn coalesce(&self) -> Self {
+        let mut regions: Vec<Region> = Vec::with_capacity(self.regions.len());
+
+        for region in &self.regions {
+            match regions.last_mut() {
+                Some(last) if last.end() == region.base && last.same_attributes(region) => {
+                    last.size += region.size;
+                }
+                _ => regions.push(region.clone()),
+            }
+        }
+
+        Self { regions }
+    }
+
+    /// Regions with at least the flags of `protection`.
+    pub fn with_protection(&self, protection: Protection) -> impl Iterator<Item = &Region> + '_ {
+        self.regions.iter().filter(move |region| region.protection.contains(protection))
+    }
+
+    /// What changed from `self` to `after`, in address order.
+    pub fn diff<'a>(&'a self, after: &'a MemoryMap) -> Vec<RegionChange<'a>> {
+        let mut changes = Vec::new();
+        let (mut old, mut new) = (self.regions.iter().peekable(), after.regions.iter().peekable());
+
+        loop {
+            match (old.peek(), new.peek()) {
+                (Some(before), Some(now)) if before.base == now.base => {
+                    if before != now {
+                        changes.push(RegionChange::Changed { before, after: now });
+                    }
+                    old.next();
+                    new.next();
+                }
+                (Some(before), Some(now)) if before.base < now.base => {
+                    changes.push(RegionChange::Removed(before));
+                    old.next();
+                }
+                (_, Some(now)) => {
+                    changes.push(RegionChange::Added(now));
+                    new.next();
+                }
+                (Some(before), None) => {
+                    changes.push(RegionChange::Removed(before));
+                    old.next();
+                }
+                (None, None) => break,
+            }
+        }
+
+        changes
+    }
+}
+
+impl<'a> IntoIterator for &'a MemoryMap {
+    type Item = &'a Region;
+    type IntoIter = core::slice::Iter<'a, Region>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.regions.iter()
+    }
+}
+
+impl fmt::Display for MemoryMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", HEADER)?;
+
+        for region in &self.regions {
+            write!(
+                f,
+                "{:x} {:x} {} {} {}",
+                region.base,
+                region.size,
+                region.protection,
+                region.state.as_str(),
+                region.kind.as_str()
+            )?;
+            if let Some(file) = &region.file {
+                // Newlines are escaped the way `/proc/<pid>/maps` does.
+                write!(f, " ")?;
+                for part in file.split_inclusive('\n') {
+                    match part.strip_suffix('\n') {
+                        Some(part) => write!(f, "{}\\012", part)?,
+                        None => write!(f, "{}", part)?,
+                    }
+                }
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for MemoryMap {
+    type Err = MemoryMapError;
+
+    fn from_str(s: &str) -> Result<Self, MemoryMapError> {
+        Self::parse(s)
+    }
+}
+
+/// `start-end perms offset dev inode [path]`, path padded with spaces.
+fn parse_maps_line(line: &str) -> Option<Region> {
+    let mut fields = line.splitn(6, ' ');
+    let (start, end) = fields.next()?.split_once('-')?;
+    let perms = fields.next()?.as_bytes();
+    let (_offset, _dev, _inode) = (fields.next()?, fields.next()?, fields.next()?);
+    let path = fields.next().map(str::trim_start).filter(|path| !path.is_empty());
+
+    let base = usize::from_str_radix(start, 16).ok()?;
+    let end = usize::from_str_radix(end, 16).ok()?;
+    if perms.len() != 4 || end < base {
+        return None;
+    }
+
+    let mut protection = Protection::NONE;
+    if perms[0] == b'r' {
+        protection = protection | Protection::READ;
+    }
+    if perms[1] == b'w' {
+        protection = protection | Protection::WRITE;
+    }
+    if perms[2] == b'x' {
+        protection = protection | Protection::EXECUTE;
+    }
+
+    let shared = perms[3] == b's';
+    let file_backed = path.is_some_and(|path| !path.starts_with('['));
+    if file_backed && !shared && perms[1] == b'w' {
+        protection = protection | Protection::COPY_ON_WRITE;
+    }
+
+    Some(Region {
+        base,
+        size: end - base,
+        protection,
+        state: RegionState::Committed,
+        kind: if file_backed || shared { RegionType::Mapped } else { RegionType::Private },
+        file: path.map(unescape),
+    })
+}
+
+/// `base size protection state type [file]`.
+fn parse_region_line(line: &str) -> Option<Region> {
+    let mut fields = line.splitn(6, ' ');
+    let base = usize::from_str_radix(fields.next()?, 16).ok()?;
+    let size = usize::from_str_radix(fields.next()?, 16).ok()?;
+    let protection = fields.next()?.parse().ok()?;
+    let state = match fields.next()? {
+        "commit" => RegionState::Committed,
+        "reserve" => RegionState::Reserved,
+        "free" => RegionState::Free,
+        _ => return None,
+    };
+    let kind = match fields.next()? {
+        "private" => RegionType::Private,
+        "mapped" => RegionType::Mapped,
+        "image" => RegionType::Image,
+        "none" => RegionType::None,
+        _ => return None,
+    };
+
+    base.checked_add(size)?;
+
+    Some(Region { base, size, protection, state, kind, file: fields.next().map(unescape) })
+}
+
+fn unescape(file: &str) -> String {
+    file.replace("\\012", "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec;
+    use std::format;
+
+    const MAPS: &str = "\
+55d0c8a00000-55d0c8a02000 r--p 00000000 08:01 1311 /usr/bin/cat
+55d0c8a02000-55d0c8a06000 r-xp 00002000 08:01 1311 /usr/bin/cat
+55d0c8a0b000-55d0c8a0c000 rw-p 0000a000 08:01 1311 /usr/bin/cat
+55d0c9e3a000-55d0c9e5b000 rw-p 00000000 00:00 0                          [heap]
+7f1c2a000000-7f1c2a021000 rw-s 00000000 00:05 3                          /dev/shm/with space
+7ffd3a1c0000-7ffd3a1e1000 rw-p 00000000 00:00 0                          [stack]
+7ffd3a1f0000-7ffd3a1f4000 rw-p 00000000 00:00 0
+7ffd3a1f4000-7ffd3a1f8000 rw-p 00000000 00:00 0
+";
+
+    fn region(base: usize, size: usize, protection: Protection) -> Region {
+        Region {
+            base,
+            size,
+            protection,
+            state: RegionState::Committed,
+            kind: RegionType::Private,
+            file: None,
+        }
+    }
+
+    #[test]
+    fn parses_proc_maps() {
+        let map = MemoryMap::parse_proc_maps(MAPS).unwrap();
+        assert_eq!(map.len(), 8);
+
+        let text = &map.regions()[1];
+        assert_eq!(text.base, 0x55d0c8a02000);
+        assert_eq!(text.size, 0x4000);
+        assert_eq!(text.protection, Protection::READ | Protection::EXECUTE);
+        assert_eq!(text.kind, RegionType::Mapped);
+        assert_eq!(text.file.as_deref(), Some("/usr/bin/cat"));
+
+        let data = &map.regions()[2];
+        assert_eq!(data.protection, Protection::READ | Protection::WRITE | Protection::COPY_ON_WRITE);
+
+        let heap = &map.regions()[3];
+        assert_eq!(heap.kind, RegionType::Private);
+        assert_eq!(heap.protection, Protection::READ | Protection::WRITE);
+        assert_eq!(heap.file.as_deref(), Some("[heap]"));
+
+        let shared = &map.regions()[4];
+        assert_eq!(shared.kind, RegionType::Mapped);
+        assert_eq!(shared.file.as_deref(), Some("/dev/shm/with space"));
+
+        assert_eq!(map.regions()[6].file, None);
+    }
+
+    #[test]
+    fn rejects_bad_lines() {
+        assert_eq!(MemoryMap::parse_proc_maps("55d0c8a00000 r--p"), Err(MemoryMapError::Syntax(1)));
+        assert_eq!(
+            MemoryMap::parse_proc_maps("2000-1000 r--p 0 0:0 0\n"),
+            Err(MemoryMapError::Syntax(1))
+        );
+        assert_eq!(
+            MemoryMap::parse_proc_maps("1000-3000 r--p 0 0:0 0\n2000-4000 r--p 0 0:0 0\n"),
+            Err(MemoryMapError::Overlap(0x2000))
+        );
+    }
+
+    #[test]
+    fn finds_by_address() {
+        let map = MemoryMap::parse_proc_maps(MAPS).unwrap();
+        assert_eq!(map.find(0x55d0c8a02000).unwrap().base, 0x55d0c8a02000);
+        assert_eq!(map.find(0x55d0c8a05fff).unwrap().base, 0x55d0c8a02000);
+        assert_eq!(map.find(0x55d0c8a06000), None);
+        assert_eq!(map.find(0), None);
+        assert_eq!(map.find(usize::MAX), None);
+    }
+
+    #[test]
+    fn coalesces_touching_regions() {
+        let map = MemoryMap::parse_proc_maps(MAPS).unwrap().coalesce();
+        assert_eq!(map.len(), 7);
+
+        let merged = map.find(0x7ffd3a1f0000).unwrap();
+        assert_eq!(merged.size, 0x8000);
+
+        // Touching but different protection.
+        assert_eq!(map.find(0x55d0c8a00000).unwrap().size, 0x2000);
+    }
+
+    #[test]
+    fn filters_by_protection() {
+        let map = MemoryMap::parse_proc_maps(MAPS).unwrap();
+        let executable: Vec<_> = map.with_protection(Protection::EXECUTE).collect();
+        assert_eq!(executable.len(), 1);
+        assert_eq!(map.with_protection(Protection::READ | Protection::WRITE).count(), 6);
+        assert_eq!(map.with_protection(Protection::NONE).count(), map.len());
+    }
+
+    #[test]
+    fn diffs_snapshots() {
+        let rw = Protection::READ | Protection::WRITE;
+        let before = MemoryMap::from_regions(vec![
+            region(0x1000, 0x1000, Protection::READ),
+            region(0x3000, 0x1000, rw),
+            region(0x5000, 0x1000, rw),
+        ])
+        .unwrap();
+        let after = MemoryMap::from_regions(vec![
+            region(0x1000, 0x1000, Protection::READ),
+            region(0x3000, 0x2000, rw),
+            region(0x6000, 0x1000, Protection::EXECUTE),
+        ])
+        .unwrap();
+
+        let changes = before.diff(&after);
+        assert_eq!(
+            changes,
+            [
+                RegionChange::Changed { before: &before.regions()[1], after: &after.regions()[1] },
+                RegionChange::Removed(&before.regions()[2]),
+                RegionChange::Added(&after.regions()[2]),
+            ]
+        );
+        assert!(after.diff(&after).is_empty());
+    }
+
+    #[test]
+    fn serializes_round_trip() {
+        let mut regions = MemoryMap::parse_proc_maps(MAPS).unwrap().regions().to_vec();
+        regions.push(Region {
+            base: 0x7fff_0000_0000,
+            size: 0x10000,
+            protection: Protection::READ | Protection::GUARD,
+            state: RegionState::Reserved,
+            kind: RegionType::Image,
+            file: Some("C:\\Windows\\odd\nname.dll".to_string()),
+        });
+        let map = MemoryMap::from_regions(regions).unwrap();
+
+        let text = format!("{}", map);
+        assert!(text.starts_with("memory-map v1\n55d0c8a00000 2000 r---- commit mapped /usr/bin/cat\n"));
+        assert!(text.contains("r---g reserve image C:\\Windows\\odd\\012name.dll\n"));
+        assert_eq!(text.parse::<MemoryMap>().unwrap(), map);
+    }
+
+    #[test]
+    fn rejects_bad_snapshots() {
+        assert_eq!(MemoryMap::parse(""), Err(MemoryMapError::UnsupportedVersion));
+        assert_eq!(MemoryMap::parse("memory-map v2\n"), Err(MemoryMapError::UnsupportedVersion));
+        assert_eq!(MemoryMap::parse("memory-map v1\n1000 1000 rwz-- commit private\n"), Err(MemoryMapError::Syntax(2)));
+        assert_eq!(MemoryMap::parse("memory-map v1\n1000 1000 rw--- busy private\n"), Err(MemoryMapError::Syntax(2)));
+        assert_eq!(MemoryMap::parse("memory-map v1\n").unwrap(), MemoryMap::default());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn snapshots_current_process() {
+        let local = 0u64;
+        let map = MemoryMap::current().unwrap();
+
+        let stack = map.find(&local as *const u64 as usize).unwrap();
+        assert!(stack.protection.contains(Protection::READ | Protection::WRITE));
+
+        let code = map.find(snapshots_current_process as *const () as usize).unwrap();
+        assert!(code.protection.contains(Protection::EXECUTE));
+        assert_eq!(code.kind, RegionType::Mapped);
+    }
+}
@@ -8,7 +8,7 @@ use winapi::shared::ntstatus::STATUS_INVALID_HANDLE;
 use winapi::um::consoleapi::{GetConsoleMode, GetConsoleOutputCP, WriteConsoleW};
 use winapi::um::fileapi::GetFileType;
 use crate::{Mutex, get_peb};
-use crate::{println, u16_stack_string::U16CStackString};
+use crate::u16_stack_string::U16CStackString;
 use winapi::um::winbase::FILE_TYPE_CHAR;
 
 pub const STD_OUTPUT_HANDLE: u32 = 0xFFFFFFF5;
@@ -221,25 +221,3 @@ impl Write for NtConsole {
         Ok(())
     }
 }
-
-#[macro_export]
-macro_rules! print {
-    ($($arg:tt)*) => {{
-        use core::fmt::Write;
-        let mut console = $crate::NtConsole;
-        let _ = core::fmt::Write::write_fmt(&mut console, core::format_args!($($arg)*));
-    }};
-}
-
-#[macro_export]
-macro_rules! println {
-    () => {
-        $crate::print!("\r\n")
-    };
-    ($($arg:tt)*) => {{
-        use core::fmt::Write;
-        let mut console = $crate::NtConsole;
-        let _ = core::fmt::Write::write_fmt(&mut console, core::format_args!($($arg)*));
-        let _ = core::fmt::Write::write_str(&mut console, "\r\n");
-    }};
-}
\ No newline at end of file
@@ -0,0 +1,221 @@
+use core::str;
+
+use crate::PeError;
+
+const DOS_SIGNATURE: &[u8; 2] = b"MZ";
+const NT_SIGNATURE: &[u8; 4] = b"PE\0\0";
+const PE32_MAGIC: u16 = 0x10B;
+const PE32_PLUS_MAGIC: u16 = 0x20B;
+const SECTION_HEADER_SIZE: usize = 40;
+
+/// How the bytes of a [`PeImage`] are laid out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// As stored on disk, sections at their `PointerToRawData`.
+    File,
+    /// As mapped by the loader, an RVA is an offset into the buffer.
+    Mapped,
+}
+
+/// Read-only view over a PE32 or PE32+ image that only touches the headers
+/// and the export directory. Every offset is bounds-checked against the buffer.
+#[derive(Clone, Copy)]
+pub struct PeImage<'a> {
+    bytes: &'a [u8],
+    layout: Layout,
+    sections: usize,
+    section_count: usize,
+    export_rva: u32,
+    export_size: u32,
+}
+
+/// One named export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Export<'a> {
+    pub name: &'a str,
+    pub ordinal: u16,
+    pub rva: u32,
+    /// The RVA points at a `dll.name` forwarder string, not at code.
+    pub forwarded: bool,
+}
+
+impl<'a> PeImage<'a> {
+    /// Parses an image read from disk.
+    pub fn parse(bytes: &'a [u8]) -> Result<Self, PeError> {
+        Self::with_layout(bytes, Layout::File)
+    }
+
+    /// Parses an image as mapped in memory, e.g. a module of the current process.
+    pub fn parse_mapped(bytes: &'a [u8]) -> Result<Self, PeError> {
+        Self::with_layout(bytes, Layout::Mapped)
+    }
+
+    pub fn with_layout(bytes: &'a [u8], layout: Layout) -> Result<Self, PeError> {
+        if bytes.get(..2) != Some(DOS_SIGNATURE) {
+            return Err(PeError::BadDosSignature);
+        }
+
+        let nt = read_u32(bytes, 0x3C)? as usize;
+        if bytes.get(nt..nt + 4) != Some(NT_SIGNATURE) {
+            return Err(PeError::BadNtSignature);
+        }
+
+        let file_header = nt + 4;
+        let section_count = read_u16(bytes, file_header + 2)? as usize;
+        let optional_size = read_u16(bytes, file_header + 16)? as usize;
+        let optional = file_header + 20;
+
+        let directories = match read_u16(bytes, optional)? {
+            PE32_MAGIC => optional + 96,
+            PE32_PLUS_MAGIC => optional + 112,
+            _ => return Err(PeError::UnsupportedFormat),
+        };
+        let directory_count = read_u32(bytes, directories - 4)?;
+
+        let (export_rva, export_size) = if directory_count > 0 {
+            (read_u32(bytes, directories)?, read_u32(bytes, directories + 4)?)
+        } else {
+            (0, 0)
+        };
+
+        let sections = optional + optional_size;
+        if sections + section_count * SECTION_HEADER_SIZE > bytes.len() {
+            return Err(PeError::Truncated);
+        }
+
+        Ok(Self { bytes, layout, sections, section_count, export_rva, export_size })
+    }
+
+    pub fn bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    /// Buffer offset of `rva`, `None` if it is outside the image.
+    pub fn rva_to_offset(&self, rva: u32) -> Option<usize> {
+        let offset = match self.layout {
+            Layout::Mapped => rva as usize,
+            Layout::File => (0..self.section_count).find_map(|index| {
+                let header = self.sections + index * SECTION_HEADER_SIZE;
+                let virtual_address = read_u32(self.bytes, header + 12).ok()?;
+                let raw_size = read_u32(self.bytes, header + 16).ok()?;
+                let raw_pointer = read_u32(self.bytes, header + 20).ok()?;
+
+                // Past `SizeOfRawData` the section is zero-fill with no file bytes.
+                let delta = rva.checked_sub(virtual_address)?;
+                (delta < raw_size).then_some(raw_pointer as usize + delta as usize)
+            })?,
+        };
+
+        (offset < self.bytes.len()).then_some(offset)
+    }
+
+    /// Up to `len` bytes at `rva`, shorter when the image ends first.
+    pub fn slice_at(&self, rva: u32, len: usize) -> Option<&'a [u8]> {
+        let offset = self.rva_to_offset(rva)?;
+        let end = offset.saturating_add(len).min(self.bytes.len());
+        Some(&self.bytes[offset..end])
+    }
+
+    /// Named exports in name-table order, which the linker keeps sorted.
+    pub fn exports(&self) -> Result<Exports<'a>, PeError> {
+        if self.export_rva == 0 || self.export_size == 0 {
+            return Err(PeError::NoExports);
+        }
+
+        let directory = self.rva_to_offset(self.export_rva).ok_or(PeError::BadRva)?;
+        let function_count = read_u32(self.bytes, directory + 20)?;
+        let name_count = read_u32(self.bytes, directory + 24)?;
+        let functions = self.rva_to_offset(read_u32(self.bytes, directory + 28)?).ok_or(PeError::BadRva)?;
+        let names = self.rva_to_offset(read_u32(self.bytes, directory + 32)?).ok_or(PeError::BadRva)?;
+        let ordinals = self.rva_to_offset(read_u32(self.bytes, directory + 36)?).ok_or(PeError::BadRva)?;
+
+        Ok(Exports {
+            image: *self,
+            functions,
+            function_count,
+            names,
+            ordinals,
+            index: 0,
+            count: name_count,
+        })
+    }
+
+    /// Linear search by exact name.
+    pub fn export(&self, name: &str) -> Result<Option<Export<'a>>, PeError> {
+        for export in self.exports()? {
+            let export = export?;
+            if export.name == name {
+                return Ok(Some(export));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// Iterator over [`PeImage::exports`]. Yields an error and stops at the
+/// first entry pointing outside the image.
+pub struct Exports<'a> {
+    image: PeImage<'a>,
+    functions: usize,
+    function_count: u32,
+    names: usize,
+    ordinals: usize,
+    index: u32,
+    count: u32,
+}
+
+impl<'a> Exports<'a> {
+    fn read(&self, index: usize) -> Result<Export<'a>, PeError> {
+        let bytes = self.image.bytes;
+
+        let name_rva = read_u32(bytes, self.names + index * 4)?;
+        let ordinal = read_u16(bytes, self.ordinals + index * 2)?;
+        if ordinal as u32 >= self.function_count {
+            return Err(PeError::BadRva);
+        }
+        let rva = read_u32(bytes, self.functions + ordinal as usize * 4)?;
+
+        let name = self.image.rva_to_offset(name_rva).ok_or(PeError::BadRva)?;
+        let name = &bytes[name..];
+        let name = &name[..name.iter().position(|&byte| byte == 0).ok_or(PeError::Truncated)?];
+        let name = str::from_utf8(name).map_err(|_| PeError::BadName)?;
+
+        let forwarded = rva.wrapping_sub(self.image.export_rva) < self.image.export_size;
+
+        Ok(Export { name, ordinal, rva, forwarded })
+    }
+}
+
+impl<'a> Iterator for Exports<'a> {
+    type Item = Result<Export<'a>, PeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.count {
+            return None;
+        }
+
+        let export = self.read(self.index as usize);
+        self.index = if export.is_ok() { self.index + 1 } else { self.count };
+        Some(export)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = (self.count - self.index) as usize;
+        (0, Some(left))
+    }
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Result<u16, PeError> {
+    let bytes = bytes.get(offset..offset + 2).ok_or(PeError::Truncated)?;
+    Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Result<u32, PeError> {
+    let bytes = bytes.get(offset..offset + 4).ok_or(PeError::Truncated)?;
+    Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
@@ -1,20 +1,22 @@
 const BLOCK_SIZE: usize = 64;
+const BLOCK_WORDS: u128 = (BLOCK_SIZE / 4) as u128;
 const KEY_SIZE: usize = 32;
-const NONCE_SIZE: usize = 12;
-const ROUNDS: usize = 20;
 
+/// Original (djb) layout: words 12-13 hold a 64-bit block counter and words
+/// 14-15 a 64-bit stream id. The RFC 8439 layout (32-bit counter, 96-bit nonce)
+/// maps onto it by packing the first nonce word into the counter's high half.
 #[repr(align(16))]
 pub struct ChaChaState([u32; 16]);
 
 impl ChaChaState {
-    fn new(key: &[u8; KEY_SIZE], nonce: &[u8; NONCE_SIZE]) -> Self {
+    fn new(key: &[u8; KEY_SIZE], stream: u64) -> Self {
         let mut state = [0u32; 16];
-        
+
         state[0] = 0x61707865;
         state[1] = 0x3320646E;
         state[2] = 0x79622D32;
         state[3] = 0x6B206574;
-        
+
         for i in 0..8 {
             state[4 + i] = u32::from_le_bytes([
                 key[i * 4],
@@ -23,40 +25,60 @@ impl ChaChaState {
                 key[i * 4 + 3],
             ]);
         }
-        
-        state[12] = 0;
-        state[13] = 0;
-        state[14] = u32::from_le_bytes([
-            nonce[0], nonce[1], nonce[2], nonce[3]
-        ]);
-        state[15] = u32::from_le_bytes([
-            nonce[4], nonce[5], nonce[6], nonce[7]
-        ]);
-        
-        ChaChaState(state)
-    }
-    
+
+        let mut state = ChaChaState(state);
+        state.set_counter(0);
+        state.set_stream(stream);
+        state
+    }
+
+    fn counter(&self) -> u64 {
+        (self.0[13] as u64) << 32 | self.0[12] as u64
+    }
+
+    fn set_counter(&mut self, counter: u64) {
+        self.0[12] = counter as u32;
+        self.0[13] = (counter >> 32) as u32;
+    }
+
+    fn stream(&self) -> u64 {
+        (self.0[15] as u64) << 32 | self.0[14] as u64
+    }
+
+    fn set_stream(&mut self, stream: u64) {
+        self.0[14] = stream as u32;
+        self.0[15] = (stream >> 32) as u32;
+    }
+
+    fn key(&self) -> [u8; KEY_SIZE] {
+        let mut key = [0u8; KEY_SIZE];
+        for i in 0..8 {
+            key[i * 4..i * 4 + 4].copy_from_slice(&self.0[4 + i].to_le_bytes());
+        }
+        key
+    }
+
     fn quarter_round(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
         state[a] = state[a].wrapping_add(state[b]);
         state[d] ^= state[a];
         state[d] = state[d].rotate_left(16);
-        
+
         state[c] = state[c].wrapping_add(state[d]);
         state[b] ^= state[c];
         state[b] = state[b].rotate_left(12);
-        
+
         state[a] = state[a].wrapping_add(state[b]);
         state[d] ^= state[a];
         state[d] = state[d].rotate_left(8);
-        
+
         state[c] = state[c].wrapping_add(state[d]);
         state[b] ^= state[c];
         state[b] = state[b].rotate_left(7);
     }
-        
-    fn inner_block(&self) -> [u32; 16] {
+
+    fn inner_block<const ROUNDS: usize>(&self) -> [u32; 16] {
         let mut working = self.0;
-        
+
         for _ in 0..ROUNDS / 2 {
             Self::quarter_round(&mut working, 0, 4, 8, 12);
             Self::quarter_round(&mut working, 1, 5, 9, 13);
@@ -67,22 +89,19 @@ impl ChaChaState {
             Self::quarter_round(&mut working, 2, 7, 8, 13);
             Self::quarter_round(&mut working, 3, 4, 9, 14);
         }
-        
+
         for i in 0..16 {
             working[i] = working[i].wrapping_add(self.0[i]);
         }
-        
+
         working
     }
-    
-    fn next_block(&mut self) -> [u8; BLOCK_SIZE] {
-        let output_state = self.inner_block();
-        
-        self.0[12] = self.0[12].wrapping_add(1);
-        if self.0[12] == 0 {
-            self.0[13] = self.0[13].wrapping_add(1);
-        }
-        
+
+    fn next_block<const ROUNDS: usize>(&mut self) -> [u8; BLOCK_SIZE] {
+        let output_state = self.inner_block::<ROUNDS>();
+
+        self.set_counter(self.counter().wrapping_add(1));
+
         let mut output = [0u8; BLOCK_SIZE];
         for i in 0..16 {
             let bytes = output_state[i].to_le_bytes();
@@ -96,66 +115,232 @@ impl ChaChaState {
     }
 }
 
-pub struct ChaChaRng {
+/// ChaCha keystream generator with `ROUNDS` of 8, 12 or 20.
+///
+/// The output is fully determined by seed, stream and word position, so a run
+/// can be replayed with [`ChaChaRng::set_word_pos`] and split across threads by
+/// giving each one its own stream.
+pub struct ChaChaRng<const ROUNDS: usize = 20> {
     state: ChaChaState,
     buffer: [u8; BLOCK_SIZE],
     index: usize,
 }
 
-impl ChaChaRng {
-    pub fn from_seed(seed: &[u8; KEY_SIZE]) -> Self {
-        let nonce = [0u8; NONCE_SIZE];
-        let state = ChaChaState::new(seed, &nonce);
-        let mut rng = ChaChaRng {
-            state,
+pub type ChaCha8Rng = ChaChaRng<8>;
+pub type ChaCha12Rng = ChaChaRng<12>;
+pub type ChaCha20Rng = ChaChaRng<20>;
+
+// Seed arrays here use a literal length: a named const would make
+// generic_const_exprs treat it as generic and break callers in other crates.
+impl<const ROUNDS: usize> ChaChaRng<ROUNDS> {
+    const VALID_ROUNDS: () = assert!(ROUNDS == 8 || ROUNDS == 12 || ROUNDS == 20, "ChaCha supports 8, 12 or 20 rounds");
+
+    pub fn from_seed(seed: &[u8; 32]) -> Self {
+        Self::from_seed_and_stream(seed, 0)
+    }
+
+    pub fn from_seed_and_stream(seed: &[u8; 32], stream: u64) -> Self {
+        let () = Self::VALID_ROUNDS;
+
+        ChaChaRng {
+            state: ChaChaState::new(seed, stream),
             buffer: [0u8; BLOCK_SIZE],
             index: BLOCK_SIZE,
-        };
-        rng.refill();
-        rng
+        }
     }
-    
+
+    pub fn get_seed(&self) -> [u8; 32] {
+        self.state.key()
+    }
+
+    pub fn get_stream(&self) -> u64 {
+        self.state.stream()
+    }
+
+    /// Switches to another stream at the same word position.
+    pub fn set_stream(&mut self, stream: u64) {
+        let word_pos = self.get_word_pos();
+        self.state.set_stream(stream);
+        self.set_word_pos(word_pos);
+    }
+
+    /// Index of the next 32-bit word in the keystream; 68 bits are significant.
+    pub fn get_word_pos(&self) -> u128 {
+        let counter = self.state.counter() as u128;
+
+        if self.index >= BLOCK_SIZE {
+            counter * BLOCK_WORDS
+        } else {
+            // `buffer` holds the block before the one the counter points to.
+            (counter.wrapping_sub(1) & u64::MAX as u128) * BLOCK_WORDS + (self.index / 4) as u128
+        }
+    }
+
+    pub fn set_word_pos(&mut self, word_pos: u128) {
+        let block = (word_pos / BLOCK_WORDS) as u64;
+        let word = (word_pos % BLOCK_WORDS) as usize;
+
+        self.state.set_counter(block);
+        if word == 0 {
+            self.index = BLOCK_SIZE;
+        } else {
+            self.refill();
+            self.index = word * 4;
+        }
+    }
+
     fn refill(&mut self) {
-        self.buffer = self.state.next_block();
+        self.buffer = self.state.next_block::<ROUNDS>();
         self.index = 0;
     }
-    
+
     pub fn next_u32(&mut self) -> u32 {
         if self.index + 4 > BLOCK_SIZE {
             self.refill();
         }
-        
+
         let bytes: [u8; 4] = self.buffer[self.index..self.index + 4].try_into().unwrap();
         self.index += 4;
         u32::from_le_bytes(bytes)
     }
-    
+
+    /// Low word first; may straddle two blocks.
     pub fn next_u64(&mut self) -> u64 {
-        if self.index + 8 > BLOCK_SIZE {
-            self.refill();
-        }
-        
-        let bytes: [u8; 8] = self.buffer[self.index..self.index + 8].try_into().unwrap();
-        self.index += 8;
-        u64::from_le_bytes(bytes)
+        let low = self.next_u32() as u64;
+        let high = self.next_u32() as u64;
+        high << 32 | low
     }
-    
+
+    /// Consumes whole words, a trailing partial word is discarded.
     pub fn fill_bytes(&mut self, buffer: &mut [u8]) {
         let mut remaining = buffer.len();
         let mut offset = 0;
-        
+
         while remaining > 0 {
             if self.index >= BLOCK_SIZE {
                 self.refill();
             }
-            
+
             let available = BLOCK_SIZE - self.index;
             let to_copy = if remaining < available { remaining } else { available };
-            
+
             buffer[offset..offset + to_copy].copy_from_slice(&self.buffer[self.index..self.index + to_copy]);
-            self.index += to_copy;
+            self.index += to_copy.next_multiple_of(4);
             offset += to_copy;
             remaining -= to_copy;
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(value: &str) -> [u8; BLOCK_SIZE] {
+        let mut out = [0u8; BLOCK_SIZE];
+        for (i, byte) in out.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&value[i * 2..i * 2 + 2], 16).unwrap();
+        }
+        out
+    }
+
+    fn first_block<const ROUNDS: usize>(rng: &mut ChaChaRng<ROUNDS>) -> [u8; BLOCK_SIZE] {
+        let mut block = [0u8; BLOCK_SIZE];
+        rng.fill_bytes(&mut block);
+        block
+    }
+
+    /// RFC 8439 nonce bytes 0..4 go into the high counter word, 4..12 into the stream.
+    fn rfc8439(key: &[u8; KEY_SIZE], counter: u32, nonce: [u8; 12]) -> ChaCha20Rng {
+        let high = u32::from_le_bytes(nonce[..4].try_into().unwrap()) as u64;
+        let stream = u64::from_le_bytes(nonce[4..].try_into().unwrap());
+        let mut rng = ChaCha20Rng::from_seed_and_stream(key, stream);
+        rng.set_word_pos(((high << 32 | counter as u64) as u128) * BLOCK_WORDS);
+        rng
+    }
+
+    #[test]
+    fn rfc8439_block_function() {
+        let key: [u8; KEY_SIZE] = core::array::from_fn(|i| i as u8);
+        let nonce = [0, 0, 0, 0x09, 0, 0, 0, 0x4a, 0, 0, 0, 0];
+        let mut rng = rfc8439(&key, 1, nonce);
+
+        assert_eq!(
+            first_block(&mut rng),
+            hex("10f1e7e4d13b5915500fdd1fa32071c4c7d1f4c733c068030422aa9ac3d46c4ed2826446079faa0914c2d705d98b02a2b5129cd1de164eb9cbd083e8a2503c4e")
+        );
+    }
+
+    #[test]
+    fn rfc8439_appendix_a1() {
+        let zero = [0u8; KEY_SIZE];
+        let mut one = [0u8; KEY_SIZE];
+        one[31] = 1;
+
+        let cases = [
+            (zero, 0, [0; 12], "76b8e0ada0f13d90405d6ae55386bd28bdd219b8a08ded1aa836efcc8b770dc7da41597c5157488d7724e03fb8d84a376a43b8f41518a11cc387b669b2ee6586"),
+            (zero, 1, [0; 12], "9f07e7be5551387a98ba977c732d080dcb0f29a048e3656912c6533e32ee7aed29b721769ce64e43d57133b074d839d531ed1f28510afb45ace10a1f4b794d6f"),
+            (one, 1, [0; 12], "3aeb5224ecf849929b9d828db1ced4dd832025e8018b8160b82284f3c949aa5a8eca00bbb4a73bdad192b5c42f73f2fd4e273644c8b36125a64addeb006c13a0"),
+            (zero, 0, [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2], "c2c64d378cd536374ae204b9ef933fcd1a8b2288b3dfa49672ab765b54ee27c78a970e0e955c14f3a88e741b97c286f75f8fc299e8148362fa198a39531bed6d"),
+        ];
+
+        for (key, counter, nonce, expected) in cases {
+            assert_eq!(first_block(&mut rfc8439(&key, counter, nonce)), hex(expected));
+        }
+    }
+
+    #[test]
+    fn reduced_rounds_reference() {
+        let zero = [0u8; KEY_SIZE];
+
+        assert_eq!(
+            first_block(&mut ChaCha8Rng::from_seed(&zero)),
+            hex("3e00ef2f895f40d67f5bb8e81f09a5a12c840ec3ce9a7f3b181be188ef711a1e984ce172b9216f419f445367456d5619314a42a3da86b001387bfdb80e0cfe42")
+        );
+        assert_eq!(
+            first_block(&mut ChaCha12Rng::from_seed(&zero)),
+            hex("9bf49a6a0755f953811fce125f2683d50429c3bb49e074147e0089a52eae155f0564f879d27ae3c02ce82834acfa8c793a629f2ca0de6919610be82f411326be")
+        );
+    }
+
+    #[test]
+    fn word_pos_round_trip() {
+        let seed = [7u8; KEY_SIZE];
+        let mut rng = ChaChaRng::<20>::from_seed_and_stream(&seed, 3);
+        assert_eq!(rng.get_word_pos(), 0);
+
+        let mut words = [0u32; 40];
+        for word in &mut words {
+            *word = rng.next_u32();
+        }
+        assert_eq!(rng.get_word_pos(), 40);
+
+        for start in [0u128, 1, 15, 16, 17, 33] {
+            rng.set_word_pos(start);
+            assert_eq!(rng.get_word_pos(), start);
+            assert_eq!(rng.next_u32(), words[start as usize]);
+        }
+
+        rng.set_word_pos(15);
+        assert_eq!(rng.next_u64(), (words[16] as u64) << 32 | words[15] as u64);
+
+        let mut bytes = [0u8; 5];
+        rng.set_word_pos(0);
+        rng.fill_bytes(&mut bytes);
+        assert_eq!(rng.get_word_pos(), 2);
+    }
+
+    #[test]
+    fn streams_are_independent() {
+        let seed = [1u8; KEY_SIZE];
+        let mut a = ChaCha20Rng::from_seed_and_stream(&seed, 0);
+        let mut b = ChaCha20Rng::from_seed_and_stream(&seed, 1);
+        assert_ne!(first_block(&mut a), first_block(&mut b));
+
+        a.set_stream(1);
+        assert_eq!(a.get_stream(), 1);
+        assert_eq!(a.get_word_pos(), b.get_word_pos());
+        assert_eq!(a.next_u64(), b.next_u64());
+        assert_eq!(a.get_seed(), seed);
+    }
+}
@@ -0,0 +1,48 @@
+use super::{Distribution, DistributionError};
+use crate::rand::RngCore;
+
+/// `true` with probability `p`, exact to 2^-64.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bernoulli {
+    threshold: u64,
+}
+
+/// `p == 1.0` cannot be expressed as a threshold below 2^64.
+const ALWAYS_TRUE: u64 = u64::MAX;
+const SCALE: f64 = 2.0 * (1u64 << 63) as f64;
+
+impl Bernoulli {
+    pub fn new(p: f64) -> Result<Self, DistributionError> {
+        if !(0.0..1.0).contains(&p) {
+            if p == 1.0 {
+                return Ok(Self { threshold: ALWAYS_TRUE });
+            }
+            return Err(DistributionError::InvalidProbability);
+        }
+
+        Ok(Self { threshold: (p * SCALE) as u64 })
+    }
+
+    /// `numerator / denominator`, computed without going through `f64` rounding twice.
+    pub fn from_ratio(numerator: u32, denominator: u32) -> Result<Self, DistributionError> {
+        if denominator == 0 || numerator > denominator {
+            return Err(DistributionError::InvalidProbability);
+        }
+
+        if numerator == denominator {
+            return Ok(Self { threshold: ALWAYS_TRUE });
+        }
+
+        Ok(Self { threshold: (((numerator as u128) << 64) / denominator as u128) as u64 })
+    }
+}
+
+impl Distribution<bool> for Bernoulli {
+    fn sample<R: RngCore + ?Sized>(&self, rng: &mut R) -> bool {
+        if self.threshold == ALWAYS_TRUE {
+            return true;
+        }
+
+        rng.next_u64() < self.threshold
+    }
+}
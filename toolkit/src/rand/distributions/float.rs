@@ -0,0 +1,192 @@
+//! Bits to float conversions and the few `libm` functions the samplers need.
+//! They are `const` so the ziggurat tables can be built at compile time.
+
+const F64_SCALE: f64 = 1.0 / (1u64 << 53) as f64;
+const F32_SCALE: f32 = 1.0 / (1u32 << 24) as f32;
+
+/// `[0, 1)` from the top 53 bits.
+pub(crate) fn f64_closed_open(bits: u64) -> f64 {
+    (bits >> 11) as f64 * F64_SCALE
+}
+
+/// `[0, 1]` from the top 53 bits.
+pub(crate) fn f64_closed_closed(bits: u64) -> f64 {
+    (bits >> 11) as f64 / ((1u64 << 53) - 1) as f64
+}
+
+/// `(0, 1)` from the top 52 bits.
+pub(crate) fn f64_open_open(bits: u64) -> f64 {
+    ((bits >> 12) as f64 + 0.5) * (1.0 / (1u64 << 52) as f64)
+}
+
+pub(crate) fn f32_closed_open(bits: u32) -> f32 {
+    (bits >> 8) as f32 * F32_SCALE
+}
+
+pub(crate) fn f32_closed_closed(bits: u32) -> f32 {
+    (bits >> 8) as f32 / ((1u32 << 24) - 1) as f32
+}
+
+/// fdlibm split of ln 2, so `k * LN2_HI` is exact.
+const LN2_HI: f64 = 6.931_471_803_691_238e-1;
+const LN2_LO: f64 = 1.908_214_929_270_587_7e-10;
+
+pub(crate) const fn exp(x: f64) -> f64 {
+    if x.is_nan() {
+        return x;
+    }
+    if x > 709.78 {
+        return f64::INFINITY;
+    }
+    if x < -745.2 {
+        return 0.0;
+    }
+
+    let k = (x / core::f64::consts::LN_2 + if x < 0.0 { -0.5 } else { 0.5 }) as i64;
+    let r = (x - k as f64 * LN2_HI) - k as f64 * LN2_LO;
+
+    // |r| <= ln2 / 2, so 18 terms are far below f64 precision.
+    let mut term = 1.0;
+    let mut sum = 1.0;
+    let mut i = 1;
+    while i < 18 {
+        term = term * r / i as f64;
+        sum += term;
+        i += 1;
+    }
+
+    scale_pow2(sum, k)
+}
+
+const fn scale_pow2(mut value: f64, mut k: i64) -> f64 {
+    while k > 1000 {
+        value *= f64::from_bits(((1000 + 1023) as u64) << 52);
+        k -= 1000;
+    }
+    while k < -1000 {
+        value *= f64::from_bits(((-1000 + 1023) as u64) << 52);
+        k += 1000;
+    }
+    value * f64::from_bits(((k + 1023) as u64) << 52)
+}
+
+/// Natural log for positive finite `x`; `-inf` at zero.
+pub(crate) const fn ln(x: f64) -> f64 {
+    if x.is_nan() || x < 0.0 {
+        return f64::NAN;
+    }
+    if x == 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if x.is_infinite() {
+        return x;
+    }
+
+    let mut x = x;
+    let mut e = 0i64;
+    if x < f64::MIN_POSITIVE {
+        x *= (1u64 << 54) as f64;
+        e -= 54;
+    }
+
+    let bits = x.to_bits();
+    e += ((bits >> 52) & 0x7ff) as i64 - 1023;
+    let mut m = f64::from_bits((bits & 0x000f_ffff_ffff_ffff) | 0x3ff0_0000_0000_0000);
+    if m > core::f64::consts::SQRT_2 {
+        m /= 2.0;
+        e += 1;
+    }
+
+    // ln m = 2 atanh(s) with |s| <= 0.172
+    let s = (m - 1.0) / (m + 1.0);
+    let s2 = s * s;
+    let mut power = s;
+    let mut sum = 0.0;
+    let mut i = 0;
+    while i < 14 {
+        sum += power / (2 * i + 1) as f64;
+        power *= s2;
+        i += 1;
+    }
+
+    e as f64 * LN2_HI + (2.0 * sum + e as f64 * LN2_LO)
+}
+
+/// Accurate for normal positive `x`, which is all the table construction needs.
+pub(crate) const fn sqrt(x: f64) -> f64 {
+    if x <= 0.0 || x.is_nan() || x.is_infinite() {
+        return if x == 0.0 || x.is_infinite() && x > 0.0 { x } else { f64::NAN };
+    }
+
+    // Halving the exponent gets within a factor of two, Newton doubles the digits.
+    let mut guess = f64::from_bits((x.to_bits() >> 1) + (0x3ff << 51));
+    let mut i = 0;
+    while i < 8 {
+        guess = 0.5 * (guess + x / guess);
+        i += 1;
+    }
+    guess
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64, ulps: f64) {
+        let tolerance = expected.abs() * f64::EPSILON * ulps;
+        assert!((actual - expected).abs() <= tolerance, "{} vs {}", actual, expected);
+    }
+
+    const E: f64 = exp(1.0);
+
+    #[test]
+    fn exp_matches_std() {
+        assert_close(E, core::f64::consts::E, 1.0);
+        for i in -7000..7000 {
+            let x = i as f64 * 0.1;
+            assert_close(exp(x), std::primitive::f64::exp(x), 4.0);
+        }
+        assert_eq!(exp(0.0), 1.0);
+        assert_eq!(exp(710.0), f64::INFINITY);
+        assert_eq!(exp(-746.0), 0.0);
+        assert!(exp(f64::NAN).is_nan());
+    }
+
+    #[test]
+    fn ln_matches_std() {
+        for i in 1..20000 {
+            let x = i as f64 * 0.37;
+            assert_close(ln(x), std::primitive::f64::ln(x), 4.0);
+        }
+        assert_close(ln(1e-310), std::primitive::f64::ln(1e-310), 4.0);
+        assert_close(ln(f64::MAX), std::primitive::f64::ln(f64::MAX), 4.0);
+        assert_eq!(ln(1.0), 0.0);
+        assert_eq!(ln(0.0), f64::NEG_INFINITY);
+        assert!(ln(-1.0).is_nan());
+    }
+
+    #[test]
+    fn sqrt_matches_std() {
+        for i in 1..20000 {
+            let x = i as f64 * 1.7e-3;
+            assert_close(sqrt(x), std::primitive::f64::sqrt(x), 1.0);
+        }
+        assert_eq!(sqrt(0.0), 0.0);
+        assert_eq!(sqrt(f64::INFINITY), f64::INFINITY);
+        assert!(sqrt(-1.0).is_nan());
+    }
+
+    #[test]
+    fn unit_interval_bounds() {
+        assert_eq!(f64_closed_open(0), 0.0);
+        assert!(f64_closed_open(u64::MAX) < 1.0);
+        assert_eq!(f64_closed_closed(u64::MAX), 1.0);
+        assert!(f64_open_open(0) > 0.0);
+        assert!(f64_open_open(u64::MAX) < 1.0);
+        assert_eq!(f32_closed_open(0), 0.0);
+        assert!(f32_closed_open(u32::MAX) < 1.0);
+        assert_eq!(f32_closed_closed(u32::MAX), 1.0);
+    }
+}
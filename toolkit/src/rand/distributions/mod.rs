@@ -0,0 +1,80 @@
+//! Sampling on top of [`RngCore`]. Everything is fixed-size and allocation free.
+
+mod bernoulli;
+mod float;
+mod normal;
+mod uniform;
+mod weighted;
+mod ziggurat;
+
+pub use bernoulli::*;
+pub use normal::*;
+pub use uniform::*;
+pub use weighted::*;
+pub(crate) use uniform::gen_index;
+pub use crate::DistributionError;
+
+use super::RngCore;
+
+pub trait Distribution<T> {
+    fn sample<R: RngCore + ?Sized>(&self, rng: &mut R) -> T;
+}
+
+impl<T, D: Distribution<T> + ?Sized> Distribution<T> for &D {
+    fn sample<R: RngCore + ?Sized>(&self, rng: &mut R) -> T {
+        (**self).sample(rng)
+    }
+}
+
+/// Full range for integers, `[0, 1)` for floats and a fair coin for `bool`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Standard;
+
+macro_rules! standard_int {
+    ($($ty:ty => $next:ident),* $(,)?) => {
+        $(
+            impl Distribution<$ty> for Standard {
+                fn sample<R: RngCore + ?Sized>(&self, rng: &mut R) -> $ty {
+                    rng.$next() as $ty
+                }
+            }
+        )*
+    };
+}
+
+standard_int! {
+    u8 => next_u32, u16 => next_u32, u32 => next_u32, u64 => next_u64, usize => next_u64,
+    i8 => next_u32, i16 => next_u32, i32 => next_u32, i64 => next_u64, isize => next_u64,
+}
+
+impl Distribution<u128> for Standard {
+    fn sample<R: RngCore + ?Sized>(&self, rng: &mut R) -> u128 {
+        let low = rng.next_u64() as u128;
+        (rng.next_u64() as u128) << 64 | low
+    }
+}
+
+impl Distribution<i128> for Standard {
+    fn sample<R: RngCore + ?Sized>(&self, rng: &mut R) -> i128 {
+        Distribution::<u128>::sample(self, rng) as i128
+    }
+}
+
+impl Distribution<bool> for Standard {
+    fn sample<R: RngCore + ?Sized>(&self, rng: &mut R) -> bool {
+        // Top bit: some weak generators have poor low bits.
+        (rng.next_u32() as i32) < 0
+    }
+}
+
+impl Distribution<f32> for Standard {
+    fn sample<R: RngCore + ?Sized>(&self, rng: &mut R) -> f32 {
+        float::f32_closed_open(rng.next_u32())
+    }
+}
+
+impl Distribution<f64> for Standard {
+    fn sample<R: RngCore + ?Sized>(&self, rng: &mut R) -> f64 {
+        float::f64_closed_open(rng.next_u64())
+    }
+}
@@ -0,0 +1,156 @@
+use super::{Distribution, DistributionError, ziggurat};
+use crate::rand::RngCore;
+
+/// Normal with mean 0 and standard deviation 1.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StandardNormal;
+
+impl Distribution<f64> for StandardNormal {
+    fn sample<R: RngCore + ?Sized>(&self, rng: &mut R) -> f64 {
+        ziggurat::normal(rng)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Normal {
+    mean: f64,
+    std_dev: f64,
+}
+
+impl Normal {
+    pub fn new(mean: f64, std_dev: f64) -> Result<Self, DistributionError> {
+        if !mean.is_finite() || !std_dev.is_finite() || std_dev < 0.0 {
+            return Err(DistributionError::InvalidParameter);
+        }
+
+        Ok(Self { mean, std_dev })
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    pub fn std_dev(&self) -> f64 {
+        self.std_dev
+    }
+}
+
+impl Distribution<f64> for Normal {
+    fn sample<R: RngCore + ?Sized>(&self, rng: &mut R) -> f64 {
+        self.mean + self.std_dev * ziggurat::normal(rng)
+    }
+}
+
+/// Exponential with rate 1.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Exp1;
+
+impl Distribution<f64> for Exp1 {
+    fn sample<R: RngCore + ?Sized>(&self, rng: &mut R) -> f64 {
+        ziggurat::exponential(rng)
+    }
+}
+
+/// Exponential with rate `lambda`, mean `1 / lambda`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Exp {
+    inv_lambda: f64,
+}
+
+impl Exp {
+    pub fn new(lambda: f64) -> Result<Self, DistributionError> {
+        if lambda.is_nan() || lambda <= 0.0 || lambda.is_infinite() {
+            return Err(DistributionError::InvalidParameter);
+        }
+
+        Ok(Self { inv_lambda: 1.0 / lambda })
+    }
+}
+
+impl Distribution<f64> for Exp {
+    fn sample<R: RngCore + ?Sized>(&self, rng: &mut R) -> f64 {
+        ziggurat::exponential(rng) * self.inv_lambda
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::{Bernoulli, Standard, Uniform};
+    use crate::rand::ChaCha20Rng;
+
+    const DRAWS: usize = 200_000;
+
+    fn moments<D: Distribution<f64>>(distribution: D) -> (f64, f64) {
+        let mut rng = ChaCha20Rng::from_seed(&[42; 32]);
+        let (mut sum, mut sum_sq) = (0.0, 0.0);
+
+        for _ in 0..DRAWS {
+            let value = distribution.sample(&mut rng);
+            assert!(value.is_finite());
+            sum += value;
+            sum_sq += value * value;
+        }
+
+        let mean = sum / DRAWS as f64;
+        (mean, sum_sq / DRAWS as f64 - mean * mean)
+    }
+
+    /// Five standard errors of the mean, and a loose bound on the variance.
+    fn assert_moments<D: Distribution<f64>>(distribution: D, mean: f64, variance: f64) {
+        let (actual_mean, actual_variance) = moments(distribution);
+        let tolerance = 5.0 * (variance / DRAWS as f64).sqrt();
+
+        assert!((actual_mean - mean).abs() < tolerance, "mean {} vs {}", actual_mean, mean);
+        assert!((actual_variance - variance).abs() < variance * 0.02, "variance {} vs {}", actual_variance, variance);
+    }
+
+    #[test]
+    fn standard_normal() {
+        assert_moments(StandardNormal, 0.0, 1.0);
+    }
+
+    #[test]
+    fn shifted_normal() {
+        assert_moments(Normal::new(-3.0, 2.5).unwrap(), -3.0, 6.25);
+    }
+
+    #[test]
+    fn exponential() {
+        assert_moments(Exp1, 1.0, 1.0);
+        assert_moments(Exp::new(4.0).unwrap(), 0.25, 0.0625);
+    }
+
+    #[test]
+    fn uniform_and_standard() {
+        assert_moments(Uniform::new(2.0, 5.0).unwrap(), 3.5, 0.75);
+
+        struct StandardF64;
+        impl Distribution<f64> for StandardF64 {
+            fn sample<R: RngCore + ?Sized>(&self, rng: &mut R) -> f64 {
+                Standard.sample(rng)
+            }
+        }
+        assert_moments(StandardF64, 0.5, 1.0 / 12.0);
+    }
+
+    #[test]
+    fn bernoulli_rate() {
+        let mut rng = ChaCha20Rng::from_seed(&[42; 32]);
+        let coin = Bernoulli::new(0.3).unwrap();
+        let hits = (0..DRAWS).filter(|_| coin.sample(&mut rng)).count();
+
+        assert!((hits as f64 / DRAWS as f64 - 0.3).abs() < 0.005);
+        assert!(Bernoulli::new(1.0).unwrap().sample(&mut rng));
+        assert!(!Bernoulli::new(0.0).unwrap().sample(&mut rng));
+        assert!(Bernoulli::new(1.5).is_err());
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        assert_eq!(Normal::new(0.0, -1.0), Err(DistributionError::InvalidParameter));
+        assert_eq!(Normal::new(f64::NAN, 1.0), Err(DistributionError::InvalidParameter));
+        assert_eq!(Exp::new(0.0), Err(DistributionError::InvalidParameter));
+        assert_eq!(Exp::new(f64::INFINITY), Err(DistributionError::InvalidParameter));
+    }
+}
@@ -0,0 +1,360 @@
+use core::ops::{Range, RangeInclusive};
+
+use super::{Distribution, DistributionError, float};
+use crate::rand::RngCore;
+
+/// Types [`Uniform`] and [`RngCore::gen_range`] can sample. Callers have
+/// already checked that the range is not empty.
+pub trait SampleUniform: Copy + PartialOrd {
+    fn sample_exclusive<R: RngCore + ?Sized>(low: Self, high: Self, rng: &mut R) -> Self;
+    fn sample_inclusive<R: RngCore + ?Sized>(low: Self, high: Self, rng: &mut R) -> Self;
+
+    /// Rejects bounds the sampler cannot handle beyond the ordering check.
+    fn check_range(_low: Self, _high: Self) -> Result<(), DistributionError> {
+        Ok(())
+    }
+}
+
+/// Uniform over `low..high` or `low..=high`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Uniform<T> {
+    low: T,
+    high: T,
+    inclusive: bool,
+}
+
+impl<T: SampleUniform> Uniform<T> {
+    pub fn new(low: T, high: T) -> Result<Self, DistributionError> {
+        if (low..high).is_empty() {
+            return Err(DistributionError::EmptyRange);
+        }
+
+        T::check_range(low, high)?;
+        Ok(Self { low, high, inclusive: false })
+    }
+
+    pub fn new_inclusive(low: T, high: T) -> Result<Self, DistributionError> {
+        if (low..=high).is_empty() {
+            return Err(DistributionError::EmptyRange);
+        }
+
+        T::check_range(low, high)?;
+        Ok(Self { low, high, inclusive: true })
+    }
+}
+
+impl<T: SampleUniform> Distribution<T> for Uniform<T> {
+    fn sample<R: RngCore + ?Sized>(&self, rng: &mut R) -> T {
+        if self.inclusive {
+            T::sample_inclusive(self.low, self.high, rng)
+        } else {
+            T::sample_exclusive(self.low, self.high, rng)
+        }
+    }
+}
+
+impl<T: SampleUniform> TryFrom<Range<T>> for Uniform<T> {
+    type Error = DistributionError;
+
+    fn try_from(range: Range<T>) -> Result<Self, Self::Error> {
+        Self::new(range.start, range.end)
+    }
+}
+
+impl<T: SampleUniform> TryFrom<RangeInclusive<T>> for Uniform<T> {
+    type Error = DistributionError;
+
+    fn try_from(range: RangeInclusive<T>) -> Result<Self, Self::Error> {
+        let (low, high) = range.into_inner();
+        Self::new_inclusive(low, high)
+    }
+}
+
+/// Ranges accepted by [`RngCore::gen_range`].
+pub trait SampleRange<T> {
+    /// Panics if the range is empty.
+    fn sample_single<R: RngCore + ?Sized>(self, rng: &mut R) -> T;
+
+    fn is_empty(&self) -> bool;
+}
+
+impl<T: SampleUniform> SampleRange<T> for Range<T> {
+    fn sample_single<R: RngCore + ?Sized>(self, rng: &mut R) -> T {
+        assert!(self.start < self.end, "cannot sample empty range");
+        T::check_range(self.start, self.end).expect("invalid range");
+        T::sample_exclusive(self.start, self.end, rng)
+    }
+
+    fn is_empty(&self) -> bool {
+        Range::is_empty(self)
+    }
+}
+
+impl<T: SampleUniform> SampleRange<T> for RangeInclusive<T> {
+    fn sample_single<R: RngCore + ?Sized>(self, rng: &mut R) -> T {
+        assert!(self.start() <= self.end(), "cannot sample empty range");
+        let (low, high) = self.into_inner();
+        T::check_range(low, high).expect("invalid range");
+        T::sample_inclusive(low, high, rng)
+    }
+
+    fn is_empty(&self) -> bool {
+        RangeInclusive::is_empty(self)
+    }
+}
+
+/// Lemire's widening multiply: unbiased value in `0..range`, `range > 0`.
+fn below_u32<R: RngCore + ?Sized>(rng: &mut R, range: u32) -> u32 {
+    let mut product = rng.next_u32() as u64 * range as u64;
+
+    if (product as u32) < range {
+        let threshold = range.wrapping_neg() % range;
+        while (product as u32) < threshold {
+            product = rng.next_u32() as u64 * range as u64;
+        }
+    }
+
+    (product >> 32) as u32
+}
+
+fn below_u64<R: RngCore + ?Sized>(rng: &mut R, range: u64) -> u64 {
+    let mut product = rng.next_u64() as u128 * range as u128;
+
+    if (product as u64) < range {
+        let threshold = range.wrapping_neg() % range;
+        while (product as u64) < threshold {
+            product = rng.next_u64() as u128 * range as u128;
+        }
+    }
+
+    (product >> 64) as u64
+}
+
+/// No 256-bit multiply, so mask to the next power of two and reject.
+fn below_u128<R: RngCore + ?Sized>(rng: &mut R, range: u128) -> u128 {
+    let mask = u128::MAX.checked_shr((range - 1).leading_zeros()).unwrap_or(0);
+
+    loop {
+        let value = next_u128(rng) & mask;
+        if value < range {
+            return value;
+        }
+    }
+}
+
+fn next_u32<R: RngCore + ?Sized>(rng: &mut R) -> u32 {
+    rng.next_u32()
+}
+
+fn next_u64<R: RngCore + ?Sized>(rng: &mut R) -> u64 {
+    rng.next_u64()
+}
+
+fn next_u128<R: RngCore + ?Sized>(rng: &mut R) -> u128 {
+    let low = rng.next_u64() as u128;
+    (rng.next_u64() as u128) << 64 | low
+}
+
+macro_rules! uniform_int {
+    ($($ty:ty => $unsigned:ty, $wide:ty, $below:ident, $full:ident;)*) => {
+        $(
+            impl SampleUniform for $ty {
+                fn sample_exclusive<R: RngCore + ?Sized>(low: Self, high: Self, rng: &mut R) -> Self {
+                    Self::sample_inclusive(low, high - 1, rng)
+                }
+
+                fn sample_inclusive<R: RngCore + ?Sized>(low: Self, high: Self, rng: &mut R) -> Self {
+                    let range = ((high as $unsigned).wrapping_sub(low as $unsigned) as $wide).wrapping_add(1);
+                    // Zero means the range wrapped, i.e. it covers the whole type.
+                    let offset = if range == 0 { $full(rng) } else { $below(rng, range) };
+                    (low as $unsigned).wrapping_add(offset as $unsigned) as $ty
+                }
+            }
+        )*
+    };
+}
+
+uniform_int! {
+    u8 => u8, u32, below_u32, next_u32;
+    u16 => u16, u32, below_u32, next_u32;
+    u32 => u32, u32, below_u32, next_u32;
+    u64 => u64, u64, below_u64, next_u64;
+    usize => usize, u64, below_u64, next_u64;
+    u128 => u128, u128, below_u128, next_u128;
+    i8 => u8, u32, below_u32, next_u32;
+    i16 => u16, u32, below_u32, next_u32;
+    i32 => u32, u32, below_u32, next_u32;
+    i64 => u64, u64, below_u64, next_u64;
+    isize => usize, u64, below_u64, next_u64;
+    i128 => u128, u128, below_u128, next_u128;
+}
+
+macro_rules! uniform_float {
+    ($($ty:ty => $next:ident, $closed_open:path, $closed_closed:path;)*) => {
+        $(
+            impl SampleUniform for $ty {
+                fn sample_exclusive<R: RngCore + ?Sized>(low: Self, high: Self, rng: &mut R) -> Self {
+                    loop {
+                        // Rounding can land exactly on `high`, retry rather than clamp.
+                        let value = low + (high - low) * $closed_open(rng.$next());
+                        if value < high {
+                            return value;
+                        }
+                    }
+                }
+
+                fn sample_inclusive<R: RngCore + ?Sized>(low: Self, high: Self, rng: &mut R) -> Self {
+                    let value = low + (high - low) * $closed_closed(rng.$next());
+                    if value > high { high } else { value }
+                }
+
+                fn check_range(low: Self, high: Self) -> Result<(), DistributionError> {
+                    if (high - low).is_finite() {
+                        Ok(())
+                    } else {
+                        Err(DistributionError::InvalidParameter)
+                    }
+                }
+            }
+        )*
+    };
+}
+
+uniform_float! {
+    f32 => next_u32, float::f32_closed_open, float::f32_closed_closed;
+    f64 => next_u64, float::f64_closed_open, float::f64_closed_closed;
+}
+
+/// Uniform index in `0..len`, `len > 0`. Uses 32-bit sampling when `len` fits so
+/// results do not depend on the pointer width.
+pub(crate) fn gen_index<R: RngCore + ?Sized>(rng: &mut R, len: usize) -> usize {
+    if len <= u32::MAX as usize {
+        below_u32(rng, len as u32) as usize
+    } else {
+        below_u64(rng, len as u64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::ChaCha20Rng;
+
+    /// Replays a fixed list of words, to drive the rejection paths.
+    struct Words<'a>(&'a [u64]);
+
+    impl RngCore for Words<'_> {
+        fn next_u32(&mut self) -> u32 {
+            self.next_u64() as u32
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            let (first, rest) = self.0.split_first().expect("ran out of words");
+            self.0 = rest;
+            *first
+        }
+    }
+
+    fn rng() -> ChaCha20Rng {
+        ChaCha20Rng::from_seed(&[7; 32])
+    }
+
+    #[test]
+    fn below_stays_in_range() {
+        let mut rng = rng();
+        for range in [1, 2, 3, 7, 1000, 1 << 31, u32::MAX - 1, u32::MAX] {
+            for _ in 0..1000 {
+                assert!(below_u32(&mut rng, range) < range);
+            }
+        }
+        for range in [1, 3, 1 << 40, u64::MAX / 3, u64::MAX] {
+            for _ in 0..1000 {
+                assert!(below_u64(&mut rng, range) < range);
+            }
+        }
+        for range in [1, 5, 1 << 100, u128::MAX] {
+            for _ in 0..1000 {
+                assert!(below_u128(&mut rng, range) < range);
+            }
+        }
+    }
+
+    #[test]
+    fn below_hits_both_ends() {
+        let mut rng = rng();
+        let mut seen = [false; 6];
+        for _ in 0..1000 {
+            seen[below_u32(&mut rng, 6) as usize] = true;
+        }
+        assert_eq!(seen, [true; 6]);
+
+        assert_eq!(below_u32(&mut Words(&[1000]), 10), 0);
+        assert_eq!(below_u32(&mut Words(&[u32::MAX as u64]), 10), 9);
+        assert_eq!(below_u64(&mut Words(&[u64::MAX]), 10), 9);
+    }
+
+    #[test]
+    fn below_rejects_the_biased_zone() {
+        // For range 3 the threshold is 2^32 mod 3 = 1: a low product half of
+        // zero is rejected and the next word is used.
+        assert_eq!(below_u32(&mut Words(&[0, u32::MAX as u64]), 3), 2);
+        assert_eq!(below_u64(&mut Words(&[0, u64::MAX]), 3), 2);
+    }
+
+    #[test]
+    fn integer_ranges() {
+        let mut rng = rng();
+        for _ in 0..1000 {
+            let value = i8::sample_inclusive(-128, 127, &mut rng);
+            assert!((-128..=127).contains(&value));
+            assert!((-5..5).contains(&rng.gen_range(-5i32..5)));
+            assert!((10..=12).contains(&rng.gen_range(10u64..=12)));
+            assert!((-3..=-1).contains(&rng.gen_range(-3i128..=-1)));
+        }
+
+        assert_eq!(rng.gen_range(4u8..5), 4);
+        assert_eq!(rng.gen_range(u64::MAX..=u64::MAX), u64::MAX);
+        // A full-width inclusive range wraps to zero and takes the raw word.
+        assert_eq!(u32::sample_inclusive(0, u32::MAX, &mut Words(&[0xdead_beef])), 0xdead_beef);
+        assert_eq!(i64::sample_inclusive(i64::MIN, i64::MAX, &mut Words(&[0])), i64::MIN);
+    }
+
+    #[test]
+    fn float_ranges() {
+        let mut rng = rng();
+        for _ in 0..1000 {
+            let value: f64 = rng.gen_range(-1.5..2.5);
+            assert!((-1.5..2.5).contains(&value));
+            let value: f32 = rng.gen_range(0.0..=1.0);
+            assert!((0.0..=1.0).contains(&value));
+        }
+
+        assert_eq!(f64::sample_inclusive(1.0, 2.0, &mut Words(&[u64::MAX])), 2.0);
+        assert_eq!(f64::sample_exclusive(1.0, 2.0, &mut Words(&[0])), 1.0);
+    }
+
+    #[test]
+    fn invalid_ranges() {
+        assert_eq!(Uniform::new(3, 3), Err(DistributionError::EmptyRange));
+        assert_eq!(Uniform::new_inclusive(4, 3), Err(DistributionError::EmptyRange));
+        assert_eq!(Uniform::new(0.0, f64::NAN), Err(DistributionError::EmptyRange));
+        assert_eq!(Uniform::new(f64::MIN, f64::MAX), Err(DistributionError::InvalidParameter));
+        assert!(Uniform::new_inclusive(3, 3).is_ok());
+        assert!(Uniform::try_from(1u8..=255).is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot sample empty range")]
+    fn gen_range_panics_on_empty() {
+        rng().gen_range(5u32..5);
+    }
+
+    #[test]
+    fn gen_index_is_width_independent() {
+        let mut a = rng();
+        let mut b = rng();
+        for len in 1..100 {
+            assert_eq!(gen_index(&mut a, len) as u32, below_u32(&mut b, len as u32));
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use super::{Distribution, DistributionError, float, uniform::gen_index};
+use crate::rand::RngCore;
+
+/// Index `i` with probability `weights[i] / sum`, in O(1) per sample using
+/// Vose's alias method. Holds at most `N` weights.
+#[derive(Debug, Clone)]
+pub struct WeightedAliasIndex<const N: usize> {
+    prob: [f64; N],
+    alias: [usize; N],
+    len: usize,
+}
+
+impl<const N: usize> WeightedAliasIndex<N> {
+    pub fn new(weights: &[f64]) -> Result<Self, DistributionError> {
+        let len = weights.len();
+
+        if len > N {
+            return Err(DistributionError::TooManyWeights);
+        }
+
+        let mut sum = 0.0;
+        for &weight in weights {
+            if !weight.is_finite() || weight < 0.0 {
+                return Err(DistributionError::InvalidWeight);
+            }
+            sum += weight;
+        }
+
+        if !sum.is_finite() {
+            return Err(DistributionError::InvalidWeight);
+        }
+
+        if sum == 0.0 {
+            return Err(DistributionError::AllWeightsZero);
+        }
+
+        let mut prob = [0.0; N];
+        let mut alias = [0usize; N];
+
+        // Scaled so the average column is exactly 1.
+        for (scaled, &weight) in prob.iter_mut().zip(weights) {
+            *scaled = weight * len as f64 / sum;
+        }
+
+        let mut small = [0usize; N];
+        let mut large = [0usize; N];
+        let (mut small_len, mut large_len) = (0, 0);
+
+        for (index, &scaled) in prob[..len].iter().enumerate() {
+            if scaled < 1.0 {
+                small[small_len] = index;
+                small_len += 1;
+            } else {
+                large[large_len] = index;
+                large_len += 1;
+            }
+        }
+
+        while small_len > 0 && large_len > 0 {
+            small_len -= 1;
+            large_len -= 1;
+            let less = small[small_len];
+            let more = large[large_len];
+
+            alias[less] = more;
+            prob[more] = (prob[more] + prob[less]) - 1.0;
+
+            if prob[more] < 1.0 {
+                small[small_len] = more;
+                small_len += 1;
+            } else {
+                large[large_len] = more;
+                large_len += 1;
+            }
+        }
+
+        // Whatever is left is 1 up to rounding error.
+        for &index in large[..large_len].iter().chain(&small[..small_len]) {
+            prob[index] = 1.0;
+        }
+
+        Ok(Self { prob, alias, len })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl<const N: usize> Distribution<usize> for WeightedAliasIndex<N> {
+    fn sample<R: RngCore + ?Sized>(&self, rng: &mut R) -> usize {
+        let column = gen_index(rng, self.len);
+
+        if float::f64_closed_open(rng.next_u64()) < self.prob[column] {
+            column
+        } else {
+            self.alias[column]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::ChaCha20Rng;
+
+    /// Probability of each index implied by the table: its own column share
+    /// plus the leftovers of every column aliased to it.
+    fn implied(table: &WeightedAliasIndex<8>) -> [f64; 8] {
+        let mut mass = [0.0; 8];
+        for column in 0..table.len {
+            assert!((0.0..=1.0).contains(&table.prob[column]));
+            assert!(table.alias[column] < table.len);
+            mass[column] += table.prob[column];
+            mass[table.alias[column]] += 1.0 - table.prob[column];
+        }
+        mass.map(|m| m / table.len as f64)
+    }
+
+    #[test]
+    fn table_reproduces_weights() {
+        let weights = [1.0, 2.0, 3.0, 0.0, 10.0, 0.5, 7.25];
+        let table = WeightedAliasIndex::<8>::new(&weights).unwrap();
+        let sum: f64 = weights.iter().sum();
+
+        assert_eq!(table.len(), weights.len());
+        for (mass, weight) in implied(&table).iter().zip(weights) {
+            assert!((mass - weight / sum).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn samples_follow_weights() {
+        let table = WeightedAliasIndex::<4>::new(&[1.0, 0.0, 3.0, 4.0]).unwrap();
+        let mut rng = ChaCha20Rng::from_seed(&[3; 32]);
+        let mut counts = [0usize; 4];
+        let draws = 80_000;
+
+        for _ in 0..draws {
+            counts[table.sample(&mut rng)] += 1;
+        }
+
+        assert_eq!(counts[1], 0);
+        for (count, expected) in counts.iter().zip([0.125, 0.0, 0.375, 0.5]) {
+            assert!((*count as f64 / draws as f64 - expected).abs() < 0.01, "{:?}", counts);
+        }
+    }
+
+    #[test]
+    fn rejects_bad_weights() {
+        assert!(matches!(WeightedAliasIndex::<2>::new(&[1.0; 3]), Err(DistributionError::TooManyWeights)));
+        assert!(matches!(WeightedAliasIndex::<2>::new(&[1.0, -1.0]), Err(DistributionError::InvalidWeight)));
+        assert!(matches!(WeightedAliasIndex::<2>::new(&[1.0, f64::NAN]), Err(DistributionError::InvalidWeight)));
+        assert!(matches!(WeightedAliasIndex::<2>::new(&[f64::MAX, f64::MAX]), Err(DistributionError::InvalidWeight)));
+        assert!(matches!(WeightedAliasIndex::<2>::new(&[0.0, 0.0]), Err(DistributionError::AllWeightsZero)));
+        assert!(matches!(WeightedAliasIndex::<2>::new(&[]), Err(DistributionError::AllWeightsZero)));
+    }
+}
@@ -0,0 +1,151 @@
+//! Marsaglia and Tsang's ziggurat with 256 layers, tables laid out as in
+//! `rand_distr`: `X[0]` is the virtual width of the base strip, `X[1]` the
+//! tail start `R` and `X[256] = 0`; `F[i] = pdf(X[i])`.
+
+use super::float::{self, exp, ln, sqrt};
+use crate::rand::RngCore;
+
+const LAYERS: usize = 256;
+
+pub(super) const NORM_R: f64 = 3.654_152_885_361_009;
+const NORM_X0: f64 = 3.910_757_959_537_09;
+
+pub(super) const EXP_R: f64 = 7.697_117_470_131_05;
+const EXP_X0: f64 = 8.697_117_470_131_05;
+
+pub(super) struct Tables {
+    x: [f64; LAYERS + 1],
+    f: [f64; LAYERS + 1],
+}
+
+const fn norm_pdf(x: f64) -> f64 {
+    exp(-x * x / 2.0)
+}
+
+const fn exp_pdf(x: f64) -> f64 {
+    exp(-x)
+}
+
+/// Every layer has area `V = X[0] * pdf(R)`; each next edge solves
+/// `pdf(X[i + 1]) = V / X[i] + pdf(X[i])`.
+const fn build(r: f64, x0: f64, normal: bool) -> Tables {
+    let pdf_r = if normal { norm_pdf(r) } else { exp_pdf(r) };
+    let v = x0 * pdf_r;
+
+    let mut x = [0.0; LAYERS + 1];
+    let mut f = [0.0; LAYERS + 1];
+    x[0] = x0;
+    x[1] = r;
+
+    let mut i = 1;
+    while i < LAYERS - 1 {
+        let pdf = if normal { norm_pdf(x[i]) } else { exp_pdf(x[i]) };
+        let y = v / x[i] + pdf;
+        x[i + 1] = if normal { sqrt(-2.0 * ln(y)) } else { -ln(y) };
+        i += 1;
+    }
+    x[LAYERS] = 0.0;
+
+    let mut i = 0;
+    while i <= LAYERS {
+        f[i] = if normal { norm_pdf(x[i]) } else { exp_pdf(x[i]) };
+        i += 1;
+    }
+
+    Tables { x, f }
+}
+
+pub(super) static NORM: Tables = build(NORM_R, NORM_X0, true);
+pub(super) static EXP: Tables = build(EXP_R, EXP_X0, false);
+
+/// One standard normal sample.
+pub(super) fn normal<R: RngCore + ?Sized>(rng: &mut R) -> f64 {
+    sample(rng, &NORM, true, norm_pdf, |rng, u| {
+        // Tail beyond R, Marsaglia 1964.
+        let mut x = 1.0f64;
+        let mut y = 0.0f64;
+
+        while -2.0 * y < x * x {
+            x = ln(float::f64_open_open(rng.next_u64())) / NORM_R;
+            y = ln(float::f64_open_open(rng.next_u64()));
+        }
+
+        if u < 0.0 { x - NORM_R } else { NORM_R - x }
+    })
+}
+
+/// One exponential sample with rate 1.
+pub(super) fn exponential<R: RngCore + ?Sized>(rng: &mut R) -> f64 {
+    sample(rng, &EXP, false, exp_pdf, |rng, _| {
+        EXP_R - ln(float::f64_open_open(rng.next_u64()))
+    })
+}
+
+fn sample<R: RngCore + ?Sized>(
+    rng: &mut R,
+    tables: &Tables,
+    symmetric: bool,
+    pdf: fn(f64) -> f64,
+    tail: impl Fn(&mut R, f64) -> f64,
+) -> f64 {
+    loop {
+        let bits = rng.next_u64();
+        let i = (bits & 0xff) as usize;
+
+        // The low byte picked the layer, the top 52 bits give the position in it.
+        let unit = f64::from_bits(0x3ff0_0000_0000_0000 | (bits >> 12));
+        let u = if symmetric { 2.0 * unit - 3.0 } else { unit - 1.0 };
+        let x = u * tables.x[i];
+
+        let test_x = if symmetric { x.abs() } else { x };
+        if test_x < tables.x[i + 1] {
+            return x;
+        }
+
+        if i == 0 {
+            return tail(rng, u);
+        }
+
+        let y = tables.f[i + 1] + (tables.f[i] - tables.f[i + 1]) * float::f64_closed_open(rng.next_u64());
+        if y < pdf(x) {
+            return x;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(tables: &Tables, r: f64, x0: f64, pdf: fn(f64) -> f64) {
+        let v = x0 * pdf(r);
+
+        assert_eq!(tables.x[0], x0);
+        assert_eq!(tables.x[1], r);
+        assert_eq!(tables.x[LAYERS], 0.0);
+        assert_eq!(tables.f[LAYERS], 1.0);
+
+        for i in 0..LAYERS {
+            assert!(tables.x[i] > tables.x[i + 1], "x not decreasing at {}", i);
+            assert!(tables.f[i] < tables.f[i + 1], "f not increasing at {}", i);
+            assert_eq!(tables.f[i], pdf(tables.x[i]));
+        }
+
+        // Every strip above the base, including the topmost one the
+        // construction never solved for, has the same area.
+        for i in 1..LAYERS {
+            let area = tables.x[i] * (tables.f[i + 1] - tables.f[i]);
+            assert!((area - v).abs() < v * 1e-6, "layer {} area {} vs {}", i, area, v);
+        }
+    }
+
+    #[test]
+    fn normal_table() {
+        check(&NORM, NORM_R, NORM_X0, norm_pdf);
+    }
+
+    #[test]
+    fn exponential_table() {
+        check(&EXP, EXP_R, EXP_X0, exp_pdf);
+    }
+}
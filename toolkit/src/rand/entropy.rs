@@ -0,0 +1,290 @@
+//! Sources of seed material for the generators in this module.
+
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::{__cpuid, __cpuid_count, _rdrand64_step, _rdseed64_step};
+
+use crate::{EntropyError, sys};
+
+pub trait EntropySource {
+    fn try_fill(&mut self, buffer: &mut [u8]) -> Result<(), EntropyError>;
+
+    fn fill(&mut self, buffer: &mut [u8]) {
+        if let Err(err) = self.try_fill(buffer) {
+            panic!("entropy source failed: {}", err);
+        }
+    }
+
+    fn seed<const N: usize>(&mut self) -> Result<[u8; N], EntropyError>
+    where
+        Self: Sized,
+    {
+        let mut seed = [0u8; N];
+        self.try_fill(&mut seed)?;
+        Ok(seed)
+    }
+}
+
+impl<E: EntropySource + ?Sized> EntropySource for &mut E {
+    fn try_fill(&mut self, buffer: &mut [u8]) -> Result<(), EntropyError> {
+        (**self).try_fill(buffer)
+    }
+}
+
+/// `ProcessPrng` from `bcryptprimitives.dll`, the generator behind `BCryptGenRandom`.
+#[cfg(windows)]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ProcessPrngEntropy;
+
+#[cfg(windows)]
+impl EntropySource for ProcessPrngEntropy {
+    fn try_fill(&mut self, buffer: &mut [u8]) -> Result<(), EntropyError> {
+        sys::rand::try_fill_bytes(buffer).map_err(EntropyError::Os)
+    }
+}
+
+/// The `getrandom` syscall, blocking only until the kernel pool is first initialized.
+#[cfg(target_os = "linux")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct GetRandomEntropy;
+
+#[cfg(target_os = "linux")]
+impl EntropySource for GetRandomEntropy {
+    fn try_fill(&mut self, buffer: &mut [u8]) -> Result<(), EntropyError> {
+        sys::rand::try_fill_bytes(buffer).map_err(EntropyError::Os)
+    }
+}
+
+#[cfg(windows)]
+pub type OsEntropy = ProcessPrngEntropy;
+
+#[cfg(target_os = "linux")]
+pub type OsEntropy = GetRandomEntropy;
+
+/// Intel's DRNG guide: RDRAND failing ten times in a row means a broken CPU.
+#[cfg(target_arch = "x86_64")]
+const RDRAND_RETRIES: usize = 10;
+
+/// RDSEED drains far faster than RDRAND and is expected to fail under load.
+#[cfg(target_arch = "x86_64")]
+const RDSEED_RETRIES: usize = 1024;
+
+/// CPUID.01H:ECX bit 30, the `Rdrnd` bit the `cpuid` project decodes.
+#[cfg(target_arch = "x86_64")]
+pub fn has_rdrand() -> bool {
+    __cpuid(1).ecx & (1 << 30) != 0
+}
+
+/// CPUID.(EAX=07H,ECX=0):EBX bit 18, the `Rdseed` bit the `cpuid` project decodes.
+#[cfg(target_arch = "x86_64")]
+pub fn has_rdseed() -> bool {
+    __cpuid(0).eax >= 7 && __cpuid_count(7, 0).ebx & (1 << 18) != 0
+}
+
+/// The DRBG output of the CPU's hardware generator, reseeded by the CPU itself.
+#[cfg(target_arch = "x86_64")]
+#[derive(Debug, Clone, Copy)]
+pub struct RdRandEntropy(());
+
+#[cfg(target_arch = "x86_64")]
+impl RdRandEntropy {
+    pub fn new() -> Result<Self, EntropyError> {
+        if has_rdrand() { Ok(Self(())) } else { Err(EntropyError::Unsupported) }
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+impl EntropySource for RdRandEntropy {
+    fn try_fill(&mut self, buffer: &mut [u8]) -> Result<(), EntropyError> {
+        // SAFETY: `new` checked the CPUID bit.
+        fill_words(buffer, || unsafe { rdrand() })
+    }
+}
+
+/// Raw conditioned entropy from the CPU, meant for seeding rather than bulk output.
+#[cfg(target_arch = "x86_64")]
+#[derive(Debug, Clone, Copy)]
+pub struct RdSeedEntropy(());
+
+#[cfg(target_arch = "x86_64")]
+impl RdSeedEntropy {
+    pub fn new() -> Result<Self, EntropyError> {
+        if has_rdseed() { Ok(Self(())) } else { Err(EntropyError::Unsupported) }
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+impl EntropySource for RdSeedEntropy {
+    fn try_fill(&mut self, buffer: &mut [u8]) -> Result<(), EntropyError> {
+        // SAFETY: `new` checked the CPUID bit.
+        fill_words(buffer, || unsafe { rdseed() })
+    }
+}
+
+fn fill_words(buffer: &mut [u8], mut next: impl FnMut() -> Result<u64, EntropyError>) -> Result<(), EntropyError> {
+    for chunk in buffer.chunks_mut(8) {
+        let word = next()?.to_le_bytes();
+        chunk.copy_from_slice(&word[..chunk.len()]);
+    }
+
+    Ok(())
+}
+
+/// Some AMD parts report success with all bits set after a suspend/resume
+/// cycle, so `u64::MAX` is treated as a failed attempt.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "rdrand")]
+unsafe fn rdrand() -> Result<u64, EntropyError> {
+    let mut value = 0;
+
+    for _ in 0..RDRAND_RETRIES {
+        if _rdrand64_step(&mut value) == 1 && value != u64::MAX {
+            return Ok(value);
+        }
+    }
+
+    Err(EntropyError::Exhausted)
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "rdseed")]
+unsafe fn rdseed() -> Result<u64, EntropyError> {
+    let mut value = 0;
+
+    for _ in 0..RDSEED_RETRIES {
+        if _rdseed64_step(&mut value) == 1 && value != u64::MAX {
+            return Ok(value);
+        }
+        core::hint::spin_loop();
+    }
+
+    Err(EntropyError::Exhausted)
+}
+
+/// Deterministic SplitMix64 stream for tests. Never use it to seed anything
+/// that has to be unpredictable.
+#[derive(Debug, Clone, Copy)]
+pub struct TestEntropy {
+    state: u64,
+}
+
+impl TestEntropy {
+    pub const fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+impl EntropySource for TestEntropy {
+    fn try_fill(&mut self, buffer: &mut [u8]) -> Result<(), EntropyError> {
+        fill_words(buffer, || Ok(self.next_u64()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// First outputs of SplitMix64 seeded with 0.
+    const SPLITMIX_0: [u64; 3] = [0xE220_A839_7B1D_CDAF, 0x6E78_9E6A_A1B9_65F4, 0x06C4_5D18_8009_454F];
+
+    struct Failing;
+
+    impl EntropySource for Failing {
+        fn try_fill(&mut self, _buffer: &mut [u8]) -> Result<(), EntropyError> {
+            Err(EntropyError::Exhausted)
+        }
+    }
+
+    fn take_seed<E: EntropySource>(mut source: E) -> Result<[u8; 8], EntropyError> {
+        source.seed()
+    }
+
+    #[test]
+    fn test_entropy_is_splitmix64() {
+        let mut entropy = TestEntropy::new(0);
+        for expected in SPLITMIX_0 {
+            assert_eq!(entropy.seed::<8>(), Ok(expected.to_le_bytes()));
+        }
+
+        assert_eq!(TestEntropy::new(7).seed::<32>(), TestEntropy::new(7).seed::<32>());
+        assert_ne!(TestEntropy::new(7).seed::<32>(), TestEntropy::new(8).seed::<32>());
+    }
+
+    #[test]
+    fn partial_words() {
+        let mut entropy = TestEntropy::new(0);
+        let mut buffer = [0u8; 13];
+        entropy.fill(&mut buffer);
+        assert_eq!(buffer[..8], SPLITMIX_0[0].to_le_bytes());
+        assert_eq!(buffer[8..], SPLITMIX_0[1].to_le_bytes()[..5]);
+
+        // The rest of a partly used word is discarded.
+        assert_eq!(entropy.seed::<3>(), Ok(SPLITMIX_0[2].to_le_bytes()[..3].try_into().unwrap()));
+
+        // An empty request draws nothing.
+        let mut entropy = TestEntropy::new(0);
+        assert_eq!(entropy.seed::<0>(), Ok([]));
+        assert_eq!(entropy.seed::<8>(), Ok(SPLITMIX_0[0].to_le_bytes()));
+    }
+
+    #[test]
+    fn forwards_through_mut_references() {
+        let mut entropy = TestEntropy::new(0);
+        assert_eq!(take_seed(&mut entropy), Ok(SPLITMIX_0[0].to_le_bytes()));
+        assert_eq!(take_seed(&mut &mut entropy), Ok(SPLITMIX_0[1].to_le_bytes()));
+        assert_eq!(entropy.seed::<8>(), Ok(SPLITMIX_0[2].to_le_bytes()));
+
+        let dynamic: &mut dyn EntropySource = &mut Failing;
+        assert_eq!(take_seed(dynamic), Err(EntropyError::Exhausted));
+    }
+
+    #[test]
+    #[should_panic(expected = "entropy source failed")]
+    fn fill_panics_on_failure() {
+        Failing.fill(&mut [0; 4]);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn getrandom_fills() {
+        let (mut a, mut b) = ([0u8; 64], [0u8; 64]);
+        GetRandomEntropy.try_fill(&mut a).unwrap();
+        GetRandomEntropy.try_fill(&mut b).unwrap();
+        assert_ne!(a, [0; 64]);
+        assert_ne!(a, b);
+        assert_ne!(OsEntropy::default().seed::<32>().unwrap(), [0; 32]);
+    }
+
+    #[cfg(all(target_arch = "x86_64", not(miri)))]
+    #[test]
+    fn cpu_sources_match_cpuid() {
+        match RdRandEntropy::new() {
+            Ok(mut source) => {
+                assert!(has_rdrand());
+                let seed = source.seed::<20>().unwrap();
+                assert_ne!(seed, [0; 20]);
+                assert_ne!(seed, source.seed::<20>().unwrap());
+            }
+            Err(err) => assert!(!has_rdrand() && err == EntropyError::Unsupported),
+        }
+
+        match RdSeedEntropy::new() {
+            // RDSEED may legitimately run dry under load.
+            Ok(mut source) => {
+                assert!(has_rdseed());
+                match source.seed::<16>() {
+                    Ok(seed) => assert_ne!(seed, [0; 16]),
+                    Err(err) => assert_eq!(err, EntropyError::Exhausted),
+                }
+            }
+            Err(err) => assert!(!has_rdseed() && err == EntropyError::Unsupported),
+        }
+    }
+}
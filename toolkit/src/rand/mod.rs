@@ -1,5 +1,14 @@
 mod chacha;
+mod entropy;
 mod rand;
+mod reseeding;
+mod rng_core;
+mod seq;
+pub mod distributions;
 
 pub use chacha::*;
-pub use rand::*;
\ No newline at end of file
+pub use entropy::*;
+pub use rand::*;
+pub use reseeding::*;
+pub use rng_core::*;
+pub use seq::*;
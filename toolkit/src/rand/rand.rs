@@ -1,25 +1,34 @@
 use core::ops::Range;
 
-use crate::{U8CStackString, rand::ChaChaRng};
+use crate::{EntropyError, U8CStackString, rand::{ChaChaRng, EntropySource}, sys};
 
-#[link(name = "bcryptprimitives", kind = "raw-dylib")]
-unsafe extern "system" {
-    pub fn ProcessPrng(pbData: *mut u8, cbData: usize) -> i32;
-}
+#[cfg(windows)]
+pub use crate::sys::rand::ProcessPrng;
 
 fn seed_from_os() -> [u8; 32] {
     let mut seed = [0u8; 32];
-    unsafe { ProcessPrng(seed.as_mut_ptr() as *mut _, seed.len() as usize); }
+    sys::rand::fill_bytes(&mut seed);
     seed
 }
 
 pub struct Rng(ChaChaRng);
 
+impl Default for Rng {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Rng {
     pub fn new() -> Self {
         let seed = seed_from_os();
         Rng(ChaChaRng::from_seed(&seed))
     }
+
+    pub fn from_entropy<E: EntropySource>(source: &mut E) -> Result<Self, EntropyError> {
+        let seed = source.seed::<32>()?;
+        Ok(Rng(ChaChaRng::from_seed(&seed)))
+    }
     
     pub fn next_u32(&mut self) -> u32 {
         self.0.next_u32()
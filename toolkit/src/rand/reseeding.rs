@@ -0,0 +1,190 @@
+use super::{ChaChaRng, EntropySource, RngCore};
+use crate::{EntropyError, sys};
+
+/// [`ChaChaRng`] that pulls a fresh key from `E` after every `threshold`
+/// bytes of output, when asked to, and after the process forks.
+///
+/// A failed automatic reseed keeps the current key and retries after another
+/// `threshold` bytes; a failed reseed after a fork panics instead, since the
+/// child would otherwise repeat the parent's output.
+pub struct ReseedingRng<E: EntropySource, const ROUNDS: usize = 20> {
+    inner: ChaChaRng<ROUNDS>,
+    source: E,
+    threshold: u64,
+    remaining: u64,
+    fork_id: u64,
+}
+
+impl<E: EntropySource, const ROUNDS: usize> ReseedingRng<E, ROUNDS> {
+    /// A `threshold` of zero disables reseeding by output volume.
+    pub fn new(mut source: E, threshold: u64) -> Result<Self, EntropyError> {
+        let fork_id = sys::rand::fork_id();
+        let seed = source.seed::<32>()?;
+
+        Ok(Self {
+            inner: ChaChaRng::from_seed(&seed),
+            source,
+            threshold,
+            remaining: threshold,
+            fork_id,
+        })
+    }
+
+    pub fn reseed(&mut self) -> Result<(), EntropyError> {
+        let fork_id = sys::rand::fork_id();
+        let seed = self.source.seed::<32>()?;
+
+        self.inner = ChaChaRng::from_seed(&seed);
+        self.remaining = self.threshold;
+        self.fork_id = fork_id;
+        Ok(())
+    }
+
+    /// `None` when reseeding by volume is disabled.
+    pub fn bytes_until_reseed(&self) -> Option<u64> {
+        (self.threshold != 0).then_some(self.remaining)
+    }
+
+    pub fn source(&self) -> &E {
+        &self.source
+    }
+
+    pub fn source_mut(&mut self) -> &mut E {
+        &mut self.source
+    }
+
+    fn reserve(&mut self, bytes: usize) {
+        if self.fork_id != sys::rand::fork_id()
+            && let Err(err) = self.reseed()
+        {
+            panic!("cannot reseed after fork: {}", err);
+        }
+
+        if self.threshold == 0 {
+            return;
+        }
+
+        if self.remaining < bytes as u64 && self.reseed().is_err() {
+            self.remaining = self.threshold;
+        }
+
+        self.remaining = self.remaining.saturating_sub(bytes as u64);
+    }
+}
+
+impl<E: EntropySource, const ROUNDS: usize> RngCore for ReseedingRng<E, ROUNDS> {
+    fn next_u32(&mut self) -> u32 {
+        self.reserve(4);
+        self.inner.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.reserve(8);
+        self.inner.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.reserve(dest.len());
+        self.inner.fill_bytes(dest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::{ChaCha20Rng, TestEntropy};
+
+    /// [`TestEntropy`] that can be switched to failing.
+    struct Flaky {
+        inner: TestEntropy,
+        failing: bool,
+    }
+
+    impl EntropySource for Flaky {
+        fn try_fill(&mut self, buffer: &mut [u8]) -> Result<(), EntropyError> {
+            if self.failing {
+                return Err(EntropyError::Exhausted);
+            }
+            self.inner.try_fill(buffer)
+        }
+    }
+
+    /// The generators the first and second seeds drawn from `TestEntropy::new(seed)` produce.
+    fn expected(seed: u64) -> (ChaCha20Rng, ChaCha20Rng) {
+        let mut source = TestEntropy::new(seed);
+        let first = ChaCha20Rng::from_seed(&source.seed::<32>().unwrap());
+        let second = ChaCha20Rng::from_seed(&source.seed::<32>().unwrap());
+        (first, second)
+    }
+
+    #[test]
+    fn reseeds_after_threshold() {
+        let (mut first, mut second) = expected(1);
+        let mut rng = ReseedingRng::<_, 20>::new(TestEntropy::new(1), 16).unwrap();
+
+        assert_eq!(rng.bytes_until_reseed(), Some(16));
+        assert_eq!(rng.next_u64(), first.next_u64());
+        assert_eq!(rng.next_u64(), first.next_u64());
+        assert_eq!(rng.bytes_until_reseed(), Some(0));
+
+        assert_eq!(rng.next_u64(), second.next_u64());
+        assert_eq!(rng.bytes_until_reseed(), Some(8));
+    }
+
+    #[test]
+    fn zero_threshold_never_reseeds() {
+        let (mut first, _) = expected(2);
+        let mut rng = ReseedingRng::<_, 20>::new(TestEntropy::new(2), 0).unwrap();
+
+        assert_eq!(rng.bytes_until_reseed(), None);
+        let mut buffer = [0u8; 1024];
+        let mut reference = [0u8; 1024];
+        rng.fill_bytes(&mut buffer);
+        first.fill_bytes(&mut reference);
+        assert_eq!(buffer, reference);
+    }
+
+    #[test]
+    fn reseeds_on_fork_id_change() {
+        let (mut first, mut second) = expected(3);
+        let mut rng = ReseedingRng::<_, 20>::new(TestEntropy::new(3), 0).unwrap();
+        assert_eq!(rng.next_u32(), first.next_u32());
+
+        // What the child of a fork sees: the token no longer matches.
+        rng.fork_id = rng.fork_id.wrapping_add(1);
+        assert_eq!(rng.next_u32(), second.next_u32());
+        assert_eq!(rng.fork_id, sys::rand::fork_id());
+    }
+
+    #[test]
+    fn entropy_failures_propagate() {
+        let failing = Flaky { inner: TestEntropy::new(4), failing: true };
+        assert!(matches!(ReseedingRng::<_, 20>::new(failing, 8), Err(EntropyError::Exhausted)));
+
+        let (mut first, mut second) = expected(4);
+        let source = Flaky { inner: TestEntropy::new(4), failing: false };
+        let mut rng = ReseedingRng::<_, 20>::new(source, 8).unwrap();
+        assert_eq!(rng.next_u64(), first.next_u64());
+
+        rng.source_mut().failing = true;
+        assert_eq!(rng.reseed(), Err(EntropyError::Exhausted));
+
+        // The automatic reseed fails too: the key is kept and the budget restarts.
+        assert_eq!(rng.next_u64(), first.next_u64());
+        assert_eq!(rng.bytes_until_reseed(), Some(0));
+
+        rng.source_mut().failing = false;
+        assert_eq!(rng.next_u64(), second.next_u64());
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot reseed after fork")]
+    fn failed_reseed_after_fork_panics() {
+        let source = Flaky { inner: TestEntropy::new(5), failing: false };
+        let mut rng = ReseedingRng::<_, 20>::new(source, 0).unwrap();
+
+        rng.source_mut().failing = true;
+        rng.fork_id = rng.fork_id.wrapping_add(1);
+        rng.next_u64();
+    }
+}
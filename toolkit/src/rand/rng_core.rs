@@ -0,0 +1,84 @@
+use super::{ChaChaRng, Rng};
+use super::distributions::{Bernoulli, Distribution, SampleRange};
+
+/// Source of random bits. [`Rng`], [`ChaChaRng`] and project-local generators
+/// implement it, so the distributions accept any of them.
+pub trait RngCore {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64;
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    /// Panics on an empty range, use [`Uniform::new`](super::distributions::Uniform::new) to handle it.
+    fn gen_range<T, R: SampleRange<T>>(&mut self, range: R) -> T
+    where
+        Self: Sized,
+    {
+        range.sample_single(self)
+    }
+
+    /// Panics unless `p` is in `0.0..=1.0`.
+    fn gen_bool(&mut self, p: f64) -> bool
+    where
+        Self: Sized,
+    {
+        Bernoulli::new(p).expect("probability must be in 0..=1").sample(self)
+    }
+
+    fn sample<T, D: Distribution<T>>(&mut self, distribution: &D) -> T
+    where
+        Self: Sized,
+    {
+        distribution.sample(self)
+    }
+}
+
+impl<R: RngCore + ?Sized> RngCore for &mut R {
+    fn next_u32(&mut self) -> u32 {
+        (**self).next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        (**self).next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        (**self).fill_bytes(dest)
+    }
+}
+
+impl<const ROUNDS: usize> RngCore for ChaChaRng<ROUNDS> {
+    fn next_u32(&mut self) -> u32 {
+        ChaChaRng::next_u32(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        ChaChaRng::next_u64(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        ChaChaRng::fill_bytes(self, dest)
+    }
+}
+
+impl RngCore for Rng {
+    fn next_u32(&mut self) -> u32 {
+        Rng::next_u32(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        Rng::next_u64(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        Rng::fill_bytes(self, dest)
+    }
+}
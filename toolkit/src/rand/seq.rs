@@ -0,0 +1,75 @@
+use crate::StaticVec;
+use super::RngCore;
+use super::distributions::gen_index;
+
+/// Random selection and Fisher–Yates shuffling on slices.
+pub trait SliceRandom {
+    type Item;
+
+    fn choose<R: RngCore + ?Sized>(&self, rng: &mut R) -> Option<&Self::Item>;
+
+    fn choose_mut<R: RngCore + ?Sized>(&mut self, rng: &mut R) -> Option<&mut Self::Item>;
+
+    /// `min(K, len)` distinct elements in random order.
+    fn choose_multiple<R: RngCore + ?Sized, const K: usize>(&self, rng: &mut R) -> StaticVec<&Self::Item, K>;
+
+    fn shuffle<R: RngCore + ?Sized>(&mut self, rng: &mut R);
+
+    /// Shuffles only the first `amount` elements into place, returning them and the rest.
+    fn partial_shuffle<R: RngCore + ?Sized>(&mut self, rng: &mut R, amount: usize) -> (&mut [Self::Item], &mut [Self::Item]);
+}
+
+impl<T> SliceRandom for [T] {
+    type Item = T;
+
+    fn choose<R: RngCore + ?Sized>(&self, rng: &mut R) -> Option<&T> {
+        if self.is_empty() {
+            return None;
+        }
+
+        Some(&self[gen_index(rng, self.len())])
+    }
+
+    fn choose_mut<R: RngCore + ?Sized>(&mut self, rng: &mut R) -> Option<&mut T> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let index = gen_index(rng, self.len());
+        Some(&mut self[index])
+    }
+
+    fn choose_multiple<R: RngCore + ?Sized, const K: usize>(&self, rng: &mut R) -> StaticVec<&T, K> {
+        let mut chosen = StaticVec::new();
+
+        // Reservoir sampling picks the set, the shuffle randomizes its order.
+        for (index, item) in self.iter().enumerate() {
+            if let Err(item) = chosen.push(item) {
+                let slot = gen_index(rng, index + 1);
+                if slot < K {
+                    chosen[slot] = item;
+                }
+            }
+        }
+
+        chosen.shuffle(rng);
+        chosen
+    }
+
+    fn shuffle<R: RngCore + ?Sized>(&mut self, rng: &mut R) {
+        for index in (1..self.len()).rev() {
+            self.swap(index, gen_index(rng, index + 1));
+        }
+    }
+
+    fn partial_shuffle<R: RngCore + ?Sized>(&mut self, rng: &mut R, amount: usize) -> (&mut [T], &mut [T]) {
+        let amount = amount.min(self.len());
+
+        for index in 0..amount {
+            let other = index + gen_index(rng, self.len() - index);
+            self.swap(index, other);
+        }
+
+        self.split_at_mut(amount)
+    }
+}
@@ -0,0 +1,274 @@
+use alloc::{collections::VecDeque, vec, vec::Vec};
+
+use super::{Match, Pattern, memchr::memchr};
+
+/// Marks a missing trie edge while the automaton is built.
+const NO_EDGE: u32 = u32::MAX;
+
+/// Aho-Corasick DFA over the literal runs of the patterns, every state with
+/// all 256 transitions resolved.
+struct Automaton {
+    next: Vec<[u32; 256]>,
+    /// Patterns whose literal ends in the state, through failure links too.
+    outputs: Vec<Vec<usize>>,
+}
+
+impl Automaton {
+    fn new<'a>(literals: impl Iterator<Item = (usize, &'a [u8])>) -> Self {
+        let mut next = vec![[NO_EDGE; 256]];
+        let mut outputs = vec![Vec::new()];
+
+        for (pattern, literal) in literals {
+            let mut state = 0;
+            for &byte in literal {
+                state = match next[state][byte as usize] {
+                    NO_EDGE => {
+                        next.push([NO_EDGE; 256]);
+                        outputs.push(Vec::new());
+                        next[state][byte as usize] = (next.len() - 1) as u32;
+                        next.len() - 1
+                    }
+                    child => child as usize,
+                };
+            }
+            outputs[state].push(pattern);
+        }
+
+        // Breadth-first, so the failure state of every state is complete
+        // before its own missing edges are copied from it.
+        let mut fail = vec![0; next.len()];
+        let mut queue = VecDeque::new();
+
+        for edge in &mut next[0] {
+            match *edge {
+                NO_EDGE => *edge = 0,
+                child => queue.push_back(child as usize),
+            }
+        }
+
+        while let Some(state) = queue.pop_front() {
+            let failure = fail[state];
+            let inherited = outputs[failure].clone();
+            outputs[state].extend(inherited);
+
+            let fallbacks = next[failure];
+            for (edge, fallback) in next[state].iter_mut().zip(fallbacks) {
+                match *edge {
+                    NO_EDGE => *edge = fallback,
+                    child => {
+                        fail[child as usize] = fallback as usize;
+                        queue.push_back(child as usize);
+                    }
+                }
+            }
+        }
+
+        Self { next, outputs }
+    }
+}
+
+/// Several [`Pattern`]s matched in one pass.
+///
+/// The longest fully specified run of each pattern goes into an
+/// Aho-Corasick automaton and every hit is verified against the whole
+/// pattern. Patterns made only of wildcards are tried at every offset.
+pub struct PatternSet {
+    patterns: Vec<Pattern>,
+    automaton: Automaton,
+    wildcards: Vec<usize>,
+    /// First byte shared by every literal, skipped to with [`memchr`] while
+    /// the automaton is in its start state.
+    first_byte: Option<u8>,
+    max_len: usize,
+}
+
+impl PatternSet {
+    pub fn new(patterns: Vec<Pattern>) -> Self {
+        let literals = patterns
+            .iter()
+            .enumerate()
+            .map(|(index, pattern)| (index, pattern.literal().1))
+            .filter(|(_, literal)| !literal.is_empty());
+        let automaton = Automaton::new(literals.clone());
+
+        let wildcards = patterns
+            .iter()
+            .enumerate()
+            .filter(|(_, pattern)| pattern.literal().1.is_empty())
+            .map(|(index, _)| index)
+            .collect();
+
+        let mut first_bytes = literals.map(|(_, literal)| literal[0]);
+        let first_byte = first_bytes.next().filter(|&first| first_bytes.all(|byte| byte == first));
+
+        let max_len = patterns.iter().map(Pattern::len).max().unwrap_or(0);
+
+        Self { patterns, automaton, wildcards, first_byte, max_len }
+    }
+
+    pub fn patterns(&self) -> &[Pattern] {
+        &self.patterns
+    }
+
+    pub fn len(&self) -> usize {
+        self.patterns.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Length of the longest pattern.
+    pub fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    /// Every match, overlapping ones included, sorted by offset and then pattern.
+    pub fn find_all(&self, haystack: &[u8]) -> Vec<Match> {
+        let mut matches = Vec::new();
+        self.for_each_match(haystack, |found| matches.push(found));
+        matches.sort_unstable();
+        matches
+    }
+
+    /// Calls `f` for every match, in no particular order.
+    pub fn for_each_match<F>(&self, haystack: &[u8], mut f: F)
+    where
+        F: FnMut(Match),
+    {
+        let Automaton { next, outputs } = &self.automaton;
+        let mut state = 0;
+        let mut position = 0;
+
+        while position < haystack.len() {
+            if state == 0
+                && let Some(first) = self.first_byte
+            {
+                match memchr(first, &haystack[position..]) {
+                    Some(skip) => position += skip,
+                    None => break,
+                }
+            }
+
+            state = next[state][haystack[position] as usize] as usize;
+            position += 1;
+
+            for &index in &outputs[state] {
+                let pattern = &self.patterns[index];
+                let (literal_start, literal) = pattern.literal();
+
+                let start = (position - literal.len()).checked_sub(literal_start);
+                if let Some(start) = start
+                    && pattern.matches(&haystack[start..])
+                {
+                    f(Match { offset: start, pattern: index });
+                }
+            }
+        }
+
+        for &index in &self.wildcards {
+            for offset in self.patterns[index].find_iter(haystack) {
+                f(Match { offset, pattern: index });
+            }
+        }
+    }
+}
+
+impl From<Pattern> for PatternSet {
+    fn from(pattern: Pattern) -> Self {
+        Self::new(vec![pattern])
+    }
+}
+
+impl FromIterator<Pattern> for PatternSet {
+    fn from_iter<I: IntoIterator<Item = Pattern>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(signatures: &[&str]) -> PatternSet {
+        signatures.iter().map(|signature| Pattern::parse(signature).unwrap()).collect()
+    }
+
+    /// Every pattern on its own, to check the automaton against.
+    fn naive(set: &PatternSet, haystack: &[u8]) -> Vec<Match> {
+        let mut matches: Vec<_> = set
+            .patterns()
+            .iter()
+            .enumerate()
+            .flat_map(|(pattern, p)| p.find_iter(haystack).map(move |offset| Match { offset, pattern }))
+            .collect();
+        matches.sort_unstable();
+        matches
+    }
+
+    #[test]
+    fn finds_every_pattern() {
+        let set = set(&["48 8B ?? ?? E8", "8B 05", "E8 ?? ?? ?? ??", "?? ?? C3"]);
+        let haystack = [0x48, 0x8B, 0x05, 0x10, 0xE8, 0x01, 0x02, 0x03, 0x04, 0xC3];
+
+        let matches = set.find_all(&haystack);
+        assert_eq!(
+            matches,
+            [
+                Match { offset: 0, pattern: 0 },
+                Match { offset: 1, pattern: 1 },
+                Match { offset: 4, pattern: 2 },
+                Match { offset: 7, pattern: 3 },
+            ]
+        );
+        assert_eq!(matches, naive(&set, &haystack));
+    }
+
+    #[test]
+    fn literals_sharing_suffixes() {
+        // "he", "she", "his", "hers" with wildcard tails.
+        let set = set(&["68 65", "73 68 65 ??", "68 69 73", "68 65 72 73", "65"]);
+        let haystack = b"ushers and his sheep, hehe";
+        assert_eq!(set.find_all(haystack), naive(&set, haystack));
+        assert_eq!(set.find_all(haystack).iter().filter(|found| found.pattern == 4).count(), 5);
+    }
+
+    #[test]
+    fn skips_to_the_shared_first_byte() {
+        let set = set(&["E8 ?? ?? ?? ?? 90", "E8 00"]);
+        assert_eq!(set.first_byte, Some(0xE8));
+
+        let mut haystack = [0x90u8; 300];
+        haystack[17] = 0xE8;
+        haystack[18] = 0x00;
+        haystack[250] = 0xE8;
+        assert_eq!(set.find_all(&haystack), naive(&set, &haystack));
+        assert_eq!(set.find_all(&haystack).len(), 3);
+    }
+
+    #[test]
+    fn agrees_with_naive_search() {
+        let set = set(&["01 02", "02 ?? 01", "0? 03", "?? ??", "03 03 03", "01"]);
+
+        // Small alphabet so patterns hit often.
+        let mut state = 0x2545F491u32;
+        let haystack: Vec<u8> = (0..2000)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                (state % 4) as u8
+            })
+            .collect();
+
+        assert_eq!(set.find_all(&haystack), naive(&set, &haystack));
+    }
+
+    #[test]
+    fn empty_set() {
+        let set = PatternSet::new(Vec::new());
+        assert!(set.is_empty());
+        assert_eq!(set.max_len(), 0);
+        assert!(set.find_all(b"anything").is_empty());
+    }
+}
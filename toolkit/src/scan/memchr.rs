@@ -0,0 +1,54 @@
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::{__m128i, _mm_cmpeq_epi8, _mm_loadu_si128, _mm_movemask_epi8, _mm_set1_epi8};
+
+/// Bytes compared per SSE2 step.
+#[cfg(target_arch = "x86_64")]
+const LANES: usize = 16;
+
+/// Index of the first `needle` in `haystack`.
+///
+/// SSE2 is part of the x86_64 baseline, so the vector path needs no CPUID check.
+#[cfg(target_arch = "x86_64")]
+pub fn memchr(needle: u8, haystack: &[u8]) -> Option<usize> {
+    let mut chunks = haystack.chunks_exact(LANES);
+    let splat = unsafe { _mm_set1_epi8(needle as i8) };
+
+    for (index, chunk) in chunks.by_ref().enumerate() {
+        let mask = unsafe {
+            let bytes = _mm_loadu_si128(chunk.as_ptr() as *const __m128i);
+            _mm_movemask_epi8(_mm_cmpeq_epi8(bytes, splat))
+        };
+        if mask != 0 {
+            return Some(index * LANES + mask.trailing_zeros() as usize);
+        }
+    }
+
+    let tail = haystack.len() - chunks.remainder().len();
+    chunks.remainder().iter().position(|&byte| byte == needle).map(|index| tail + index)
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+pub fn memchr(needle: u8, haystack: &[u8]) -> Option<usize> {
+    haystack.iter().position(|&byte| byte == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn matches_scalar_search() {
+        let mut haystack = vec![0u8; 100];
+        assert_eq!(memchr(1, &haystack), None);
+        assert_eq!(memchr(0, &haystack[..0]), None);
+
+        for index in [0, 15, 16, 31, 47, 95, 96, 99] {
+            haystack[index] = 1;
+            for start in 0..=index {
+                assert_eq!(memchr(1, &haystack[start..]), Some(index - start), "{} from {}", index, start);
+            }
+            haystack[index] = 0;
+        }
+    }
+}
@@ -0,0 +1,220 @@
+//! Byte signature search over slices and over memory read in chunks.
+
+mod aho_corasick;
+mod memchr;
+mod pattern;
+
+use core::ops::Range;
+
+use alloc::{vec, vec::Vec};
+
+use crate::{MemoryMap, Protection, RegionState, Status};
+#[cfg(target_os = "linux")]
+use crate::fs::File;
+
+pub use aho_corasick::PatternSet;
+pub use memchr::memchr;
+pub use pattern::{FindIter, Pattern};
+
+/// Bytes read per step by [`PatternSet::scan`] callers that have no better size.
+pub const DEFAULT_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Where a pattern matched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Match {
+    /// Offset into the haystack, or the address for a [`ReadAt`] scan.
+    pub offset: usize,
+    /// Index of the pattern in its [`PatternSet`].
+    pub pattern: usize,
+}
+
+/// Memory that can only be read a piece at a time, such as another process.
+pub trait ReadAt {
+    /// Reads up to `buf.len()` bytes at `address`. A short read means the
+    /// readable range ends there.
+    fn read_at(&mut self, address: usize, buf: &mut [u8]) -> Result<usize, Status>;
+}
+
+/// Addresses are offsets into the slice.
+impl ReadAt for &[u8] {
+    fn read_at(&mut self, address: usize, buf: &mut [u8]) -> Result<usize, Status> {
+        let available = self.get(address..).unwrap_or_default();
+        let len = buf.len().min(available.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        Ok(len)
+    }
+}
+
+/// A process handle with `PROCESS_VM_READ`, such as `NtCurrentProcess`.
+#[cfg(windows)]
+impl ReadAt for winapi::shared::ntdef::HANDLE {
+    fn read_at(&mut self, address: usize, buf: &mut [u8]) -> Result<usize, Status> {
+        crate::ProcessMemoryReader::read_remote_into(*self, address as _, buf).map_err(Status)
+    }
+}
+
+/// `/proc/<pid>/mem`, where unmapped or unreadable pages fail with `EIO`
+/// instead of faulting. Opening another process's needs ptrace access.
+#[cfg(target_os = "linux")]
+impl ReadAt for File {
+    fn read_at(&mut self, address: usize, buf: &mut [u8]) -> Result<usize, Status> {
+        Ok(File::read_at(self, buf, address as u64)?)
+    }
+}
+
+impl PatternSet {
+    /// Matches in `range` of `reader`, read `chunk_size` bytes at a time.
+    /// Consecutive reads overlap by one pattern length, so a match across
+    /// a chunk boundary is reported once. Sorted by address.
+    pub fn scan<R: ReadAt + ?Sized>(
+        &self,
+        reader: &mut R,
+        range: Range<usize>,
+        chunk_size: usize,
+    ) -> Result<Vec<Match>, Status> {
+        let mut matches = Vec::new();
+        self.scan_into(reader, range, chunk_size, &mut matches)?;
+        Ok(matches)
+    }
+
+    /// [`PatternSet::scan`] over every committed, readable, non-guard region
+    /// of `map`. A region that fails to read keeps the matches found before
+    /// the failure and the scan moves on.
+    pub fn scan_map<R: ReadAt + ?Sized>(&self, reader: &mut R, map: &MemoryMap, chunk_size: usize) -> Vec<Match> {
+        let mut matches = Vec::new();
+
+        let readable = map
+            .with_protection(Protection::READ)
+            .filter(|region| region.state == RegionState::Committed && !region.protection.contains(Protection::GUARD));
+
+        for region in readable {
+            let _ = self.scan_into(reader, region.base..region.end(), chunk_size, &mut matches);
+        }
+
+        matches
+    }
+
+    fn scan_into<R: ReadAt + ?Sized>(
+        &self,
+        reader: &mut R,
+        range: Range<usize>,
+        chunk_size: usize,
+        matches: &mut Vec<Match>,
+    ) -> Result<(), Status> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        let chunk_size = chunk_size.max(1);
+        let overlap = self.max_len() - 1;
+        let mut buffer = vec![0; chunk_size + overlap];
+        let mut address = range.start;
+
+        while address < range.end {
+            let wanted = buffer.len().min(range.end - address);
+            let read = reader.read_at(address, &mut buffer[..wanted])?;
+            let last = read < buffer.len();
+
+            // Matches starting in the overlap are found again by the next read.
+            let limit = if last { read } else { chunk_size };
+            for found in self.find_all(&buffer[..read]) {
+                if found.offset < limit {
+                    matches.push(Match { offset: address + found.offset, pattern: found.pattern });
+                }
+            }
+
+            if last {
+                break;
+            }
+            address += chunk_size;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Serves at most `limit` bytes per read, like a reader hitting page ends.
+    struct Stingy<'a> {
+        bytes: &'a [u8],
+        limit: usize,
+    }
+
+    impl ReadAt for Stingy<'_> {
+        fn read_at(&mut self, address: usize, buf: &mut [u8]) -> Result<usize, Status> {
+            let len = buf.len().min(self.limit);
+            self.bytes.read_at(address, &mut buf[..len])
+        }
+    }
+
+    fn haystack() -> Vec<u8> {
+        let mut haystack = vec![0x90u8; 1000];
+        // 14 and 63 are overwritten by their neighbours.
+        for offset in [0, 14, 15, 63, 64, 500, 995] {
+            haystack[offset..offset + 5].copy_from_slice(&[0x48, 0x8B, 0x05, offset as u8, 0xE8]);
+        }
+        haystack
+    }
+
+    #[test]
+    fn chunked_scan_matches_slice_search() {
+        let haystack = haystack();
+        let set = PatternSet::from(Pattern::parse("48 8B ?? ?? E8").unwrap());
+        let expected = set.find_all(&haystack);
+        assert_eq!(expected.len(), 5);
+
+        for chunk_size in [1, 2, 5, 7, 16, 64, 999, 1000, DEFAULT_CHUNK_SIZE] {
+            let found = set.scan(&mut haystack.as_slice(), 0..haystack.len(), chunk_size).unwrap();
+            assert_eq!(found, expected, "chunk size {}", chunk_size);
+        }
+    }
+
+    #[test]
+    fn chunked_scan_of_a_range() {
+        let haystack = haystack();
+        let set = PatternSet::from(Pattern::parse("48 8B ?? ?? E8").unwrap());
+
+        let found = set.scan(&mut haystack.as_slice(), 15..500, 16).unwrap();
+        let offsets: Vec<_> = found.iter().map(|found| found.offset).collect();
+        assert_eq!(offsets, [15, 64]);
+    }
+
+    #[test]
+    fn short_reads_end_the_scan() {
+        let haystack = haystack();
+        let set = PatternSet::from(Pattern::parse("48 8B ?? ?? E8").unwrap());
+
+        let mut reader = Stingy { bytes: &haystack, limit: 60 };
+        let found = set.scan(&mut reader, 0..haystack.len(), 64).unwrap();
+        let offsets: Vec<_> = found.iter().map(|found| found.offset).collect();
+        assert_eq!(offsets, [0, 15]);
+    }
+
+    #[test]
+    fn scans_current_process() {
+        // Copies of the bytes elsewhere, like the pattern's own buffer, may match too.
+        let mut marker = [0u8; 64];
+        let signature = [0xDE, 0xC0, 0xAD, 0x0B, 0x5C, 0xA1, 0x7E];
+        marker[20..27].copy_from_slice(&signature);
+        marker[24] = 0x42;
+        let address = marker.as_ptr() as usize + 20;
+
+        let set = PatternSet::from(Pattern::parse("DE C0 AD 0B ?? A1 7E").unwrap());
+        let map = MemoryMap::current().unwrap();
+        #[cfg(windows)]
+        let mut memory = ntapi::ntpsapi::NtCurrentProcess;
+        #[cfg(target_os = "linux")]
+        let mut memory = File::open("/proc/self/mem").unwrap();
+
+        let region = map.find(address).unwrap();
+        let found = set.scan(&mut memory, region.base..region.end(), DEFAULT_CHUNK_SIZE).unwrap();
+        assert!(found.iter().any(|found| found.offset == address));
+
+        let found = set.scan_map(&mut memory, &map, DEFAULT_CHUNK_SIZE);
+        assert!(found.iter().any(|found| found.offset == address));
+        assert!(found.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+}
@@ -0,0 +1,281 @@
+use core::{fmt, str::FromStr};
+
+use alloc::vec::Vec;
+
+use crate::PatternError;
+
+use super::memchr::memchr;
+
+/// A byte signature where every byte has a mask of the bits that must match.
+///
+/// Parsed from IDA-style text: hex bytes separated by spaces, `?` or `??`
+/// for any byte, and `?` in place of one digit for a nibble wildcard.
+///
+/// ```
+/// use toolkit::scan::Pattern;
+///
+/// let pattern: Pattern = "48 8B ?? ?? E8 4?".parse().unwrap();
+/// let code = [0x90, 0x48, 0x8B, 0x05, 0x11, 0xE8, 0x41];
+/// assert_eq!(pattern.find(&code), Some(1));
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Pattern {
+    bytes: Vec<u8>,
+    masks: Vec<u8>,
+    /// Longest run of fully specified bytes, as `(start, len)`.
+    literal: (usize, usize),
+}
+
+impl Pattern {
+    /// IDA-style signature, see the type docs.
+    pub fn parse(signature: &str) -> Result<Self, PatternError> {
+        let mut bytes = Vec::new();
+        let mut masks = Vec::new();
+
+        for (index, token) in signature.split_whitespace().enumerate() {
+            let (byte, mask) = parse_token(token).ok_or(PatternError::InvalidToken(index))?;
+            bytes.push(byte);
+            masks.push(mask);
+        }
+
+        Self::with_masks(bytes, masks)
+    }
+
+    /// Code-style signature: `x` in `mask` keeps the byte, `?` ignores it.
+    pub fn with_code_mask(bytes: &[u8], mask: &str) -> Result<Self, PatternError> {
+        if mask.len() != bytes.len() {
+            return Err(PatternError::MaskLength);
+        }
+
+        let masks = mask
+            .bytes()
+            .enumerate()
+            .map(|(index, ch)| match ch {
+                b'x' => Ok(0xFF),
+                b'?' => Ok(0x00),
+                _ => Err(PatternError::InvalidToken(index)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Self::with_masks(bytes.to_vec(), masks)
+    }
+
+    /// One bit mask per byte, `0xFF` for an exact byte.
+    pub fn with_masks(mut bytes: Vec<u8>, masks: Vec<u8>) -> Result<Self, PatternError> {
+        if bytes.is_empty() {
+            return Err(PatternError::Empty);
+        }
+        if masks.len() != bytes.len() {
+            return Err(PatternError::MaskLength);
+        }
+
+        for (byte, mask) in bytes.iter_mut().zip(&masks) {
+            *byte &= mask;
+        }
+
+        let literal = longest_literal(&masks);
+        Ok(Self { bytes, masks, literal })
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn masks(&self) -> &[u8] {
+        &self.masks
+    }
+
+    /// Offset and bytes of the longest fully specified run, empty when every
+    /// byte has a wildcard.
+    pub fn literal(&self) -> (usize, &[u8]) {
+        let (start, len) = self.literal;
+        (start, &self.bytes[start..start + len])
+    }
+
+    /// The pattern matches at the start of `window`.
+    pub fn matches(&self, window: &[u8]) -> bool {
+        window.len() >= self.len()
+            && window
+                .iter()
+                .zip(self.bytes.iter().zip(&self.masks))
+                .all(|(&byte, (&expected, &mask))| byte & mask == expected)
+    }
+
+    /// First match in `haystack`.
+    pub fn find(&self, haystack: &[u8]) -> Option<usize> {
+        self.find_iter(haystack).next()
+    }
+
+    /// Every match in `haystack`, overlapping ones included.
+    pub fn find_iter<'p, 'h>(&'p self, haystack: &'h [u8]) -> FindIter<'p, 'h> {
+        FindIter { pattern: self, haystack, position: 0 }
+    }
+}
+
+impl FromStr for Pattern {
+    type Err = PatternError;
+
+    fn from_str(s: &str) -> Result<Self, PatternError> {
+        Self::parse(s)
+    }
+}
+
+/// Writes the IDA-style form, `?` for a nibble wildcard and `??` for a byte.
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, (&byte, &mask)) in self.bytes.iter().zip(&self.masks).enumerate() {
+            if index > 0 {
+                write!(f, " ")?;
+            }
+            for shift in [4, 0] {
+                match (mask >> shift) & 0xF {
+                    0xF => write!(f, "{:X}", (byte >> shift) & 0xF)?,
+                    _ => write!(f, "?")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Pattern(\"{}\")", self)
+    }
+}
+
+/// Iterator over [`Pattern::find_iter`].
+pub struct FindIter<'p, 'h> {
+    pattern: &'p Pattern,
+    haystack: &'h [u8],
+    position: usize,
+}
+
+impl Iterator for FindIter<'_, '_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let pattern = self.pattern;
+        let last = self.haystack.len().checked_sub(pattern.len())?;
+        let (literal_start, literal) = pattern.literal();
+
+        while self.position <= last {
+            let start = match literal.first() {
+                // Jump to the next occurrence of the literal's first byte.
+                Some(&first) => {
+                    let from = self.position + literal_start;
+                    let found = memchr(first, &self.haystack[from..last + literal_start + 1])?;
+                    self.position + found
+                }
+                None => self.position,
+            };
+
+            self.position = start + 1;
+            if pattern.matches(&self.haystack[start..]) {
+                return Some(start);
+            }
+        }
+
+        None
+    }
+}
+
+fn parse_token(token: &str) -> Option<(u8, u8)> {
+    let digits = token.as_bytes();
+    match digits {
+        [b'?'] | [b'?', b'?'] => Some((0, 0)),
+        [high, low] => {
+            let (high, high_mask) = parse_nibble(*high)?;
+            let (low, low_mask) = parse_nibble(*low)?;
+            Some((high << 4 | low, high_mask << 4 | low_mask))
+        }
+        _ => None,
+    }
+}
+
+fn parse_nibble(digit: u8) -> Option<(u8, u8)> {
+    match digit {
+        b'?' => Some((0, 0)),
+        digit => Some(((digit as char).to_digit(16)? as u8, 0xF)),
+    }
+}
+
+fn longest_literal(masks: &[u8]) -> (usize, usize) {
+    let mut best = (0, 0);
+    let mut start = 0;
+
+    for (index, &mask) in masks.iter().enumerate() {
+        if mask != 0xFF {
+            start = index + 1;
+        } else if index + 1 - start > best.1 {
+            best = (start, index + 1 - start);
+        }
+    }
+
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use alloc::vec;
+    use std::format;
+
+    #[test]
+    fn parses_signatures() {
+        let pattern = Pattern::parse("48 8b ?? ? E8 4? ?f").unwrap();
+        assert_eq!(pattern.bytes(), [0x48, 0x8B, 0, 0, 0xE8, 0x40, 0x0F]);
+        assert_eq!(pattern.masks(), [0xFF, 0xFF, 0, 0, 0xFF, 0xF0, 0x0F]);
+        assert_eq!(pattern.literal(), (0, &[0x48, 0x8B][..]));
+        assert_eq!(format!("{}", pattern), "48 8B ?? ?? E8 4? ?F");
+
+        assert_eq!(Pattern::parse(""), Err(PatternError::Empty));
+        assert_eq!(Pattern::parse("48 8G"), Err(PatternError::InvalidToken(1)));
+        assert_eq!(Pattern::parse("488B"), Err(PatternError::InvalidToken(0)));
+    }
+
+    #[test]
+    fn code_masks() {
+        let pattern = Pattern::with_code_mask(b"\x48\x8B\x00\x00\xE8", "xx??x").unwrap();
+        assert_eq!(pattern, Pattern::parse("48 8B ?? ?? E8").unwrap());
+        assert_eq!(Pattern::with_code_mask(b"\x48", "xx"), Err(PatternError::MaskLength));
+        assert_eq!(Pattern::with_code_mask(b"\x48", "y"), Err(PatternError::InvalidToken(0)));
+    }
+
+    #[test]
+    fn finds_with_wildcards() {
+        let haystack = [0x00, 0x48, 0x8B, 0x05, 0x10, 0xE8, 0x48, 0x8B, 0xFF, 0xFF, 0xE8, 0x48];
+        let pattern = Pattern::parse("48 8B ?? ?? E8").unwrap();
+        assert_eq!(pattern.find_iter(&haystack).collect::<Vec<_>>(), [1, 6]);
+
+        // The trailing 48 8B has no room for the rest.
+        let pattern = Pattern::parse("48 8B ??").unwrap();
+        assert_eq!(pattern.find_iter(&haystack).collect::<Vec<_>>(), [1, 6]);
+
+        let pattern = Pattern::parse("?? E8 4?").unwrap();
+        assert_eq!(pattern.find_iter(&haystack).collect::<Vec<_>>(), [4, 9]);
+
+        let pattern = Pattern::parse("?F ?F").unwrap();
+        assert_eq!(pattern.find_iter(&haystack).collect::<Vec<_>>(), [8]);
+
+        assert_eq!(Pattern::parse("E8 E8").unwrap().find(&haystack), None);
+        assert_eq!(Pattern::parse("00").unwrap().find(&[]), None);
+    }
+
+    #[test]
+    fn finds_overlapping() {
+        let haystack = vec![0xAA; 40];
+        let pattern = Pattern::parse("AA AA ?? AA").unwrap();
+        assert_eq!(pattern.find_iter(&haystack).count(), 37);
+    }
+}
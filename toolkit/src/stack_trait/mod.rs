@@ -1,5 +1,9 @@
 mod data_buf;
+#[cfg(feature = "alloc")]
+mod small_box;
 mod stack_trait;
 
 pub use data_buf::*;
+#[cfg(feature = "alloc")]
+pub use small_box::*;
 pub use stack_trait::*;
\ No newline at end of file
@@ -0,0 +1,354 @@
+use alloc::alloc::{Layout, dealloc};
+use alloc::boxed::Box;
+use core::fmt;
+use core::marker::Unsize;
+use core::mem;
+use core::ops::{Deref, DerefMut};
+
+use crate::stack_trait::stack_trait::alloc_like;
+use crate::stack_trait::{DataBuf, DynClone, Stacked};
+
+/// `dyn Trait` stored inline in `D` when it fits, on the heap otherwise.
+/// Inline values use the [`Stacked`] layout, so converting between the two
+/// is a copy of the buffer. `Send`/`Sync` follow `T` as for `Box<T>`.
+pub struct SmallBox<T: ?Sized, D: DataBuf>(Repr<T, D>);
+
+enum Repr<T: ?Sized, D: DataBuf> {
+    Inline(Stacked<T, D>),
+    Heap(Box<T>),
+}
+
+impl<T: ?Sized, D: DataBuf> SmallBox<T, D> {
+    pub fn new<U>(value: U) -> Self
+    where
+        U: Unsize<T>,
+    {
+        match Stacked::new(value) {
+            Ok(stacked) => Self(Repr::Inline(stacked)),
+            Err(value) => {
+                let boxed: Box<T> = Box::<U>::new(value);
+                Self(Repr::Heap(boxed))
+            }
+        }
+    }
+
+    /// Moves the value inline if it fits, otherwise keeps the allocation.
+    pub fn from_box(boxed: Box<T>) -> Self {
+        match Stacked::from_box(boxed) {
+            Ok(stacked) => Self(Repr::Inline(stacked)),
+            Err(boxed) => Self(Repr::Heap(boxed)),
+        }
+    }
+
+    pub fn is_inline(&self) -> bool {
+        matches!(self.0, Repr::Inline(_))
+    }
+
+    pub fn into_box(self) -> Box<T> {
+        match self.0 {
+            Repr::Inline(stacked) => stacked.into_box(),
+            Repr::Heap(boxed) => boxed,
+        }
+    }
+
+    /// Hands `self` back if the value is on the heap and does not fit `D`.
+    pub fn into_stacked(self) -> Result<Stacked<T, D>, Self> {
+        match self.0 {
+            Repr::Inline(stacked) => Ok(stacked),
+            Repr::Heap(boxed) => Stacked::from_box(boxed).map_err(|boxed| Self(Repr::Heap(boxed))),
+        }
+    }
+}
+
+impl<T: ?Sized, D: DataBuf> From<Stacked<T, D>> for SmallBox<T, D> {
+    fn from(stacked: Stacked<T, D>) -> Self {
+        Self(Repr::Inline(stacked))
+    }
+}
+
+impl<T: ?Sized, D: DataBuf> From<Box<T>> for SmallBox<T, D> {
+    fn from(boxed: Box<T>) -> Self {
+        Self::from_box(boxed)
+    }
+}
+
+impl<T: ?Sized, D: DataBuf> Deref for SmallBox<T, D> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        match &self.0 {
+            Repr::Inline(stacked) => stacked,
+            Repr::Heap(boxed) => boxed,
+        }
+    }
+}
+
+impl<T: ?Sized, D: DataBuf> DerefMut for SmallBox<T, D> {
+    fn deref_mut(&mut self) -> &mut T {
+        match &mut self.0 {
+            Repr::Inline(stacked) => stacked,
+            Repr::Heap(boxed) => boxed,
+        }
+    }
+}
+
+/// Clones keep the representation of the original.
+impl<T: ?Sized + DynClone, D: DataBuf> Clone for SmallBox<T, D> {
+    fn clone(&self) -> Self {
+        match &self.0 {
+            Repr::Inline(stacked) => match stacked.try_clone() {
+                Some(stacked) => Self(Repr::Inline(stacked)),
+                None => Self(Repr::Heap(clone_boxed(&**stacked))),
+            },
+            Repr::Heap(boxed) => Self(Repr::Heap(clone_boxed(&**boxed))),
+        }
+    }
+}
+
+fn clone_boxed<T: ?Sized + DynClone>(value: &T) -> Box<T> {
+    /// Frees the block if `clone_to` unwinds.
+    struct Guard(*mut u8, Layout);
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            if self.1.size() != 0 {
+                unsafe { dealloc(self.0, self.1) };
+            }
+        }
+    }
+
+    unsafe {
+        let dst = alloc_like(value);
+        let guard = Guard(dst as *mut u8, Layout::for_value(value));
+        value.clone_to(dst as *mut u8);
+        mem::forget(guard);
+
+        Box::from_raw(dst)
+    }
+}
+
+impl<T: ?Sized + PartialEq, D: DataBuf> PartialEq for SmallBox<T, D> {
+    fn eq(&self, other: &Self) -> bool {
+        self.deref().eq(other.deref())
+    }
+}
+
+impl<T: ?Sized + fmt::Debug, D: DataBuf> fmt::Debug for SmallBox<T, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.deref().fmt(f)
+    }
+}
+
+impl<T: ?Sized + fmt::Display, D: DataBuf> fmt::Display for SmallBox<T, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.deref().fmt(f)
+    }
+}
+
+impl<T: ?Sized, D: DataBuf> AsRef<T> for SmallBox<T, D> {
+    fn as_ref(&self) -> &T {
+        self.deref()
+    }
+}
+
+impl<T: ?Sized, D: DataBuf> AsMut<T> for SmallBox<T, D> {
+    fn as_mut(&mut self) -> &mut T {
+        self.deref_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stack_trait::Buf;
+    use alloc::rc::Rc;
+    use alloc::string::String;
+    use core::any::Any;
+    use core::cell::Cell;
+
+    type Small = Buf<usize, 4>;
+
+    trait Counter: DynClone {
+        fn get(&self) -> u64;
+        fn bump(&mut self);
+    }
+
+    #[derive(Clone)]
+    struct Tiny(u64, Rc<()>);
+
+    impl Counter for Tiny {
+        fn get(&self) -> u64 {
+            self.0
+        }
+
+        fn bump(&mut self) {
+            self.0 += 1;
+        }
+    }
+
+    #[derive(Clone)]
+    struct Large([u64; 16], Rc<()>);
+
+    impl Counter for Large {
+        fn get(&self) -> u64 {
+            self.0.iter().sum()
+        }
+
+        fn bump(&mut self) {
+            self.0[15] += 1;
+        }
+    }
+
+    #[derive(Clone)]
+    struct Empty;
+
+    impl Counter for Empty {
+        fn get(&self) -> u64 {
+            0
+        }
+
+        fn bump(&mut self) {}
+    }
+
+    #[repr(align(64))]
+    #[derive(Clone)]
+    struct OverAligned(u64);
+
+    impl Counter for OverAligned {
+        fn get(&self) -> u64 {
+            self.0
+        }
+
+        fn bump(&mut self) {
+            self.0 += 1;
+        }
+    }
+
+    trait Anything {}
+
+    impl<T> Anything for T {}
+
+    struct DropFlag<'a>(&'a Cell<u32>);
+
+    impl Drop for DropFlag<'_> {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    #[test]
+    fn placement() {
+        let rc = Rc::new(());
+        let tiny: SmallBox<dyn Counter, Small> = SmallBox::new(Tiny(1, rc.clone()));
+        let large: SmallBox<dyn Counter, Small> = SmallBox::new(Large([1; 16], rc.clone()));
+        let empty: SmallBox<dyn Counter, Small> = SmallBox::new(Empty);
+        let aligned: SmallBox<dyn Counter, Small> = SmallBox::new(OverAligned(7));
+
+        assert!(tiny.is_inline());
+        assert!(!large.is_inline());
+        assert!(empty.is_inline());
+        assert!(!aligned.is_inline());
+        assert_eq!((tiny.get(), large.get(), empty.get(), aligned.get()), (1, 16, 0, 7));
+        assert_eq!(&*aligned as *const dyn Counter as *const u8 as usize % 64, 0);
+    }
+
+    #[test]
+    fn deref_mut_and_drop() {
+        let rc = Rc::new(());
+        let mut tiny: SmallBox<dyn Counter, Small> = SmallBox::new(Tiny(1, rc.clone()));
+        let mut large: SmallBox<dyn Counter, Small> = SmallBox::new(Large([0; 16], rc.clone()));
+
+        tiny.bump();
+        large.bump();
+        assert_eq!((tiny.get(), large.get()), (2, 1));
+        assert_eq!(Rc::strong_count(&rc), 3);
+
+        drop(tiny);
+        drop(large);
+        assert_eq!(Rc::strong_count(&rc), 1);
+
+        let drops = Cell::new(0);
+        let inline: SmallBox<dyn Anything + '_, Small> = SmallBox::new(DropFlag(&drops));
+        let heap: SmallBox<dyn Anything + '_, Small> = SmallBox::new((DropFlag(&drops), [0u64; 8]));
+        assert!(inline.is_inline() && !heap.is_inline());
+        drop(inline);
+        drop(heap);
+        assert_eq!(drops.get(), 2);
+    }
+
+    #[test]
+    fn clone_keeps_representation() {
+        let rc = Rc::new(());
+        let tiny: SmallBox<dyn Counter, Small> = SmallBox::new(Tiny(3, rc.clone()));
+        let large: SmallBox<dyn Counter, Small> = SmallBox::new(Large([2; 16], rc.clone()));
+
+        let mut tiny2 = tiny.clone();
+        let mut large2 = large.clone();
+        tiny2.bump();
+        large2.bump();
+
+        assert!(tiny2.is_inline() && !large2.is_inline());
+        assert_eq!((tiny.get(), tiny2.get()), (3, 4));
+        assert_eq!((large.get(), large2.get()), (32, 33));
+        assert_eq!(Rc::strong_count(&rc), 5);
+
+        let empty: SmallBox<dyn Counter, Small> = SmallBox::new(Empty);
+        assert!(empty.clone().is_inline());
+
+        drop((tiny, tiny2, large, large2));
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+
+    #[test]
+    fn box_round_trips() {
+        let rc = Rc::new(());
+
+        let boxed: Box<dyn Counter> = Box::new(Tiny(5, rc.clone()));
+        let small: SmallBox<dyn Counter, Small> = SmallBox::from_box(boxed);
+        assert!(small.is_inline());
+        let kept = small.into_box();
+        assert_eq!(kept.get(), 5);
+
+        let boxed: Box<dyn Counter> = Box::new(Large([1; 16], rc.clone()));
+        let small: SmallBox<dyn Counter, Small> = boxed.into();
+        assert!(!small.is_inline());
+        let small = small.into_stacked().err().unwrap();
+        assert_eq!(small.into_box().get(), 16);
+
+        let stacked: Stacked<dyn Counter, Small> = Stacked::new(Tiny(9, rc.clone())).ok().unwrap();
+        let small = SmallBox::from(stacked);
+        assert_eq!(small.into_stacked().ok().unwrap().get(), 9);
+
+        let empty: SmallBox<dyn Counter, Small> = SmallBox::from_box(Box::new(Empty));
+        assert!(empty.is_inline());
+        assert_eq!(empty.into_box().get(), 0);
+
+        drop(kept);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+
+    #[test]
+    fn slices_and_str() {
+        let short: SmallBox<[u16], Small> = SmallBox::new([1u16, 2, 3]);
+        let long: SmallBox<[u16], Small> = SmallBox::new([7u16; 64]);
+        assert!(short.is_inline() && !long.is_inline());
+        assert_eq!(&*short, &[1, 2, 3]);
+        assert_eq!(long.len(), 64);
+
+        let text: SmallBox<str, Small> = SmallBox::from_box(Box::from("inline"));
+        let heap: SmallBox<str, Small> = SmallBox::from_box(String::from("a string that needs the heap").into_boxed_str());
+        assert!(text.is_inline() && !heap.is_inline());
+        assert_eq!(&*text, "inline");
+        assert_eq!(heap.into_box().len(), 28);
+    }
+
+    #[test]
+    fn auto_traits_follow_t() {
+        fn assert_send<T: Send>() {}
+        fn assert_sync<T: Sync>() {}
+
+        assert_send::<SmallBox<dyn Any + Send, Small>>();
+        assert_sync::<SmallBox<dyn Any + Send + Sync, Small>>();
+        assert_send::<SmallBox<[u8], Small>>();
+    }
+}
@@ -1,10 +1,36 @@
+use core::any::Any;
 use core::fmt;
 use core::marker::PhantomData;
-use core::mem::{self, MaybeUninit};
+use core::mem::{self, ManuallyDrop, MaybeUninit};
 use core::ops::{Deref, DerefMut};
 use core::ptr;
 
-use crate::stack_trait::{DataBuf, Pod};
+#[cfg(feature = "alloc")]
+use alloc::alloc::{Layout, alloc, dealloc, handle_alloc_error};
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+
+use crate::stack_trait::{Buf, DataBuf, Pod};
+
+/// Object-safe cloning into raw storage. Add it as a supertrait
+/// (`trait Strategy: DynClone`) to make `Stacked<dyn Strategy, _>` clonable;
+/// every `Clone` type implements it.
+///
+/// # Safety
+/// `clone_to` must leave a fully initialized value of `Self` at `dst`, as the
+/// clone is used as one without further checks.
+pub unsafe trait DynClone {
+    /// # Safety
+    /// `dst` must be valid for writes of `size_of_val(self)` bytes and aligned
+    /// to `align_of_val(self)`.
+    unsafe fn clone_to(&self, dst: *mut u8);
+}
+
+unsafe impl<T: Clone> DynClone for T {
+    unsafe fn clone_to(&self, dst: *mut u8) {
+        (dst as *mut T).write(self.clone());
+    }
+}
 
 #[repr(C)]
 pub struct Stacked<T: ?Sized, D: DataBuf> {
@@ -18,31 +44,39 @@ impl<T: ?Sized, D: DataBuf> Stacked<T, D> {
         U: Sized + core::marker::Unsize<T>,
         D: Default,
     {
-        let mut data = D::default();
-        let size = mem::size_of::<U>();
-        let meta = MetaInfo::from_ptr(&value as *const U as *const T);
-        let req_words = D::round_to_words(meta.meta_len * mem::size_of::<usize>()) + D::round_to_words(size);
-        
-        if data.extend(req_words).is_err() {
+        let ptr = &value as *const U as *const T;
+        let Some(mut data) = Self::alloc_for(ptr, mem::size_of::<U>(), mem::align_of::<U>()) else {
             return Err(value);
-        }
-        
-        unsafe {
-            let buf = data.as_mut();
-            let ptr = buf.as_mut_ptr() as *mut U;
-            ptr.write(value);
-            
-            let info_ofs = buf.len() - D::round_to_words(meta.meta_len * mem::size_of::<usize>());
-            let info_dst = &mut buf[info_ofs..];
-            store_metadata(info_dst, &meta.meta[..meta.meta_len]);
-        }
-        
+        };
+
+        unsafe { (data.as_mut().as_mut_ptr() as *mut U).write(value) };
+
         Ok(Self {
             data,
             _marker: PhantomData,
         })
     }
-    
+
+    /// Reserves room for a value of `size` bytes and stores the metadata of
+    /// `ptr` behind it. The value itself is left for the caller to write.
+    /// Values aligned above `D::Inner` are rejected, as the buffer starts
+    /// only at that alignment.
+    fn alloc_for(ptr: *const T, size: usize, align: usize) -> Option<D> {
+        if align > mem::align_of::<D::Inner>() {
+            return None;
+        }
+
+        let meta = MetaInfo::from_ptr(ptr);
+        let meta_words = D::round_to_words(meta.meta_len * WORD_SIZE);
+        let mut data = D::default();
+        data.extend(meta_words + D::round_to_words(size)).ok()?;
+
+        let buf = data.as_mut();
+        let info_ofs = buf.len() - meta_words;
+        store_metadata(&mut buf[info_ofs..], meta.words());
+        Some(data)
+    }
+
     unsafe fn as_ptr(&self) -> *mut T {
         let buf = self.data.as_ref();
         let info_size = mem::size_of::<*mut T>() / mem::size_of::<usize>() - 1;
@@ -59,46 +93,175 @@ impl<T: ?Sized, D: DataBuf> Stacked<T, D> {
         make_fat_ptr(data.as_mut_ptr() as *mut (), &meta)
     }
     
-    pub fn into_inner(mut self) -> T
+    pub fn into_inner(self) -> T
     where
         T: Sized,
     {
-        unsafe { ptr::read(self.data.as_mut().as_mut_ptr() as *const T) }
+        let this = ManuallyDrop::new(self);
+        unsafe { ptr::read(this.as_ptr()) }
     }
-    
+
+    /// `None` only if a clone does not fit a fresh `D`, which cannot happen
+    /// for fixed-size buffers such as [`Buf`].
+    pub fn try_clone(&self) -> Option<Self>
+    where
+        T: DynClone,
+    {
+        let mut data = Self::alloc_for(unsafe { self.as_ptr() }, mem::size_of_val(&**self), mem::align_of_val(&**self))?;
+        unsafe { (**self).clone_to(data.as_mut().as_mut_ptr() as *mut u8) };
+
+        Some(Stacked {
+            data,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Moves the value into another kind of buffer, handing `self` back if
+    /// it does not fit.
+    pub fn into_buf<D2: DataBuf>(self) -> Result<Stacked<T, D2>, Self> {
+        let src = unsafe { self.as_ptr() };
+        let size = mem::size_of_val(&*self);
+
+        let Some(mut data) = Stacked::<T, D2>::alloc_for(src, size, mem::align_of_val(&*self)) else {
+            return Err(self);
+        };
+
+        unsafe { ptr::copy_nonoverlapping(src as *const u8, data.as_mut().as_mut_ptr() as *mut u8, size) };
+        mem::forget(self);
+
+        Ok(Stacked {
+            data,
+            _marker: PhantomData,
+        })
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn into_box(self) -> Box<T> {
+        unsafe {
+            let src = self.as_ptr();
+            let dst = alloc_like(src);
+            ptr::copy_nonoverlapping(src as *const u8, dst as *mut u8, mem::size_of_val_raw(src));
+            mem::forget(self);
+
+            Box::from_raw(dst)
+        }
+    }
+
+    /// Moves a boxed value onto the stack and frees the heap block, handing
+    /// the box back if the value does not fit.
+    #[cfg(feature = "alloc")]
+    pub fn from_box(boxed: Box<T>) -> Result<Self, Box<T>> {
+        let size = mem::size_of_val(&*boxed);
+
+        let Some(mut data) = Self::alloc_for(&*boxed, size, mem::align_of_val(&*boxed)) else {
+            return Err(boxed);
+        };
+
+        unsafe {
+            let raw = Box::into_raw(boxed);
+            ptr::copy_nonoverlapping(raw as *const u8, data.as_mut().as_mut_ptr() as *mut u8, size);
+
+            let layout = Layout::for_value_raw(raw);
+            if layout.size() != 0 {
+                dealloc(raw as *mut u8, layout);
+            }
+        }
+
+        Ok(Self {
+            data,
+            _marker: PhantomData,
+        })
+    }
+
     pub fn capacity(&self) -> usize {
         self.data.as_ref().len() * mem::size_of::<D::Inner>()
     }
 }
 
-struct MetaInfo {
-    data_ptr: *const (),
-    meta_len: usize,
-    meta: [usize; 3],
+impl<T: ?Sized, W: Pod, const N: usize> Stacked<T, Buf<W, N>> {
+    /// Moves the value into a `Buf` of `M` words, growing or shrinking it.
+    pub fn resize<const M: usize>(self) -> Result<Stacked<T, Buf<W, M>>, Self> {
+        self.into_buf()
+    }
 }
 
-fn mem_as_slice<T>(ptr: &mut T) -> &mut [usize] {
-    let words = mem::size_of::<T>() / mem::size_of::<usize>();
-    unsafe { core::slice::from_raw_parts_mut(ptr as *mut _ as *mut usize, words) }
+macro_rules! impl_downcast {
+    ( $($any:ty),* ) => {
+        $(
+            impl<D: DataBuf> Stacked<$any, D> {
+                pub fn is<U: Any>(&self) -> bool {
+                    (**self).is::<U>()
+                }
+
+                pub fn downcast_ref<U: Any>(&self) -> Option<&U> {
+                    (**self).downcast_ref()
+                }
+
+                pub fn downcast_mut<U: Any>(&mut self) -> Option<&mut U> {
+                    (**self).downcast_mut()
+                }
+
+                pub fn downcast<U: Any>(self) -> Result<U, Self> {
+                    if !self.is::<U>() {
+                        return Err(self);
+                    }
+
+                    let this = ManuallyDrop::new(self);
+                    Ok(unsafe { ptr::read(this.as_ptr() as *const U) })
+                }
+            }
+        )*
+    }
 }
 
+impl_downcast! { dyn Any, dyn Any + Send, dyn Any + Send + Sync }
+
+/// Heap block laid out for `*like`, returned as a pointer carrying the same
+/// metadata. The contents are uninitialized; zero-sized values get a dangling,
+/// aligned pointer as `Box` expects.
+#[cfg(feature = "alloc")]
+pub(super) unsafe fn alloc_like<T: ?Sized>(like: *const T) -> *mut T {
+    let layout = Layout::for_value_raw(like);
+
+    let dst = if layout.size() == 0 {
+        ptr::without_provenance_mut::<u8>(layout.align())
+    } else {
+        let dst = alloc(layout);
+        if dst.is_null() {
+            handle_alloc_error(layout);
+        }
+        dst
+    };
+
+    make_fat_ptr(dst as *mut (), MetaInfo::from_ptr(like).words())
+}
+
+/// Metadata words of a fat pointer. Kept as `MaybeUninit` so a vtable
+/// pointer keeps its provenance while it sits in the buffer.
+struct MetaInfo {
+    meta_len: usize,
+    meta: [MaybeUninit<usize>; 3],
+}
 
 impl MetaInfo {
-    pub fn from_ptr<T: ?Sized>(mut ptr: *const T) -> Self {
-        let addr = ptr as *const ();
-        let rv = mem_as_slice(&mut ptr);
-        let mut vals = [0; 3];
-        vals[..rv.len() - 1].copy_from_slice(&rv[1..]);
-        
-        MetaInfo {
-            data_ptr: addr,
-            meta_len: rv.len() - 1,
-            meta: vals,
+    pub fn from_ptr<T: ?Sized>(ptr: *const T) -> Self {
+        let meta_len = mem::size_of::<*const T>() / WORD_SIZE - 1;
+        let mut meta = [MaybeUninit::uninit(); 3];
+
+        unsafe {
+            let words = &ptr as *const *const T as *const MaybeUninit<usize>;
+            ptr::copy_nonoverlapping(words.add(1), meta.as_mut_ptr(), meta_len);
         }
+
+        MetaInfo { meta_len, meta }
+    }
+
+    fn words(&self) -> &[MaybeUninit<usize>] {
+        &self.meta[..self.meta_len]
     }
 }
 
-fn store_metadata<W: Pod>(dst: &mut [MaybeUninit<W>], meta_words: &[usize]) {
+fn store_metadata<W: Pod>(dst: &mut [MaybeUninit<W>], meta_words: &[MaybeUninit<usize>]) {
     let n_bytes = core::mem::size_of_val(meta_words);
     unsafe {
         ptr::copy(
@@ -109,14 +272,6 @@ fn store_metadata<W: Pod>(dst: &mut [MaybeUninit<W>], meta_words: &[usize]) {
     }
 }
 
-fn read_metadata(src: *const usize, len: usize) -> [usize; 4] {
-    unsafe {
-        let mut meta = [0usize; 4];
-        ptr::copy_nonoverlapping(src, meta.as_mut_ptr(), len);
-        meta
-    }
-}
-
 fn make_fat_ptr<T: ?Sized, W: Pod>(data: *mut (), meta: &[MaybeUninit<W>]) -> *mut T {
     unsafe {
 
@@ -124,7 +279,7 @@ fn make_fat_ptr<T: ?Sized, W: Pod>(data: *mut (), meta: &[MaybeUninit<W>]) -> *m
         #[derive(Copy, Clone)]
         struct Raw {
             ptr: *const (),
-            meta: [usize; 4],
+            meta: [MaybeUninit<usize>; 4],
         }
 
         union Inner<T: ?Sized> {
@@ -135,7 +290,7 @@ fn make_fat_ptr<T: ?Sized, W: Pod>(data: *mut (), meta: &[MaybeUninit<W>]) -> *m
         let mut rv = Inner {
             raw: Raw {
                 ptr: data,
-                meta: [0; 4],
+                meta: [MaybeUninit::uninit(); 4],
             },
         };
         
@@ -176,36 +331,9 @@ impl<T: ?Sized, D: DataBuf> Drop for Stacked<T, D> {
 
 const WORD_SIZE: usize = mem::size_of::<usize>();
 
-impl<T: ?Sized + Clone, D: DataBuf> Clone for Stacked<T, D>
-where
-    T: Clone,
-    D: Default,
-{
+impl<T: ?Sized + DynClone, D: DataBuf> Clone for Stacked<T, D> {
     fn clone(&self) -> Self {
-        let mut data = D::default();
-        unsafe {
-            let src = self.as_ptr() as *const u8;
-            let size = mem::size_of_val(&**self);
-            let info_size = mem::size_of::<*mut T>() / WORD_SIZE - 1;
-            let req_words = D::round_to_words(info_size * WORD_SIZE) + D::round_to_words(size);
-            data.extend(req_words).unwrap();
-            
-            let dst = data.as_mut().as_mut_ptr() as *mut u8;
-            ptr::copy_nonoverlapping(src, dst, size);
-            
-            let info_ofs = data.as_ref().len() - D::round_to_words(info_size * WORD_SIZE);
-            let meta_ptr = data.as_mut()[info_ofs..].as_mut_ptr() as *mut usize;
-            let meta = read_metadata(
-                self.data.as_ref()[info_ofs..].as_ptr() as *const usize,
-                info_size,
-            );
-            ptr::copy_nonoverlapping(meta.as_ptr(), meta_ptr, info_size);
-        }
-        
-        Stacked {
-            data,
-            _marker: PhantomData,
-        }
+        self.try_clone().expect("clone does not fit the buffer")
     }
 }
 
@@ -231,4 +359,162 @@ impl<T: ?Sized, D: DataBuf> AsMut<T> for Stacked<T, D> {
     fn as_mut(&mut self) -> &mut T {
         self.deref_mut()
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use super::*;
+    use alloc::rc::Rc;
+    use alloc::string::String;
+
+    type Small = Buf<usize, 4>;
+
+    trait Shape: DynClone {
+        fn area(&self) -> u64;
+        fn grow(&mut self);
+    }
+
+    #[derive(Clone)]
+    struct Square(u64, Rc<()>);
+
+    impl Shape for Square {
+        fn area(&self) -> u64 {
+            self.0 * self.0
+        }
+
+        fn grow(&mut self) {
+            self.0 += 1;
+        }
+    }
+
+    #[derive(Clone)]
+    struct Point;
+
+    impl Shape for Point {
+        fn area(&self) -> u64 {
+            0
+        }
+
+        fn grow(&mut self) {}
+    }
+
+    #[test]
+    fn fit_and_alignment() {
+        let rc = Rc::new(());
+        let fits: Result<Stacked<dyn Shape, Small>, _> = Stacked::new(Square(2, rc.clone()));
+        assert_eq!(fits.ok().unwrap().area(), 4);
+
+        // Two words of value plus one of vtable.
+        let too_small: Result<Stacked<dyn Shape, Buf<usize, 2>>, _> = Stacked::new(Square(2, rc.clone()));
+        assert_eq!(too_small.err().unwrap().0, 2);
+
+        // A byte buffer is only byte aligned.
+        let misaligned: Result<Stacked<dyn Shape, Buf<u8, 64>>, _> = Stacked::new(Square(2, rc.clone()));
+        assert!(misaligned.is_err());
+        drop(misaligned);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+
+    #[test]
+    fn try_clone() {
+        let rc = Rc::new(());
+        let a: Stacked<dyn Shape, Small> = Stacked::new(Square(3, rc.clone())).ok().unwrap();
+        let mut b = a.try_clone().unwrap();
+        assert_eq!(Rc::strong_count(&rc), 3);
+
+        b.grow();
+        assert_eq!((a.area(), b.area()), (9, 16));
+
+        let c = b.clone();
+        drop((a, b));
+        assert_eq!(c.area(), 16);
+        assert_eq!(Rc::strong_count(&rc), 2);
+
+        let point: Stacked<dyn Shape, Small> = Stacked::new(Point).ok().unwrap();
+        assert_eq!(point.try_clone().unwrap().area(), 0);
+    }
+
+    #[test]
+    fn resize_and_into_buf() {
+        let rc = Rc::new(());
+        let s: Stacked<dyn Shape, Small> = Stacked::new(Square(5, rc.clone())).ok().unwrap();
+
+        let big = s.resize::<16>().ok().unwrap();
+        assert_eq!(big.capacity(), 3 * mem::size_of::<usize>());
+
+        let big = big.resize::<2>().err().unwrap();
+        let exact = big.resize::<3>().ok().unwrap();
+        let exact = exact.into_buf::<Buf<u8, 64>>().err().unwrap();
+        assert_eq!(exact.area(), 25);
+        assert_eq!(Rc::strong_count(&rc), 2);
+
+        drop(exact);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+
+    #[test]
+    fn slices() {
+        let s: Stacked<[u16], Small> = Stacked::new([1u16, 2, 3]).ok().unwrap();
+        assert_eq!(&*s, &[1, 2, 3]);
+
+        let strings: Stacked<[String], Buf<usize, 8>> = Stacked::new([String::from("a"), String::from("bc")]).ok().unwrap();
+        assert_eq!(strings.concat(), "abc");
+    }
+
+    #[test]
+    fn downcasts() {
+        let rc = Rc::new(5u32);
+        let mut s: Stacked<dyn Any, Small> = Stacked::new(rc.clone()).ok().unwrap();
+        assert!(s.is::<Rc<u32>>());
+        assert!(!s.is::<u32>());
+        assert!(s.downcast_ref::<u32>().is_none());
+        assert_eq!(**s.downcast_ref::<Rc<u32>>().unwrap(), 5);
+
+        *s.downcast_mut::<Rc<u32>>().unwrap() = Rc::new(6);
+        assert_eq!(Rc::strong_count(&rc), 1);
+
+        let s = s.downcast::<String>().err().unwrap();
+        let six: Rc<u32> = s.downcast().ok().unwrap();
+        assert_eq!(*six, 6);
+        assert_eq!(Rc::strong_count(&six), 1);
+
+        let send: Stacked<dyn Any + Send, Small> = Stacked::new(3u8).ok().unwrap();
+        assert_eq!(send.downcast::<u8>().ok(), Some(3));
+
+        let sync: Stacked<dyn Any + Send + Sync, Small> = Stacked::new(4u64).ok().unwrap();
+        assert_eq!(sync.downcast_ref::<u64>(), Some(&4));
+        assert_eq!(sync.downcast::<i64>().err().unwrap().downcast::<u64>().ok(), Some(4));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn boxes() {
+        let rc = Rc::new(());
+        let s: Stacked<dyn Shape, Small> = Stacked::new(Square(4, rc.clone())).ok().unwrap();
+
+        let boxed: Box<dyn Shape> = s.into_box();
+        assert_eq!(boxed.area(), 16);
+        assert_eq!(Rc::strong_count(&rc), 2);
+
+        let back: Stacked<dyn Shape, Small> = Stacked::from_box(boxed).ok().unwrap();
+        assert_eq!(back.area(), 16);
+        assert_eq!(Rc::strong_count(&rc), 2);
+
+        let boxed = back.into_box();
+        let refused: Result<Stacked<dyn Shape, Buf<usize, 2>>, _> = Stacked::from_box(boxed);
+        assert_eq!(refused.err().unwrap().area(), 16);
+        assert_eq!(Rc::strong_count(&rc), 1);
+
+        let point: Stacked<dyn Shape, Small> = Stacked::new(Point).ok().unwrap();
+        let point: Stacked<dyn Shape, Small> = Stacked::from_box(point.into_box()).ok().unwrap();
+        assert_eq!(point.area(), 0);
+
+        let slice: Box<[u16]> = Stacked::<[u16], Small>::new([7u16, 8]).ok().unwrap().into_box();
+        assert_eq!(&*slice, &[7, 8]);
+
+        let large: Box<dyn Any> = Box::new([7u64; 8]);
+        let large = Stacked::<dyn Any, Small>::from_box(large).err().unwrap();
+        assert_eq!(large.downcast_ref::<[u64; 8]>().unwrap()[0], 7);
+    }
+}
@@ -1,2 +0,0 @@
-
-static mut BUFFER: [u8; 1024] = [0; 1024];
\ No newline at end of file
@@ -0,0 +1,496 @@
+use core::fmt;
+
+use crate::{FileError, ThreadError};
+
+/// Win32 error codes used by the table.
+mod win32 {
+    pub const ERROR_SUCCESS: u32 = 0;
+    pub const ERROR_INVALID_FUNCTION: u32 = 1;
+    pub const ERROR_FILE_NOT_FOUND: u32 = 2;
+    pub const ERROR_PATH_NOT_FOUND: u32 = 3;
+    pub const ERROR_TOO_MANY_OPEN_FILES: u32 = 4;
+    pub const ERROR_ACCESS_DENIED: u32 = 5;
+    pub const ERROR_INVALID_HANDLE: u32 = 6;
+    pub const ERROR_NOT_ENOUGH_MEMORY: u32 = 8;
+    pub const ERROR_NOT_READY: u32 = 21;
+    pub const ERROR_BAD_COMMAND: u32 = 22;
+    pub const ERROR_CRC: u32 = 23;
+    pub const ERROR_BAD_LENGTH: u32 = 24;
+    pub const ERROR_GEN_FAILURE: u32 = 31;
+    pub const ERROR_SHARING_VIOLATION: u32 = 32;
+    pub const ERROR_HANDLE_EOF: u32 = 38;
+    pub const ERROR_NOT_SUPPORTED: u32 = 50;
+    pub const ERROR_INVALID_PARAMETER: u32 = 87;
+    pub const ERROR_BROKEN_PIPE: u32 = 109;
+    pub const ERROR_DISK_FULL: u32 = 112;
+    pub const ERROR_INSUFFICIENT_BUFFER: u32 = 122;
+    pub const ERROR_INVALID_NAME: u32 = 123;
+    pub const ERROR_MOD_NOT_FOUND: u32 = 126;
+    pub const ERROR_PROC_NOT_FOUND: u32 = 127;
+    pub const ERROR_DIR_NOT_EMPTY: u32 = 145;
+    pub const ERROR_BAD_PATHNAME: u32 = 161;
+    pub const ERROR_ALREADY_EXISTS: u32 = 183;
+    pub const ERROR_BAD_EXE_FORMAT: u32 = 193;
+    pub const ERROR_MORE_DATA: u32 = 234;
+    pub const WAIT_TIMEOUT: u32 = 258;
+    pub const ERROR_NO_MORE_ITEMS: u32 = 259;
+    pub const ERROR_DIRECTORY: u32 = 267;
+    pub const ERROR_MR_MID_NOT_FOUND: u32 = 317;
+    pub const ERROR_INVALID_ADDRESS: u32 = 487;
+    pub const ERROR_OPERATION_ABORTED: u32 = 995;
+    pub const ERROR_IO_DEVICE: u32 = 1117;
+    pub const ERROR_IO_PENDING: u32 = 997;
+    pub const ERROR_NOACCESS: u32 = 998;
+    pub const ERROR_STACK_OVERFLOW: u32 = 1001;
+    pub const ERROR_NOT_FOUND: u32 = 1168;
+    pub const ERROR_PRIVILEGE_NOT_HELD: u32 = 1314;
+    pub const ERROR_NO_SYSTEM_RESOURCES: u32 = 1450;
+}
+
+/// Linux errno values used by the table.
+mod errno {
+    pub const OK: i32 = 0;
+    pub const EPERM: i32 = 1;
+    pub const ENOENT: i32 = 2;
+    pub const ESRCH: i32 = 3;
+    pub const EIO: i32 = 5;
+    pub const ENOEXEC: i32 = 8;
+    pub const EBADF: i32 = 9;
+    pub const ENOMEM: i32 = 12;
+    pub const EACCES: i32 = 13;
+    pub const EFAULT: i32 = 14;
+    pub const EBUSY: i32 = 16;
+    pub const EEXIST: i32 = 17;
+    pub const ENODEV: i32 = 19;
+    pub const ENOTDIR: i32 = 20;
+    pub const EISDIR: i32 = 21;
+    pub const EINVAL: i32 = 22;
+    pub const EMFILE: i32 = 24;
+    pub const ENOSPC: i32 = 28;
+    pub const EPIPE: i32 = 32;
+    pub const ERANGE: i32 = 34;
+    pub const ENAMETOOLONG: i32 = 36;
+    pub const ENOSYS: i32 = 38;
+    pub const ENOTEMPTY: i32 = 39;
+    pub const ENODATA: i32 = 61;
+    pub const EOVERFLOW: i32 = 75;
+    pub const EOPNOTSUPP: i32 = 95;
+    pub const ETIMEDOUT: i32 = 110;
+    pub const EINPROGRESS: i32 = 115;
+    pub const ECANCELED: i32 = 125;
+}
+
+use errno::*;
+use win32::*;
+
+/// Facility of NTSTATUS codes wrapping a Win32 error, `NTSTATUS_FROM_WIN32`.
+const FACILITY_NTWIN32: u16 = 0x7;
+
+/// Toolkit-defined facility of customer codes wrapping an errno without a table entry.
+const FACILITY_ERRNO: u16 = 0xFF;
+
+const CUSTOMER_BIT: u32 = 1 << 29;
+
+/// Top two bits of an NTSTATUS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Success,
+    Informational,
+    Warning,
+    Error,
+}
+
+struct Entry {
+    status: Status,
+    name: &'static str,
+    win32: u32,
+    errno: i32,
+    message: &'static str,
+}
+
+/// Defines a `Status` constant and a table entry per line:
+/// `NAME = code, win32 error, errno, "message";`. The first line with a given
+/// Win32 error or errno is the one the reverse conversions pick.
+macro_rules! statuses {
+    ($($name:ident = $code:literal, $win32:ident, $errno:ident, $message:literal;)*) => {
+        impl Status {
+            $(pub const $name: Status = Status($code as u32 as i32);)*
+        }
+
+        const TABLE: &[Entry] = &[
+            $(Entry {
+                status: Status::$name,
+                name: concat!("STATUS_", stringify!($name)),
+                win32: $win32,
+                errno: $errno,
+                message: $message,
+            },)*
+        ];
+    };
+}
+
+statuses! {
+    SUCCESS = 0x00000000, ERROR_SUCCESS, OK, "The operation completed successfully";
+    // Ahead of UNSUCCESSFUL so that EIO converts to it.
+    IO_DEVICE_ERROR = 0xC0000185, ERROR_IO_DEVICE, EIO, "An I/O device error occurred";
+    TIMEOUT = 0x00000102, WAIT_TIMEOUT, ETIMEDOUT, "The wait timed out";
+    PENDING = 0x00000103, ERROR_IO_PENDING, EINPROGRESS, "The operation is still in progress";
+    BUFFER_OVERFLOW = 0x80000005, ERROR_MORE_DATA, EOVERFLOW, "The data was too large for the buffer";
+    NO_MORE_ENTRIES = 0x8000001A, ERROR_NO_MORE_ITEMS, ENODATA, "No more entries are available";
+    UNSUCCESSFUL = 0xC0000001, ERROR_GEN_FAILURE, EIO, "The operation was unsuccessful";
+    NOT_IMPLEMENTED = 0xC0000002, ERROR_INVALID_FUNCTION, ENOSYS, "The function is not implemented";
+    INVALID_INFO_CLASS = 0xC0000003, ERROR_INVALID_PARAMETER, EINVAL, "Invalid information class";
+    INFO_LENGTH_MISMATCH = 0xC0000004, ERROR_BAD_LENGTH, EINVAL, "The buffer length does not match the information class";
+    ACCESS_VIOLATION = 0xC0000005, ERROR_NOACCESS, EFAULT, "Access violation";
+    INVALID_HANDLE = 0xC0000008, ERROR_INVALID_HANDLE, EBADF, "Invalid handle";
+    INVALID_CID = 0xC000000B, ERROR_INVALID_PARAMETER, ESRCH, "Invalid client id";
+    INVALID_PARAMETER = 0xC000000D, ERROR_INVALID_PARAMETER, EINVAL, "Invalid parameter";
+    NO_SUCH_FILE = 0xC000000F, ERROR_FILE_NOT_FOUND, ENOENT, "The file does not exist";
+    END_OF_FILE = 0xC0000011, ERROR_HANDLE_EOF, ENODATA, "End of file";
+    NO_MEMORY = 0xC0000017, ERROR_NOT_ENOUGH_MEMORY, ENOMEM, "Not enough memory";
+    CONFLICTING_ADDRESSES = 0xC0000018, ERROR_INVALID_ADDRESS, EEXIST, "The address range is already in use";
+    INVALID_SYSTEM_SERVICE = 0xC000001C, ERROR_INVALID_FUNCTION, ENOSYS, "Invalid system service number";
+    ACCESS_DENIED = 0xC0000022, ERROR_ACCESS_DENIED, EACCES, "Access denied";
+    BUFFER_TOO_SMALL = 0xC0000023, ERROR_INSUFFICIENT_BUFFER, ERANGE, "The buffer is too small";
+    OBJECT_TYPE_MISMATCH = 0xC0000024, ERROR_INVALID_HANDLE, EINVAL, "The handle refers to another object type";
+    OBJECT_NAME_INVALID = 0xC0000033, ERROR_INVALID_NAME, EINVAL, "Invalid object name";
+    OBJECT_NAME_NOT_FOUND = 0xC0000034, ERROR_FILE_NOT_FOUND, ENOENT, "Object name not found";
+    OBJECT_NAME_COLLISION = 0xC0000035, ERROR_ALREADY_EXISTS, EEXIST, "Object name already exists";
+    OBJECT_PATH_NOT_FOUND = 0xC000003A, ERROR_PATH_NOT_FOUND, ENOENT, "Path not found";
+    OBJECT_PATH_SYNTAX_BAD = 0xC000003B, ERROR_BAD_PATHNAME, ENAMETOOLONG, "Path syntax bad";
+    DATA_ERROR = 0xC000003E, ERROR_CRC, EIO, "Invalid data";
+    SHARING_VIOLATION = 0xC0000043, ERROR_SHARING_VIOLATION, EBUSY, "Sharing violation";
+    THREAD_IS_TERMINATING = 0xC000004B, ERROR_ACCESS_DENIED, ESRCH, "The thread is terminating";
+    PRIVILEGE_NOT_HELD = 0xC0000061, ERROR_PRIVILEGE_NOT_HELD, EPERM, "A required privilege is not held";
+    INVALID_IMAGE_FORMAT = 0xC000007B, ERROR_BAD_EXE_FORMAT, ENOEXEC, "Invalid image format";
+    DISK_FULL = 0xC000007F, ERROR_DISK_FULL, ENOSPC, "The disk is full";
+    INSUFFICIENT_RESOURCES = 0xC000009A, ERROR_NO_SYSTEM_RESOURCES, ENOMEM, "Insufficient system resources";
+    FILE_IS_A_DIRECTORY = 0xC00000BA, ERROR_ACCESS_DENIED, EISDIR, "The file is a directory";
+    NOT_SUPPORTED = 0xC00000BB, ERROR_NOT_SUPPORTED, EOPNOTSUPP, "The request is not supported";
+    STACK_OVERFLOW = 0xC00000FD, ERROR_STACK_OVERFLOW, EFAULT, "Stack overflow";
+    DIRECTORY_NOT_EMPTY = 0xC0000101, ERROR_DIR_NOT_EMPTY, ENOTEMPTY, "The directory is not empty";
+    NOT_A_DIRECTORY = 0xC0000103, ERROR_DIRECTORY, ENOTDIR, "Not a directory";
+    PROCESS_IS_TERMINATING = 0xC000010A, ERROR_ACCESS_DENIED, ESRCH, "The process is terminating";
+    TOO_MANY_OPENED_FILES = 0xC000011F, ERROR_TOO_MANY_OPEN_FILES, EMFILE, "Too many open files";
+    CANCELLED = 0xC0000120, ERROR_OPERATION_ABORTED, ECANCELED, "The operation was cancelled";
+    DLL_NOT_FOUND = 0xC0000135, ERROR_MOD_NOT_FOUND, ENOENT, "DLL not found";
+    ENTRYPOINT_NOT_FOUND = 0xC0000139, ERROR_PROC_NOT_FOUND, ENOENT, "Entry point not found";
+    PIPE_BROKEN = 0xC000014B, ERROR_BROKEN_PIPE, EPIPE, "The pipe is broken";
+    INVALID_DEVICE_STATE = 0xC0000184, ERROR_BAD_COMMAND, EINVAL, "The device is not in a valid state";
+    NOT_FOUND = 0xC0000225, ERROR_NOT_FOUND, ENOENT, "Not found";
+    VOLUME_DISMOUNTED = 0xC000026E, ERROR_NOT_READY, ENODEV, "The volume was dismounted";
+}
+
+/// An NTSTATUS code. Common codes have a symbolic name, a message and a
+/// Win32 error and errno equivalent; others still decode into severity,
+/// facility and code.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Status(pub i32);
+
+impl Status {
+    /// A raw OS error as reported by the platform layer: an NTSTATUS on
+    /// Windows, an errno on Linux.
+    pub fn from_os(code: i32) -> Self {
+        if cfg!(windows) { Self(code) } else { Self::from_errno(code) }
+    }
+
+    /// The table entry for `errno`, or a toolkit customer code that
+    /// [`Status::to_errno`] turns back into `errno`.
+    pub fn from_errno(errno: i32) -> Self {
+        match TABLE.iter().find(|entry| entry.errno == errno) {
+            Some(entry) => entry.status,
+            None => Self::custom(FACILITY_ERRNO, errno as u16),
+        }
+    }
+
+    /// The table entry for `error`, otherwise `NTSTATUS_FROM_WIN32(error)`.
+    pub fn from_win32(error: u32) -> Self {
+        match TABLE.iter().find(|entry| entry.win32 == error) {
+            Some(entry) => entry.status,
+            None => Self((0xC000_0000 | (FACILITY_NTWIN32 as u32) << 16 | (error & 0xFFFF)) as i32),
+        }
+    }
+
+    const fn custom(facility: u16, code: u16) -> Self {
+        Self((0xC000_0000 | CUSTOMER_BIT | (facility as u32 & 0xFFF) << 16 | code as u32) as i32)
+    }
+
+    pub const fn code(self) -> i32 {
+        self.0
+    }
+
+    /// `NT_SUCCESS`: success and informational codes.
+    pub const fn is_success(self) -> bool {
+        self.0 >= 0
+    }
+
+    pub const fn is_error(self) -> bool {
+        matches!(self.severity(), Severity::Error)
+    }
+
+    pub const fn severity(self) -> Severity {
+        match (self.0 as u32) >> 30 {
+            0 => Severity::Success,
+            1 => Severity::Informational,
+            2 => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+
+    /// Set for codes not defined by Microsoft.
+    pub const fn is_customer(self) -> bool {
+        self.0 as u32 & CUSTOMER_BIT != 0
+    }
+
+    pub const fn facility(self) -> u16 {
+        ((self.0 as u32 >> 16) & 0xFFF) as u16
+    }
+
+    /// Low 16 bits, the code within the facility.
+    pub const fn facility_code(self) -> u16 {
+        self.0 as u16
+    }
+
+    /// `STATUS_*` name of a code from the table.
+    pub fn name(self) -> Option<&'static str> {
+        self.entry().map(|entry| entry.name)
+    }
+
+    pub fn message(self) -> Option<&'static str> {
+        self.entry().map(|entry| entry.message)
+    }
+
+    /// The table mapping, the wrapped error of `NTSTATUS_FROM_WIN32` codes,
+    /// and `ERROR_MR_MID_NOT_FOUND` otherwise, like `RtlNtStatusToDosError`.
+    pub fn to_win32(self) -> u32 {
+        if let Some(entry) = self.entry() {
+            entry.win32
+        } else if self.facility() == FACILITY_NTWIN32 && !self.is_customer() {
+            self.facility_code() as u32
+        } else {
+            ERROR_MR_MID_NOT_FOUND
+        }
+    }
+
+    /// The table mapping, the errno wrapped by [`Status::from_errno`], `0`
+    /// for other non-error codes and `EIO` for other errors.
+    pub fn to_errno(self) -> i32 {
+        if let Some(entry) = self.entry() {
+            entry.errno
+        } else if self.is_customer() && self.facility() == FACILITY_ERRNO {
+            self.facility_code() as i32
+        } else if self.is_error() {
+            EIO
+        } else {
+            OK
+        }
+    }
+
+    /// `Ok` when [`Status::is_success`], so `status.ok()?` works.
+    pub fn ok(self) -> Result<(), Self> {
+        if self.is_success() { Ok(()) } else { Err(self) }
+    }
+
+    fn entry(self) -> Option<&'static Entry> {
+        TABLE.iter().find(|entry| entry.status == self)
+    }
+}
+
+impl fmt::Debug for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.name() {
+            Some(name) => write!(f, "{}", name),
+            None => write!(f, "Status(0x{:08X})", self.0 as u32),
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.entry() {
+            Some(entry) => write!(f, "{} (0x{:08X}): {}", entry.name, self.0 as u32, entry.message),
+            None if self.is_customer() && self.facility() == FACILITY_ERRNO => {
+                write!(f, "OS error {}", self.facility_code())
+            }
+            None => write!(f, "NTSTATUS 0x{:08X}", self.0 as u32),
+        }
+    }
+}
+
+impl core::error::Error for Status {}
+
+impl From<i32> for Status {
+    fn from(status: i32) -> Self {
+        Self(status)
+    }
+}
+
+impl From<u32> for Status {
+    fn from(status: u32) -> Self {
+        Self(status as i32)
+    }
+}
+
+impl From<Status> for i32 {
+    fn from(status: Status) -> Self {
+        status.0
+    }
+}
+
+impl From<ThreadError> for Status {
+    fn from(err: ThreadError) -> Self {
+        match err {
+            ThreadError::CreationFailed(code) | ThreadError::WaitFailed(code) | ThreadError::QueryFailed(code) => {
+                Self::from_os(code)
+            }
+            ThreadError::InvalidHandle => Self::INVALID_HANDLE,
+            ThreadError::Timeout => Self::TIMEOUT,
+            ThreadError::InvalidArgument => Self::INVALID_PARAMETER,
+            ThreadError::ThreadRunning => Self::PENDING,
+            ThreadError::ThreadTerminated => Self::THREAD_IS_TERMINATING,
+            ThreadError::NoResult | ThreadError::NotJoinable => Self::INVALID_DEVICE_STATE,
+        }
+    }
+}
+
+impl From<FileError> for Status {
+    fn from(err: FileError) -> Self {
+        match err {
+            FileError::Success => Self::SUCCESS,
+            FileError::ObjectNameNotFound => Self::OBJECT_NAME_NOT_FOUND,
+            FileError::AccessDenied => Self::ACCESS_DENIED,
+            FileError::FileIsDirectory => Self::FILE_IS_A_DIRECTORY,
+            FileError::InvalidParameter => Self::INVALID_PARAMETER,
+            FileError::VolumeDismounted => Self::VOLUME_DISMOUNTED,
+            FileError::FileNotFound => Self::NO_SUCH_FILE,
+            FileError::PathNotFound => Self::OBJECT_PATH_NOT_FOUND,
+            FileError::SharingViolation => Self::SHARING_VIOLATION,
+            FileError::BufferOverflow => Self::BUFFER_OVERFLOW,
+            FileError::EndOfFile | FileError::UnexpectedEof => Self::END_OF_FILE,
+            FileError::PathSyntaxBad => Self::OBJECT_PATH_SYNTAX_BAD,
+            FileError::NameCollision => Self::OBJECT_NAME_COLLISION,
+            FileError::Cancelled => Self::CANCELLED,
+            FileError::InvalidState => Self::INVALID_DEVICE_STATE,
+            FileError::InvalidData => Self::DATA_ERROR,
+            FileError::DiskFull => Self::DISK_FULL,
+            FileError::DirectoryNotEmpty => Self::DIRECTORY_NOT_EMPTY,
+            FileError::TooManyOpenFiles => Self::TOO_MANY_OPENED_FILES,
+            FileError::IoError => Self::IO_DEVICE_ERROR,
+            FileError::Unspecified => Self::UNSUCCESSFUL,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::format;
+
+    #[test]
+    fn table_codes_are_unique() {
+        for (index, entry) in TABLE.iter().enumerate() {
+            assert!(TABLE[index + 1..].iter().all(|other| other.status != entry.status), "{}", entry.name);
+        }
+    }
+
+    #[test]
+    fn decodes_fields() {
+        let status = Status::ACCESS_DENIED;
+        assert_eq!(status.severity(), Severity::Error);
+        assert!(!status.is_success());
+        assert!(!status.is_customer());
+        assert_eq!(status.facility(), 0);
+        assert_eq!(status.facility_code(), 0x22);
+
+        assert_eq!(Status::BUFFER_OVERFLOW.severity(), Severity::Warning);
+        assert_eq!(Status::PENDING.severity(), Severity::Success);
+        assert!(Status(0x4000_0000).is_success());
+        assert_eq!(Status::from_win32(1234).facility(), FACILITY_NTWIN32);
+    }
+
+    #[test]
+    fn names_and_messages() {
+        assert_eq!(Status::OBJECT_NAME_NOT_FOUND.name(), Some("STATUS_OBJECT_NAME_NOT_FOUND"));
+        assert_eq!(Status::ACCESS_DENIED.message(), Some("Access denied"));
+        assert_eq!(Status(0xC0DE_0001u32 as i32).name(), None);
+        assert_eq!(format!("{:?}", Status::SUCCESS), "STATUS_SUCCESS");
+        assert_eq!(format!("{:?}", Status(0xC0DE_0001u32 as i32)), "Status(0xC0DE0001)");
+        assert_eq!(format!("{}", Status::ACCESS_DENIED), "STATUS_ACCESS_DENIED (0xC0000022): Access denied");
+    }
+
+    #[test]
+    fn win32_conversion() {
+        assert_eq!(Status::ACCESS_DENIED.to_win32(), ERROR_ACCESS_DENIED);
+        assert_eq!(Status::from_win32(ERROR_ACCESS_DENIED), Status::ACCESS_DENIED);
+        assert_eq!(Status::from_win32(1234).code() as u32, 0xC007_04D2);
+        assert_eq!(Status::from_win32(1234).to_win32(), 1234);
+        assert_eq!(Status(0xC0DE_0001u32 as i32).to_win32(), ERROR_MR_MID_NOT_FOUND);
+    }
+
+    #[test]
+    fn errno_conversion() {
+        assert_eq!(Status::from_errno(EACCES), Status::ACCESS_DENIED);
+        assert_eq!(Status::OBJECT_NAME_NOT_FOUND.to_errno(), ENOENT);
+        assert_eq!(Status::SUCCESS.to_errno(), 0);
+
+        // No table entry: wrapped and unwrapped again.
+        let status = Status::from_errno(71);
+        assert!(status.is_customer());
+        assert!(status.is_error());
+        assert_eq!(status.to_errno(), 71);
+        assert_eq!(format!("{}", status), "OS error 71");
+
+        assert_eq!(Status(0xC0DE_0001u32 as i32).to_errno(), EIO);
+        assert_eq!(Status(0x4000_0001).to_errno(), 0);
+    }
+
+    #[test]
+    fn question_mark_across_error_types() {
+        fn open() -> Result<(), FileError> {
+            Err(FileError::AccessDenied)
+        }
+
+        fn caller() -> Result<(), Status> {
+            open()?;
+            Ok(())
+        }
+
+        fn raw() -> Result<(), i32> {
+            caller()?;
+            Ok(())
+        }
+
+        assert_eq!(caller(), Err(Status::ACCESS_DENIED));
+        assert_eq!(raw(), Err(0xC0000022u32 as i32));
+        assert_eq!(Status::SUCCESS.ok(), Ok(()));
+        assert_eq!(Status::TIMEOUT.ok(), Ok(()));
+        assert_eq!(Status::NO_MEMORY.ok(), Err(Status::NO_MEMORY));
+    }
+
+    #[test]
+    fn file_error_round_trip() {
+        for err in [FileError::AccessDenied, FileError::FileNotFound, FileError::NameCollision, FileError::FileIsDirectory] {
+            assert_eq!(FileError::from(Status::from(err)), err);
+        }
+        assert_eq!(FileError::from(Status::from_errno(EPERM)), FileError::AccessDenied);
+        assert_eq!(FileError::from(Status::from_errno(71)), FileError::Unspecified);
+        assert_eq!(FileError::from(Status(0xC0DE_0001u32 as i32)), FileError::Unspecified);
+        assert_eq!(FileError::from(Status::UNSUCCESSFUL), FileError::Unspecified);
+
+        // EIO survives both ways.
+        assert_eq!(Status::from_errno(EIO), Status::IO_DEVICE_ERROR);
+        assert_eq!(FileError::from(Status::from_errno(EIO)), FileError::IoError);
+        assert_eq!(FileError::from_errno(EIO), FileError::IoError);
+        assert_eq!(Status::from(FileError::IoError).to_errno(), EIO);
+        assert_eq!(FileError::IoError as u32, Status::IO_DEVICE_ERROR.code() as u32);
+
+        for err in [FileError::DiskFull, FileError::DirectoryNotEmpty, FileError::TooManyOpenFiles, FileError::InvalidData] {
+            assert_eq!(FileError::from(Status::from(err)), err);
+        }
+        assert_eq!(FileError::from_errno(ENOSPC), FileError::DiskFull);
+        assert_eq!(Status::from(ThreadError::Timeout), Status::TIMEOUT);
+    }
+}
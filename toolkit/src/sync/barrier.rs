@@ -0,0 +1,96 @@
+use super::{Condvar, Mutex};
+
+struct BarrierState {
+    count: usize,
+    generation: usize,
+}
+
+/// Blocks `n` threads until all of them reached [`Barrier::wait`]. Reusable.
+pub struct Barrier {
+    lock: Mutex<BarrierState>,
+    cvar: Condvar,
+    num_threads: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BarrierWaitResult(bool);
+
+impl BarrierWaitResult {
+    /// `true` for exactly one thread per generation.
+    pub fn is_leader(&self) -> bool {
+        self.0
+    }
+}
+
+impl Barrier {
+    pub const fn new(n: usize) -> Self {
+        Self {
+            lock: Mutex::new(BarrierState {
+                count: 0,
+                generation: 0,
+            }),
+            cvar: Condvar::new(),
+            num_threads: n,
+        }
+    }
+
+    pub fn wait(&self) -> BarrierWaitResult {
+        let mut state = self.lock.lock();
+        let generation = state.generation;
+        state.count += 1;
+
+        if state.count < self.num_threads {
+            let _state = self.cvar.wait_while(state, |state| state.generation == generation);
+            BarrierWaitResult(false)
+        } else {
+            state.count = 0;
+            state.generation = state.generation.wrapping_add(1);
+            drop(state);
+            self.cvar.notify_all();
+            BarrierWaitResult(true)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+
+    #[test]
+    fn one_leader_per_generation() {
+        const THREADS: usize = 6;
+        const ROUNDS: usize = 20;
+
+        let barrier = Barrier::new(THREADS);
+        let leaders = AtomicUsize::new(0);
+        let arrived = AtomicUsize::new(0);
+
+        thread::scope(|scope| {
+            for _ in 0..THREADS {
+                scope.spawn(|| {
+                    for round in 0..ROUNDS {
+                        arrived.fetch_add(1, Ordering::SeqCst);
+                        if barrier.wait().is_leader() {
+                            leaders.fetch_add(1, Ordering::Relaxed);
+                        }
+                        // Nobody passes before every thread of this round arrived.
+                        assert!(arrived.load(Ordering::SeqCst) >= (round + 1) * THREADS);
+                    }
+                });
+            }
+        });
+
+        assert_eq!(leaders.load(Ordering::Relaxed), ROUNDS);
+    }
+
+    #[test]
+    fn single_thread_is_always_leader() {
+        let barrier = Barrier::new(1);
+        assert!(barrier.wait().is_leader());
+        assert!(barrier.wait().is_leader());
+    }
+}
@@ -0,0 +1,162 @@
+use core::sync::atomic::{AtomicU32, Ordering};
+use core::time::Duration;
+
+use crate::futex::{wait_on_address, wake_by_address_all, wake_by_address_single};
+
+use super::MutexGuard;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WaitTimeoutResult(bool);
+
+impl WaitTimeoutResult {
+    pub fn timed_out(&self) -> bool {
+        self.0
+    }
+}
+
+/// Condition variable for [`Mutex`](super::Mutex). Every notify bumps a sequence
+/// counter, so a notify between unlocking and waiting is never lost.
+pub struct Condvar {
+    futex: AtomicU32,
+}
+
+impl Condvar {
+    pub const fn new() -> Self {
+        Self {
+            futex: AtomicU32::new(0),
+        }
+    }
+
+    /// May wake up spuriously, check the condition in a loop or use [`Condvar::wait_while`].
+    pub fn wait<'a, T>(&self, guard: MutexGuard<'a, T>) -> MutexGuard<'a, T> {
+        self.wait_optional_timeout(guard, None).0
+    }
+
+    pub fn wait_timeout<'a, T>(&self, guard: MutexGuard<'a, T>, timeout: Duration) -> (MutexGuard<'a, T>, WaitTimeoutResult) {
+        self.wait_optional_timeout(guard, Some(timeout))
+    }
+
+    pub fn wait_while<'a, T, F>(&self, mut guard: MutexGuard<'a, T>, mut condition: F) -> MutexGuard<'a, T>
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        while condition(&mut *guard) {
+            guard = self.wait(guard);
+        }
+        guard
+    }
+
+    pub fn notify_one(&self) {
+        self.futex.fetch_add(1, Ordering::Relaxed);
+        wake_by_address_single(&self.futex);
+    }
+
+    pub fn notify_all(&self) {
+        self.futex.fetch_add(1, Ordering::Relaxed);
+        wake_by_address_all(&self.futex);
+    }
+
+    fn wait_optional_timeout<'a, T>(&self, guard: MutexGuard<'a, T>, timeout: Option<Duration>) -> (MutexGuard<'a, T>, WaitTimeoutResult) {
+        let mutex = guard.0;
+        let sequence = self.futex.load(Ordering::Relaxed);
+
+        drop(guard);
+        let woken = wait_on_address(&self.futex, sequence, timeout);
+
+        (mutex.lock(), WaitTimeoutResult(!woken))
+    }
+}
+
+impl Default for Condvar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::Mutex;
+    use std::thread;
+
+    #[test]
+    fn notify_one_wakes_a_waiter() {
+        let ready = Mutex::new(false);
+        let condvar = Condvar::new();
+
+        thread::scope(|scope| {
+            scope.spawn(|| {
+                thread::sleep(Duration::from_millis(10));
+                *ready.lock() = true;
+                condvar.notify_one();
+            });
+
+            let (guard, result) = condvar.wait_timeout(ready.lock(), Duration::from_secs(5));
+            drop(guard);
+            assert!(!result.timed_out());
+            assert!(*condvar.wait_while(ready.lock(), |ready| !*ready));
+        });
+    }
+
+    #[test]
+    fn notify_all_wakes_every_waiter() {
+        struct State {
+            go: bool,
+            woken: usize,
+        }
+
+        let state = Mutex::new(State { go: false, woken: 0 });
+        let condvar = Condvar::new();
+
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    let mut guard = condvar.wait_while(state.lock(), |state| !state.go);
+                    guard.woken += 1;
+                });
+            }
+
+            thread::sleep(Duration::from_millis(10));
+            state.lock().go = true;
+            condvar.notify_all();
+        });
+
+        assert_eq!(state.lock().woken, 4);
+    }
+
+    #[test]
+    fn notify_without_waiters_is_not_stored() {
+        let mutex = Mutex::new(());
+        let condvar = Condvar::new();
+        condvar.notify_one();
+        condvar.notify_all();
+
+        let (_guard, result) = condvar.wait_timeout(mutex.lock(), Duration::from_millis(10));
+        assert!(result.timed_out());
+    }
+
+    #[test]
+    fn hands_off_between_threads() {
+        // Ping-pong: every increment waits for the other side's turn.
+        let turn = Mutex::new(0usize);
+        let changed = Condvar::new();
+
+        thread::scope(|scope| {
+            for parity in 0..2 {
+                let (turn, changed) = (&turn, &changed);
+                scope.spawn(move || {
+                    for _ in 0..1000 {
+                        let mut current = changed.wait_while(turn.lock(), |turn| *turn % 2 != parity);
+                        *current += 1;
+                        drop(current);
+                        changed.notify_all();
+                    }
+                });
+            }
+        });
+
+        assert_eq!(*turn.lock(), 2000);
+    }
+}
@@ -0,0 +1,104 @@
+use core::cell::Cell;
+use core::fmt;
+use core::ops::Deref;
+
+use super::OnceLock;
+
+/// Value initialized on first access, e.g. `static TABLE: LazyLock<[u32; 256]> = LazyLock::new(build_table);`.
+pub struct LazyLock<T, F = fn() -> T> {
+    cell: OnceLock<T>,
+    init: Cell<Option<F>>,
+}
+
+unsafe impl<T: Send + Sync, F: Send> Sync for LazyLock<T, F> {}
+
+impl<T, F: FnOnce() -> T> LazyLock<T, F> {
+    pub const fn new(f: F) -> Self {
+        Self {
+            cell: OnceLock::new(),
+            init: Cell::new(Some(f)),
+        }
+    }
+
+    pub fn force(this: &Self) -> &T {
+        this.cell.get_or_init(|| match this.init.take() {
+            Some(f) => f(),
+            None => panic!("LazyLock instance has previously been poisoned"),
+        })
+    }
+
+    pub fn get(this: &Self) -> Option<&T> {
+        this.cell.get()
+    }
+}
+
+impl<T, F: FnOnce() -> T> Deref for LazyLock<T, F> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        Self::force(self)
+    }
+}
+
+impl<T: Default> Default for LazyLock<T> {
+    fn default() -> Self {
+        Self::new(T::default)
+    }
+}
+
+impl<T: fmt::Debug, F> fmt::Debug for LazyLock<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.cell.get() {
+            Some(value) => f.debug_tuple("LazyLock").field(value).finish(),
+            None => f.write_str("LazyLock(<uninit>)"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use std::panic::{AssertUnwindSafe, catch_unwind};
+    use std::thread;
+
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+    static SQUARES: LazyLock<[u32; 16]> = LazyLock::new(|| {
+        CALLS.fetch_add(1, Ordering::Relaxed);
+        core::array::from_fn(|i| (i * i) as u32)
+    });
+
+    #[test]
+    fn static_initializes_once() {
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| assert_eq!(SQUARES[5], 25));
+            }
+        });
+
+        assert_eq!(CALLS.load(Ordering::Relaxed), 1);
+        assert_eq!(LazyLock::get(&SQUARES).map(|squares| squares[15]), Some(225));
+    }
+
+    #[test]
+    fn force_and_get() {
+        let lazy = LazyLock::new(|| 7);
+        assert_eq!(LazyLock::get(&lazy), None);
+        assert_eq!(*LazyLock::force(&lazy), 7);
+        assert_eq!(LazyLock::get(&lazy), Some(&7));
+
+        let lazy = LazyLock::<u8>::default();
+        assert_eq!(*lazy, 0);
+    }
+
+    #[test]
+    fn panicking_init_poisons() {
+        let lazy: LazyLock<u32> = LazyLock::new(|| panic!("init failed"));
+
+        assert!(catch_unwind(AssertUnwindSafe(|| *lazy)).is_err());
+        let second = catch_unwind(AssertUnwindSafe(|| *lazy)).unwrap_err();
+        assert_eq!(second.downcast_ref::<&str>(), Some(&"LazyLock instance has previously been poisoned"));
+    }
+}
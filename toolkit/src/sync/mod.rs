@@ -0,0 +1,13 @@
+mod mutex;
+mod condvar;
+mod once;
+mod once_lock;
+mod lazy_lock;
+mod barrier;
+
+pub use mutex::*;
+pub use condvar::*;
+pub use once::*;
+pub use once_lock::*;
+pub use lazy_lock::*;
+pub use barrier::*;
@@ -110,7 +110,7 @@ impl<T> Mutex<T> {
     }
 }
 
-pub struct MutexGuard<'a, T>(&'a Mutex<T>);
+pub struct MutexGuard<'a, T>(pub(crate) &'a Mutex<T>);
 
 impl<T> Drop for MutexGuard<'_, T> {
     fn drop(&mut self) {
@@ -129,4 +129,29 @@ impl<T> DerefMut for MutexGuard<'_, T> {
     fn deref_mut(&mut self) -> &mut T {
         unsafe { &mut *self.0.data.get() }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn exclusive_under_contention() {
+        let counter = Mutex::new(0usize);
+
+        thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    for _ in 0..10_000 {
+                        *counter.lock() += 1;
+                    }
+                });
+            }
+        });
+
+        assert_eq!(*counter.lock(), 80_000);
+    }
+}
@@ -0,0 +1,149 @@
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use crate::futex::{wait_on_address, wake_by_address_all};
+
+const INCOMPLETE: u32 = 0;
+const RUNNING: u32 = 1;
+const QUEUED: u32 = 2;
+const COMPLETE: u32 = 3;
+
+/// One-time initialization. If the closure panics the `Once` goes back to
+/// incomplete and the next caller runs its own closure.
+pub struct Once {
+    state: AtomicU32,
+}
+
+impl Once {
+    pub const fn new() -> Self {
+        Self {
+            state: AtomicU32::new(INCOMPLETE),
+        }
+    }
+
+    pub fn is_completed(&self) -> bool {
+        self.state.load(Ordering::Acquire) == COMPLETE
+    }
+
+    pub fn call_once<F: FnOnce()>(&self, f: F) {
+        if self.is_completed() {
+            return;
+        }
+
+        let mut f = Some(f);
+        self.call_once_slow(&mut || f.take().unwrap()());
+    }
+
+    #[cold]
+    fn call_once_slow(&self, f: &mut dyn FnMut()) {
+        let mut state = self.state.load(Ordering::Acquire);
+
+        loop {
+            match state {
+                INCOMPLETE => {
+                    if let Err(current) = self.state.compare_exchange_weak(INCOMPLETE, RUNNING, Ordering::Acquire, Ordering::Acquire) {
+                        state = current;
+                        continue;
+                    }
+
+                    let mut guard = CompletionGuard {
+                        state: &self.state,
+                        set_on_drop: INCOMPLETE,
+                    };
+                    f();
+                    guard.set_on_drop = COMPLETE;
+                    return;
+                }
+                RUNNING | QUEUED => {
+                    if state == RUNNING
+                        && let Err(current) = self.state.compare_exchange_weak(RUNNING, QUEUED, Ordering::Relaxed, Ordering::Acquire)
+                    {
+                        state = current;
+                        continue;
+                    }
+
+                    wait_on_address(&self.state, QUEUED, None);
+                    state = self.state.load(Ordering::Acquire);
+                }
+                _ => return,
+            }
+        }
+    }
+}
+
+impl Default for Once {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct CompletionGuard<'a> {
+    state: &'a AtomicU32,
+    set_on_drop: u32,
+}
+
+impl Drop for CompletionGuard<'_> {
+    fn drop(&mut self) {
+        if self.state.swap(self.set_on_drop, Ordering::Release) == QUEUED {
+            wake_by_address_all(self.state);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use core::sync::atomic::AtomicUsize;
+    use core::time::Duration;
+    use std::panic::{AssertUnwindSafe, catch_unwind};
+    use std::thread;
+
+    #[test]
+    fn runs_once() {
+        let once = Once::new();
+        let mut calls = 0;
+        assert!(!once.is_completed());
+
+        once.call_once(|| calls += 1);
+        once.call_once(|| calls += 1);
+        assert_eq!(calls, 1);
+        assert!(once.is_completed());
+    }
+
+    #[test]
+    fn panic_resets_to_incomplete() {
+        let once = Once::new();
+
+        let result = catch_unwind(AssertUnwindSafe(|| once.call_once(|| panic!("init failed"))));
+        assert!(result.is_err());
+        assert!(!once.is_completed());
+
+        let mut ran = false;
+        once.call_once(|| ran = true);
+        assert!(ran);
+        assert!(once.is_completed());
+    }
+
+    #[test]
+    fn concurrent_callers_wait_for_the_call() {
+        let once = Once::new();
+        let calls = AtomicUsize::new(0);
+
+        thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    once.call_once(|| {
+                        thread::sleep(Duration::from_millis(20));
+                        calls.fetch_add(1, Ordering::Relaxed);
+                    });
+                    // Every caller returns only after the call completed.
+                    assert!(once.is_completed());
+                    assert_eq!(calls.load(Ordering::Relaxed), 1);
+                });
+            }
+        });
+
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+    }
+}
@@ -0,0 +1,188 @@
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::mem::MaybeUninit;
+
+use super::Once;
+
+/// Cell written at most once, safe to share between threads.
+pub struct OnceLock<T> {
+    once: Once,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+unsafe impl<T: Send + Sync> Sync for OnceLock<T> {}
+unsafe impl<T: Send> Send for OnceLock<T> {}
+
+impl<T> OnceLock<T> {
+    pub const fn new() -> Self {
+        Self {
+            once: Once::new(),
+            value: UnsafeCell::new(MaybeUninit::uninit()),
+        }
+    }
+
+    pub fn get(&self) -> Option<&T> {
+        if self.once.is_completed() {
+            Some(unsafe { (*self.value.get()).assume_init_ref() })
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        if self.once.is_completed() {
+            Some(unsafe { self.value.get_mut().assume_init_mut() })
+        } else {
+            None
+        }
+    }
+
+    /// Gives the value back if the cell was already initialized.
+    pub fn set(&self, value: T) -> Result<(), T> {
+        let mut value = Some(value);
+        self.get_or_init(|| value.take().unwrap());
+
+        match value {
+            Some(value) => Err(value),
+            None => Ok(()),
+        }
+    }
+
+    /// Concurrent callers block until the first closure finishes; only one closure runs.
+    pub fn get_or_init<F: FnOnce() -> T>(&self, f: F) -> &T {
+        if let Some(value) = self.get() {
+            return value;
+        }
+
+        let slot = self.value.get();
+        self.once.call_once(|| unsafe {
+            (*slot).write(f());
+        });
+
+        unsafe { (*slot).assume_init_ref() }
+    }
+
+    pub fn into_inner(mut self) -> Option<T> {
+        self.take()
+    }
+
+    pub fn take(&mut self) -> Option<T> {
+        if !self.once.is_completed() {
+            return None;
+        }
+
+        self.once = Once::new();
+        Some(unsafe { self.value.get_mut().assume_init_read() })
+    }
+}
+
+impl<T> Drop for OnceLock<T> {
+    fn drop(&mut self) {
+        if self.once.is_completed() {
+            unsafe { self.value.get_mut().assume_init_drop() };
+        }
+    }
+}
+
+impl<T> Default for OnceLock<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> From<T> for OnceLock<T> {
+    fn from(value: T) -> Self {
+        let cell = Self::new();
+        let _ = cell.set(value);
+        cell
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for OnceLock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.get() {
+            Some(value) => f.debug_tuple("OnceLock").field(value).finish(),
+            None => f.write_str("OnceLock(<uninit>)"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use core::cell::Cell;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use std::{format, thread};
+
+    struct Counted<'a>(&'a Cell<usize>);
+
+    impl Drop for Counted<'_> {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    #[test]
+    fn set_get_take() {
+        let mut cell = OnceLock::new();
+        assert_eq!(cell.get(), None);
+        assert_eq!(format!("{:?}", cell), "OnceLock(<uninit>)");
+
+        assert_eq!(cell.set(1), Ok(()));
+        assert_eq!(cell.set(2), Err(2));
+        assert_eq!(cell.get_or_init(|| 3), &1);
+        assert_eq!(format!("{:?}", cell), "OnceLock(1)");
+
+        *cell.get_mut().unwrap() += 10;
+        assert_eq!(cell.take(), Some(11));
+        assert_eq!(cell.get(), None);
+        assert_eq!(cell.get_or_init(|| 4), &4);
+        assert_eq!(OnceLock::from(5).into_inner(), Some(5));
+        assert_eq!(OnceLock::<u8>::new().into_inner(), None);
+    }
+
+    #[test]
+    fn drops_the_value_once() {
+        let drops = Cell::new(0);
+
+        let cell = OnceLock::new();
+        assert!(cell.set(Counted(&drops)).is_ok());
+        assert!(cell.set(Counted(&drops)).is_err());
+        assert_eq!(drops.get(), 1);
+        drop(cell);
+        assert_eq!(drops.get(), 2);
+
+        let mut cell = OnceLock::from(Counted(&drops));
+        drop(cell.take());
+        drop(cell);
+        assert_eq!(drops.get(), 3);
+
+        drop(OnceLock::<Counted>::new());
+        assert_eq!(drops.get(), 3);
+    }
+
+    #[test]
+    fn concurrent_init_runs_one_closure() {
+        let cell = OnceLock::new();
+        let calls = AtomicUsize::new(0);
+
+        thread::scope(|scope| {
+            for i in 0..8 {
+                let (cell, calls) = (&cell, &calls);
+                scope.spawn(move || {
+                    let value = cell.get_or_init(|| {
+                        calls.fetch_add(1, Ordering::Relaxed);
+                        thread::sleep(core::time::Duration::from_millis(10));
+                        i
+                    });
+                    assert_eq!(cell.get(), Some(value));
+                });
+            }
+        });
+
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+        assert!(cell.get().is_some());
+    }
+}
@@ -0,0 +1,21 @@
+use super::fs;
+
+pub const NEWLINE: &str = "\n";
+
+const STDOUT_FILENO: i32 = 1;
+
+pub fn write(text: &str) -> Result<usize, i32> {
+    let mut buf = text.as_bytes();
+    let mut total = 0;
+
+    while !buf.is_empty() {
+        let written = fs::write(STDOUT_FILENO, buf)?;
+        if written == 0 {
+            break;
+        }
+        buf = &buf[written..];
+        total += written;
+    }
+
+    Ok(total)
+}
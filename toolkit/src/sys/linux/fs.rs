@@ -0,0 +1,550 @@
+use core::mem;
+
+use crate::error::FileError;
+use crate::fs::{FileAttributes, FileMetadata, FileSize, FileTime};
+use crate::io::{Read, Seek, SeekFrom, Write};
+use crate::types::ToPath;
+
+use super::syscall::*;
+
+pub const AT_FDCWD: i32 = -100;
+
+pub const O_RDONLY: u32 = 0o0;
+pub const O_WRONLY: u32 = 0o1;
+pub const O_RDWR: u32 = 0o2;
+pub const O_CREAT: u32 = 0o100;
+pub const O_EXCL: u32 = 0o200;
+pub const O_TRUNC: u32 = 0o1000;
+pub const O_APPEND: u32 = 0o2000;
+pub const O_DIRECT: u32 = 0o40000;
+pub const O_NOFOLLOW: u32 = 0o400000;
+pub const O_CLOEXEC: u32 = 0o2000000;
+
+const RENAME_NOREPLACE: usize = 1;
+
+const SEEK_SET: usize = 0;
+const SEEK_CUR: usize = 1;
+const SEEK_END: usize = 2;
+
+const S_IFMT: u32 = 0o170000;
+const S_IFDIR: u32 = 0o040000;
+
+const UNIX_EPOCH_TICKS: i64 = 11644473600 * 10_000_000;
+
+/// Opens `path`, which must be nul-terminated, relative to the current directory.
+pub fn open(path: &[u8], flags: u32, mode: u32) -> Result<i32, i32> {
+    debug_assert!(path.last() == Some(&0));
+    let ret = unsafe {
+        syscall4(SYS_OPENAT, AT_FDCWD as usize, path.as_ptr() as usize, flags as usize, mode as usize)
+    };
+    errno(ret).map(|fd| fd as i32)
+}
+
+pub fn read(fd: i32, buf: &mut [u8]) -> Result<usize, i32> {
+    loop {
+        let ret = unsafe { syscall3(SYS_READ, fd as usize, buf.as_mut_ptr() as usize, buf.len()) };
+        if ret != -EINTR {
+            return errno(ret);
+        }
+    }
+}
+
+/// Reads at `offset` without moving the file position.
+pub fn pread(fd: i32, buf: &mut [u8], offset: u64) -> Result<usize, i32> {
+    loop {
+        let ret = unsafe {
+            syscall4(SYS_PREAD64, fd as usize, buf.as_mut_ptr() as usize, buf.len(), offset as usize)
+        };
+        if ret != -EINTR {
+            return errno(ret);
+        }
+    }
+}
+
+pub fn write(fd: i32, buf: &[u8]) -> Result<usize, i32> {
+    loop {
+        let ret = unsafe { syscall3(SYS_WRITE, fd as usize, buf.as_ptr() as usize, buf.len()) };
+        if ret != -EINTR {
+            return errno(ret);
+        }
+    }
+}
+
+pub fn close(fd: i32) -> Result<(), i32> {
+    let ret = unsafe { syscall1(SYS_CLOSE, fd as usize) };
+    errno(ret).map(|_| ())
+}
+
+pub fn unlink(path: &[u8]) -> Result<(), i32> {
+    debug_assert!(path.last() == Some(&0));
+    let ret = unsafe { syscall3(SYS_UNLINKAT, AT_FDCWD as usize, path.as_ptr() as usize, 0) };
+    errno(ret).map(|_| ())
+}
+
+/// Both paths must be nul-terminated. Fails with `EEXIST` if `to` exists and `replace` is unset.
+pub fn renameat2(from: &[u8], to: &[u8], replace: bool) -> Result<(), i32> {
+    debug_assert!(from.last() == Some(&0) && to.last() == Some(&0));
+    let flags = if replace { 0 } else { RENAME_NOREPLACE };
+    let ret = unsafe {
+        syscall5(
+            SYS_RENAMEAT2,
+            AT_FDCWD as usize,
+            from.as_ptr() as usize,
+            AT_FDCWD as usize,
+            to.as_ptr() as usize,
+            flags,
+        )
+    };
+    errno(ret).map(|_| ())
+}
+
+/// Fails with [`FileError::NameCollision`] if `to` exists and `replace` is unset.
+pub fn rename<P: ToPath, Q: ToPath>(from: P, to: Q, replace: bool) -> Result<(), FileError> {
+    let from = from.as_path().ok_or(FileError::PathSyntaxBad)?;
+    let to = to.as_path().ok_or(FileError::PathSyntaxBad)?;
+    renameat2(from.as_bytes_with_nul(), to.as_bytes_with_nul(), replace).map_err(FileError::from_errno)
+}
+
+#[repr(C)]
+struct Stat {
+    st_dev: u64,
+    st_ino: u64,
+    st_nlink: u64,
+    st_mode: u32,
+    st_uid: u32,
+    st_gid: u32,
+    __pad0: i32,
+    st_rdev: u64,
+    st_size: i64,
+    st_blksize: i64,
+    st_blocks: i64,
+    st_atime: i64,
+    st_atime_nsec: i64,
+    st_mtime: i64,
+    st_mtime_nsec: i64,
+    st_ctime: i64,
+    st_ctime_nsec: i64,
+    __unused: [i64; 3],
+}
+
+fn file_time(sec: i64, nsec: i64) -> FileTime {
+    FileTime((sec * 10_000_000 + nsec / 100 + UNIX_EPOCH_TICKS).max(0) as u64)
+}
+
+/// Linux counterpart of the NT create options. Share modes and most attributes
+/// have no Linux equivalent and are accepted but ignored.
+#[derive(Clone, Copy)]
+pub struct FileOptions {
+    pub read: bool,
+    pub write: bool,
+    pub append: bool,
+    pub disposition: u32,
+    pub custom_flags: u32,
+    pub mode: u32,
+    pub delete_on_close: bool,
+}
+
+impl FileOptions {
+    pub fn new() -> Self {
+        Self {
+            read: true,
+            write: false,
+            append: false,
+            disposition: 0,
+            custom_flags: 0,
+            mode: 0o666,
+            delete_on_close: false,
+        }
+    }
+
+    pub fn read(&mut self) -> &mut Self {
+        self.read = true;
+        self
+    }
+
+    pub fn write(&mut self) -> &mut Self {
+        self.write = true;
+        self
+    }
+
+    pub fn read_write(&mut self) -> &mut Self {
+        self.read = true;
+        self.write = true;
+        self
+    }
+
+    pub fn append(&mut self) -> &mut Self {
+        self.append = true;
+        self
+    }
+
+    pub fn share_read(&mut self) -> &mut Self {
+        self
+    }
+
+    pub fn share_write(&mut self) -> &mut Self {
+        self
+    }
+
+    pub fn share_delete(&mut self) -> &mut Self {
+        self
+    }
+
+    pub fn share_all(&mut self) -> &mut Self {
+        self
+    }
+
+    pub fn create(&mut self) -> &mut Self {
+        self.disposition = O_CREAT | O_EXCL;
+        self
+    }
+
+    /// `CREATE_ALWAYS`: creates the file or truncates the existing one.
+    pub fn create_always(&mut self) -> &mut Self {
+        self.disposition = O_CREAT | O_TRUNC;
+        self
+    }
+
+    pub fn open_always(&mut self) -> &mut Self {
+        self.disposition = O_CREAT;
+        self
+    }
+
+    pub fn truncate(&mut self) -> &mut Self {
+        self.disposition = O_TRUNC;
+        self
+    }
+
+    pub fn truncate_always(&mut self) -> &mut Self {
+        self.disposition = O_CREAT | O_TRUNC;
+        self
+    }
+
+    /// Only `FILE_ATTRIBUTE_READONLY` (0x1) is honoured, as a mode without write bits.
+    pub fn attributes(&mut self, attrs: u32) -> &mut Self {
+        if attrs & 0x1 != 0 {
+            self.readonly();
+        }
+        self
+    }
+
+    pub fn hidden(&mut self) -> &mut Self {
+        self
+    }
+
+    pub fn readonly(&mut self) -> &mut Self {
+        self.mode &= !0o222;
+        self
+    }
+
+    pub fn system(&mut self) -> &mut Self {
+        self
+    }
+
+    pub fn temporary(&mut self) -> &mut Self {
+        self
+    }
+
+    pub fn archive(&mut self) -> &mut Self {
+        self
+    }
+
+    pub fn normal(&mut self) -> &mut Self {
+        self
+    }
+
+    pub fn synchronous(&mut self) -> &mut Self {
+        self
+    }
+
+    pub fn asynchronous(&mut self) -> &mut Self {
+        self
+    }
+
+    pub fn no_intermediate_buffering(&mut self) -> &mut Self {
+        self.custom_flags |= O_DIRECT;
+        self
+    }
+
+    pub fn random_access(&mut self) -> &mut Self {
+        self
+    }
+
+    pub fn sequential_scan(&mut self) -> &mut Self {
+        self
+    }
+
+    /// The path is unlinked right after opening; the data lives until the last descriptor closes.
+    pub fn delete_on_close(&mut self) -> &mut Self {
+        self.delete_on_close = true;
+        self
+    }
+
+    pub fn open_reparse_point(&mut self) -> &mut Self {
+        self.custom_flags |= O_NOFOLLOW;
+        self
+    }
+
+    pub fn open_no_recall(&mut self) -> &mut Self {
+        self
+    }
+
+    pub fn build(&self) -> (u32, u32) {
+        let access = match (self.read, self.write || self.append) {
+            (_, false) => O_RDONLY,
+            (false, true) => O_WRONLY,
+            (true, true) => O_RDWR,
+        };
+        let append = if self.append { O_APPEND } else { 0 };
+
+        (access | append | self.disposition | self.custom_flags | O_CLOEXEC, self.mode)
+    }
+}
+
+impl Default for FileOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct File {
+    fd: i32,
+}
+
+impl File {
+    pub fn open<P: ToPath>(path: P) -> Result<Self, FileError> {
+        let mut opts = FileOptions::new();
+        opts.read().share_read().synchronous();
+        Self::open_with_options(path, &opts)
+    }
+
+    pub fn create<P: ToPath>(path: P) -> Result<Self, FileError> {
+        let mut opts = FileOptions::new();
+        opts.read_write().share_all().truncate_always().synchronous();
+        Self::create_with_options(path, &opts)
+    }
+
+    pub fn open_with_options<P: ToPath>(path: P, opts: &FileOptions) -> Result<Self, FileError> {
+        let path = path.as_path().ok_or(FileError::PathSyntaxBad)?;
+        let (flags, mode) = opts.build();
+
+        let fd = open(path.as_bytes_with_nul(), flags, mode).map_err(FileError::from_errno)?;
+        let file = Self { fd };
+
+        if opts.delete_on_close {
+            unlink(path.as_bytes_with_nul()).map_err(FileError::from_errno)?;
+        }
+
+        Ok(file)
+    }
+
+    pub fn create_with_options<P: ToPath>(path: P, opts: &FileOptions) -> Result<Self, FileError> {
+        Self::open_with_options(path, opts)
+    }
+
+    pub fn metadata(&self) -> Result<FileMetadata, FileError> {
+        let mut stat: Stat = unsafe { mem::zeroed() };
+        let ret = unsafe { syscall2(SYS_FSTAT, self.fd as usize, &mut stat as *mut Stat as usize) };
+        errno(ret).map_err(FileError::from_errno)?;
+
+        let mut attributes = 0;
+        if stat.st_mode & S_IFMT == S_IFDIR {
+            attributes |= 0x10;
+        }
+        if stat.st_mode & 0o222 == 0 {
+            attributes |= 0x1;
+        }
+        if attributes == 0 {
+            attributes = 0x80;
+        }
+
+        Ok(FileMetadata {
+            size: FileSize(stat.st_size as u64),
+            // fstat has no birth time, the status change time is the closest match
+            creation_time: file_time(stat.st_ctime, stat.st_ctime_nsec),
+            last_access_time: file_time(stat.st_atime, stat.st_atime_nsec),
+            last_write_time: file_time(stat.st_mtime, stat.st_mtime_nsec),
+            attributes: FileAttributes(attributes),
+        })
+    }
+
+    /// Reads at `offset` without moving the file position.
+    pub fn read_at(&self, buf: &mut [u8], offset: u64) -> Result<usize, FileError> {
+        pread(self.fd, buf, offset).map_err(FileError::from_errno)
+    }
+
+    pub fn into_raw_fd(self) -> i32 {
+        let fd = self.fd;
+        mem::forget(self);
+        fd
+    }
+}
+
+impl Drop for File {
+    fn drop(&mut self) {
+        let _ = close(self.fd);
+    }
+}
+
+impl Read for File {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, FileError> {
+        read(self.fd, buf).map_err(FileError::from_errno)
+    }
+}
+
+impl Write for File {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, FileError> {
+        write(self.fd, buf).map_err(FileError::from_errno)
+    }
+}
+
+impl Seek for File {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, FileError> {
+        let (offset, whence) = match pos {
+            SeekFrom::Start(offset) => (offset as i64, SEEK_SET),
+            SeekFrom::End(offset) => (offset, SEEK_END),
+            SeekFrom::Current(offset) => (offset, SEEK_CUR),
+        };
+
+        let ret = unsafe { syscall3(SYS_LSEEK, self.fd as usize, offset as usize, whence) };
+        errno(ret).map(|pos| pos as u64).map_err(FileError::from_errno)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::format;
+    use std::string::String;
+
+    /// A path under the temp directory that is removed when dropped.
+    struct TempPath(String);
+
+    impl TempPath {
+        fn new(name: &str) -> Self {
+            let pid = unsafe { syscall0(SYS_GETPID) };
+            let path = format!("/tmp/toolkit-fs-{}-{}", pid, name);
+            let _ = unlink(format!("{}\0", path).as_bytes());
+            Self(path)
+        }
+
+        fn as_str(&self) -> &str {
+            &self.0
+        }
+    }
+
+    impl Drop for TempPath {
+        fn drop(&mut self) {
+            let _ = unlink(format!("{}\0", self.0).as_bytes());
+        }
+    }
+
+    fn write_file(path: &TempPath, contents: &[u8]) {
+        File::create(path.as_str()).unwrap().write_all(contents).unwrap();
+    }
+
+    fn read_file(path: &TempPath) -> ([u8; 64], usize) {
+        let mut buf = [0; 64];
+        let mut file = File::open(path.as_str()).unwrap();
+        let mut len = 0;
+        loop {
+            match file.read(&mut buf[len..]).unwrap() {
+                0 => return (buf, len),
+                read => len += read,
+            }
+        }
+    }
+
+    fn open_with(path: &TempPath, options: impl FnOnce(&mut FileOptions) -> &mut FileOptions) -> Result<File, FileError> {
+        let mut opts = FileOptions::new();
+        options(&mut opts);
+        File::open_with_options(path.as_str(), &opts)
+    }
+
+    #[test]
+    fn create_then_open() {
+        let path = TempPath::new("create");
+        assert!(matches!(File::open(path.as_str()), Err(FileError::FileNotFound)));
+
+        write_file(&path, b"hello world");
+        let (buf, len) = read_file(&path);
+        assert_eq!(&buf[..len], b"hello world");
+
+        let file = File::open(path.as_str()).unwrap();
+        assert_eq!(file.metadata().unwrap().size.0, 11);
+        assert!(matches!(file.metadata().unwrap().attributes, FileAttributes(0x80)));
+    }
+
+    #[test]
+    fn dispositions() {
+        let path = TempPath::new("dispositions");
+
+        assert!(matches!(open_with(&path, |opts| opts.write().truncate()), Err(FileError::FileNotFound)));
+        open_with(&path, |opts| opts.write().create()).unwrap();
+        assert!(matches!(open_with(&path, |opts| opts.write().create()), Err(FileError::NameCollision)));
+
+        write_file(&path, b"keep");
+        open_with(&path, |opts| opts.write().open_always()).unwrap();
+        assert_eq!(read_file(&path).1, 4);
+
+        open_with(&path, |opts| opts.write().create_always()).unwrap();
+        assert_eq!(read_file(&path).1, 0);
+
+        write_file(&path, b"again");
+        open_with(&path, |opts| opts.write().truncate()).unwrap();
+        assert_eq!(read_file(&path).1, 0);
+
+        let missing = TempPath::new("dispositions-missing");
+        open_with(&missing, |opts| opts.write().create_always()).unwrap();
+        assert_eq!(read_file(&missing).1, 0);
+    }
+
+    #[test]
+    fn append_writes_at_the_end() {
+        let path = TempPath::new("append");
+        write_file(&path, b"head");
+
+        let mut file = open_with(&path, |opts| opts.append().open_always()).unwrap();
+        file.seek(SeekFrom::Start(0)).unwrap();
+        file.write_all(b"-tail").unwrap();
+        drop(file);
+
+        let (buf, len) = read_file(&path);
+        assert_eq!(&buf[..len], b"head-tail");
+    }
+
+    #[test]
+    fn seek_and_read_at() {
+        let path = TempPath::new("seek");
+        write_file(&path, b"0123456789");
+
+        let mut file = File::open(path.as_str()).unwrap();
+        let mut buf = [0; 3];
+        assert_eq!(file.read_at(&mut buf, 7).unwrap(), 3);
+        assert_eq!(&buf, b"789");
+
+        file.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"012");
+        assert_eq!(file.seek(SeekFrom::End(-2)).unwrap(), 8);
+        assert_eq!(file.seek(SeekFrom::Current(-1)).unwrap(), 7);
+    }
+
+    #[test]
+    fn delete_on_close_and_rename() {
+        let path = TempPath::new("rename-from");
+        let target = TempPath::new("rename-to");
+        write_file(&path, b"a");
+        write_file(&target, b"bb");
+
+        assert!(matches!(rename(path.as_str(), target.as_str(), false), Err(FileError::NameCollision)));
+        rename(path.as_str(), target.as_str(), true).unwrap();
+        assert!(matches!(File::open(path.as_str()), Err(FileError::FileNotFound)));
+        assert_eq!(read_file(&target).1, 1);
+
+        let mut file = open_with(&target, |opts| opts.read_write().delete_on_close()).unwrap();
+        assert!(matches!(File::open(target.as_str()), Err(FileError::FileNotFound)));
+        file.write_all(b"still open").unwrap();
+    }
+}
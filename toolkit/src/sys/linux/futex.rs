@@ -0,0 +1,73 @@
+use core::ffi::c_void;
+use core::ptr;
+use core::sync::atomic::{AtomicU32, Ordering};
+use core::time::Duration;
+
+use super::syscall::*;
+
+pub const FUTEX_WAIT: usize = 0;
+pub const FUTEX_WAKE: usize = 1;
+pub const FUTEX_PRIVATE_FLAG: usize = 128;
+
+pub unsafe fn futex(uaddr: *const AtomicU32, op: usize, val: u32, timeout: *const Timespec) -> isize {
+    syscall4(SYS_FUTEX, uaddr as usize, op, val as usize, timeout as usize)
+}
+
+/// The kernel only understands aligned 32-bit futex words, so narrower values
+/// are widened onto the word that contains them. Any store to the narrow value
+/// changes the containing word, so the kernel-side comparison stays race-free.
+fn futex_word(address: *const c_void) -> (*const AtomicU32, u32) {
+    let addr = address as usize;
+    let word = (addr & !3) as *const AtomicU32;
+    let shift = ((addr & 3) * 8) as u32;
+    (word, shift)
+}
+
+fn read_compare(compare: *const c_void, size: usize) -> u32 {
+    let mut bytes = [0u8; 4];
+    unsafe { ptr::copy_nonoverlapping(compare as *const u8, bytes.as_mut_ptr(), size) };
+    u32::from_le_bytes(bytes)
+}
+
+pub unsafe fn wait_on_address(
+    address: *const c_void,
+    compare: *const c_void,
+    size: usize,
+    timeout: Option<Duration>,
+) -> bool {
+    debug_assert!(size == 1 || size == 2 || size == 4);
+
+    let (word, shift) = futex_word(address);
+    let mask = if size == 4 { u32::MAX } else { ((1u32 << (size * 8)) - 1) << shift };
+    let compare = read_compare(compare, size) << shift;
+
+    let current = (*word).load(Ordering::Relaxed);
+    if current & mask != compare {
+        return true;
+    }
+
+    let timespec = timeout.map(|dur| Timespec {
+        tv_sec: dur.as_secs().min(i64::MAX as u64) as i64,
+        tv_nsec: dur.subsec_nanos() as i64,
+    });
+    let timespec_ptr = timespec.as_ref().map_or(ptr::null(), |ts| ts as *const Timespec);
+
+    let ret = futex(word, FUTEX_WAIT | FUTEX_PRIVATE_FLAG, current, timespec_ptr);
+    ret != -ETIMEDOUT
+}
+
+/// Waiters on neighbouring narrow values share a futex word, so waking a single
+/// one of them could pick the wrong waiter; those are woken all at once instead.
+pub unsafe fn wake_by_address_single(address: *const c_void, size: usize) {
+    if size < 4 {
+        return wake_by_address_all(address);
+    }
+
+    let (word, _) = futex_word(address);
+    futex(word, FUTEX_WAKE | FUTEX_PRIVATE_FLAG, 1, ptr::null());
+}
+
+pub unsafe fn wake_by_address_all(address: *const c_void) {
+    let (word, _) = futex_word(address);
+    futex(word, FUTEX_WAKE | FUTEX_PRIVATE_FLAG, i32::MAX as u32, ptr::null());
+}
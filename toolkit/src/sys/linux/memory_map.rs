@@ -0,0 +1,19 @@
+use alloc::{format, vec::Vec};
+
+use crate::error::FileError;
+use crate::io::Read;
+
+use super::fs::File;
+
+/// Raw `/proc/<pid>/maps`, `/proc/self/maps` for `None`.
+pub fn read_maps(pid: Option<u32>) -> Result<Vec<u8>, FileError> {
+    let mut file = match pid {
+        Some(pid) => File::open(format!("/proc/{}/maps", pid).as_str())?,
+        None => File::open("/proc/self/maps")?,
+    };
+
+    // procfs reports a size of zero, so read until the end.
+    let mut maps = Vec::new();
+    file.read_to_end(&mut maps)?;
+    Ok(maps)
+}
@@ -0,0 +1,14 @@
+pub mod syscall;
+pub mod console;
+pub mod fs;
+pub mod futex;
+#[cfg(feature = "alloc")]
+pub mod memory_map;
+#[cfg(feature = "alloc")]
+mod pthread;
+pub mod rand;
+#[cfg(feature = "alloc")]
+pub mod thread;
+pub mod time;
+#[cfg(feature = "alloc")]
+pub mod tls;
@@ -0,0 +1,24 @@
+use core::ffi::c_void;
+
+pub type StartRoutine = unsafe extern "C" fn(arg: *mut c_void) -> *mut c_void;
+
+// Weak, so they resolve to `None` in processes that do not link libc.
+unsafe extern "C" {
+    #[linkage = "extern_weak"]
+    pub static pthread_create: Option<unsafe extern "C" fn(*mut usize, *const c_void, StartRoutine, *mut c_void) -> i32>;
+    #[linkage = "extern_weak"]
+    pub static pthread_join: Option<unsafe extern "C" fn(usize, *mut *mut c_void) -> i32>;
+    #[linkage = "extern_weak"]
+    pub static pthread_detach: Option<unsafe extern "C" fn(usize) -> i32>;
+    #[linkage = "extern_weak"]
+    pub static pthread_key_create: Option<unsafe extern "C" fn(*mut u32, Option<unsafe extern "C" fn(*mut c_void)>) -> i32>;
+    #[linkage = "extern_weak"]
+    pub static pthread_getspecific: Option<unsafe extern "C" fn(u32) -> *mut c_void>;
+    #[linkage = "extern_weak"]
+    pub static pthread_setspecific: Option<unsafe extern "C" fn(u32, *const c_void) -> i32>;
+}
+
+/// libc is linked, so threads have to come from it.
+pub fn available() -> bool {
+    unsafe { pthread_create.is_some() }
+}
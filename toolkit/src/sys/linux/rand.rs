@@ -0,0 +1,107 @@
+use core::ptr;
+use core::sync::atomic::{AtomicBool, AtomicPtr, AtomicU64, Ordering};
+
+use super::syscall::*;
+
+const PAGE: usize = 4096;
+const PROT_READ: usize = 1;
+const PROT_WRITE: usize = 2;
+const MAP_PRIVATE: usize = 0x02;
+const MAP_ANONYMOUS: usize = 0x20;
+const MADV_WIPEONFORK: usize = 18;
+
+/// Page the kernel zeroes in a forked child (`MADV_WIPEONFORK`, Linux 4.14+),
+/// holding the fork token of the current process.
+static FORK_PAGE: AtomicPtr<AtomicU64> = AtomicPtr::new(ptr::null_mut());
+/// Set once the page could not be created; `fork_id` then returns the pid.
+static FORK_PAGE_FAILED: AtomicBool = AtomicBool::new(false);
+/// Last token handed out. Ordinary memory, so a child continues the count.
+static FORK_EPOCH: AtomicU64 = AtomicU64::new(0);
+
+pub fn fill_bytes(buffer: &mut [u8]) {
+    if let Err(code) = try_fill_bytes(buffer) {
+        panic!("getrandom failed: {}", code);
+    }
+}
+
+pub fn try_fill_bytes(buffer: &mut [u8]) -> Result<(), i32> {
+    let mut filled = 0;
+
+    while filled < buffer.len() {
+        let remaining = &mut buffer[filled..];
+        let ret = unsafe { syscall3(SYS_GETRANDOM, remaining.as_mut_ptr() as usize, remaining.len(), 0) };
+
+        match errno(ret) {
+            Ok(read) => filled += read,
+            Err(code) if code as isize == EINTR => continue,
+            Err(code) => return Err(code),
+        }
+    }
+
+    Ok(())
+}
+
+/// Differs between a parent and its forked child, so generators can tell
+/// when their state has been duplicated. Costs a load on the fast path.
+pub fn fork_id() -> u64 {
+    let Some(token) = fork_page() else {
+        return unsafe { syscall0(SYS_GETPID) as u64 };
+    };
+
+    let current = token.load(Ordering::Acquire);
+    if current != 0 {
+        return current;
+    }
+
+    // Wiped by fork. Racing threads may each burn an epoch, but only one
+    // value is installed and everyone returns it.
+    let next = FORK_EPOCH.fetch_add(1, Ordering::AcqRel) + 1;
+    match token.compare_exchange(0, next, Ordering::AcqRel, Ordering::Acquire) {
+        Ok(_) => next,
+        Err(installed) => installed,
+    }
+}
+
+fn fork_page() -> Option<&'static AtomicU64> {
+    let page = FORK_PAGE.load(Ordering::Acquire);
+    if !page.is_null() {
+        return Some(unsafe { &*page });
+    }
+
+    if FORK_PAGE_FAILED.load(Ordering::Relaxed) {
+        return None;
+    }
+
+    let page = match map_fork_page() {
+        Ok(page) => page,
+        Err(_) => {
+            FORK_PAGE_FAILED.store(true, Ordering::Relaxed);
+            return None;
+        }
+    };
+
+    unsafe { (*page).store(FORK_EPOCH.fetch_add(1, Ordering::AcqRel) + 1, Ordering::Release) };
+
+    match FORK_PAGE.compare_exchange(ptr::null_mut(), page, Ordering::AcqRel, Ordering::Acquire) {
+        Ok(_) => Some(unsafe { &*page }),
+        Err(existing) => {
+            unsafe { syscall2(SYS_MUNMAP, page as usize, PAGE) };
+            Some(unsafe { &*existing })
+        }
+    }
+}
+
+fn map_fork_page() -> Result<*mut AtomicU64, i32> {
+    let page = unsafe {
+        syscall6(SYS_MMAP, 0, PAGE, PROT_READ | PROT_WRITE, MAP_PRIVATE | MAP_ANONYMOUS, usize::MAX, 0)
+    };
+    let page = errno(page)?;
+
+    let ret = unsafe { syscall3(SYS_MADVISE, page, PAGE, MADV_WIPEONFORK) };
+    if let Err(code) = errno(ret) {
+        unsafe { syscall2(SYS_MUNMAP, page, PAGE) };
+        return Err(code);
+    }
+
+    Ok(page as *mut AtomicU64)
+}
@@ -0,0 +1,329 @@
+use core::arch::asm;
+use core::ffi::c_void;
+use core::ptr;
+use core::sync::atomic::{AtomicPtr, AtomicU32, Ordering};
+
+use crate::ThreadError;
+
+use super::pthread::{StartRoutine, pthread_create, pthread_detach, pthread_join};
+use super::futex::{FUTEX_WAIT, FUTEX_WAKE, futex};
+use super::syscall::*;
+use super::time::{CLOCK_MONOTONIC, clock_gettime};
+
+/// Entry point of a spawned thread. Returning from it exits the thread.
+pub type ThreadStart = unsafe extern "system" fn(param: *mut c_void);
+
+const PAGE: usize = 4096;
+const STACK_SIZE: usize = 1024 * 1024;
+
+const PROT_NONE: usize = 0;
+const PROT_READ: usize = 1;
+const PROT_WRITE: usize = 2;
+const MAP_PRIVATE: usize = 0x02;
+const MAP_ANONYMOUS: usize = 0x20;
+const MAP_STACK: usize = 0x20000;
+
+const CLONE_VM: usize = 0x100;
+const CLONE_FS: usize = 0x200;
+const CLONE_FILES: usize = 0x400;
+const CLONE_SIGHAND: usize = 0x800;
+const CLONE_THREAD: usize = 0x10000;
+const CLONE_SYSVSEM: usize = 0x40000;
+const CLONE_SETTLS: usize = 0x80000;
+const CLONE_PARENT_SETTID: usize = 0x100000;
+const CLONE_CHILD_CLEARTID: usize = 0x200000;
+
+const ARCH_GET_FS: usize = 0x1003;
+
+/// Tells a raw `clone` thread's [`Header`] apart from a control block set up by someone else.
+const MAGIC: usize = 0x6B74_6864_7262_6174;
+
+/// Page above the stack top, out of reach of the stack growing down.
+#[repr(C)]
+struct Header {
+    /// x86-64 thread control block that `fs` points at for raw `clone`
+    /// threads: `fs:0` and `fs:0x10` point back at it, `fs:0x28` and
+    /// `fs:0x30` hold the stack-protector and pointer guards.
+    tcb: [usize; 7],
+    magic: usize,
+    /// Per-thread pointer of [`super::tls`] for raw `clone` threads.
+    tls: AtomicPtr<c_void>,
+    /// Thread id, cleared and futex-woken when the thread is done.
+    tid: AtomicU32,
+    pthread: usize,
+    entry: ThreadStart,
+    param: *mut c_void,
+}
+
+/// Mapping of a `PROT_NONE` guard page at the bottom, the stack, and the
+/// [`Header`] page on top. A zero-sized stack maps the header alone, for
+/// threads that get their stack from libc.
+struct ThreadStack {
+    base: *mut u8,
+    size: usize,
+}
+
+impl ThreadStack {
+    fn new(stack_size: usize) -> Result<Self, i32> {
+        let stack_size = (stack_size + PAGE - 1) & !(PAGE - 1);
+        let guard = if stack_size == 0 { 0 } else { PAGE };
+        let size = guard + stack_size + PAGE;
+
+        let base = unsafe {
+            syscall6(SYS_MMAP, 0, size, PROT_READ | PROT_WRITE, MAP_PRIVATE | MAP_ANONYMOUS | MAP_STACK, usize::MAX, 0)
+        };
+        let base = errno(base)? as *mut u8;
+
+        if guard != 0 {
+            let ret = unsafe { syscall3(SYS_MPROTECT, base as usize, guard, PROT_NONE) };
+            if let Err(code) = errno(ret) {
+                unsafe { syscall2(SYS_MUNMAP, base as usize, size) };
+                return Err(code);
+            }
+        }
+
+        Ok(Self { base, size })
+    }
+
+    fn top(&self) -> *mut u8 {
+        unsafe { self.base.add(self.size - PAGE) }
+    }
+
+    fn header(&self) -> *mut Header {
+        self.top() as *mut Header
+    }
+
+    fn free(self) {
+        unsafe { syscall2(SYS_MUNMAP, self.base as usize, self.size) };
+    }
+}
+
+/// In a process that links libc the thread is created with `pthread_create`,
+/// since only libc can set up the TLS block its own code (malloc's
+/// per-thread cache included) expects. Without libc it is a raw `clone`
+/// whose `fs` points at a control block of its own in the [`Header`].
+pub struct NativeThread {
+    stack: ThreadStack,
+}
+
+// The mapping is not tied to the thread that created it, and the running
+// thread only touches its header through the atomic tid word until the
+// mapping is freed after the wait. `pthread_join` and `pthread_detach` may be
+// called from any thread.
+unsafe impl Send for NativeThread {}
+
+impl NativeThread {
+    pub fn spawn(entry: ThreadStart, param: *mut c_void) -> Result<Self, ThreadError> {
+        match unsafe { pthread_create } {
+            Some(create) => Self::spawn_pthread(create, entry, param),
+            None => Self::spawn_raw(entry, param),
+        }
+    }
+
+    fn spawn_pthread(
+        create: unsafe extern "C" fn(*mut usize, *const c_void, StartRoutine, *mut c_void) -> i32,
+        entry: ThreadStart,
+        param: *mut c_void,
+    ) -> Result<Self, ThreadError> {
+        unsafe extern "C" fn start(header: *mut c_void) -> *mut c_void {
+            let header = &*(header as *const Header);
+            (header.entry)(header.param);
+            super::tls::run_exit_hook();
+
+            // The joiner also waits in `pthread_join`, so the header outlives this wake.
+            header.tid.store(0, Ordering::Release);
+            futex(&header.tid, FUTEX_WAKE, i32::MAX as u32, ptr::null());
+            ptr::null_mut()
+        }
+
+        let stack = ThreadStack::new(0).map_err(ThreadError::CreationFailed)?;
+        let header = stack.header();
+
+        unsafe {
+            header.write(Header {
+                tcb: [0; 7],
+                magic: 0,
+                tls: AtomicPtr::new(ptr::null_mut()),
+                tid: AtomicU32::new(u32::MAX),
+                pthread: 0,
+                entry,
+                param,
+            });
+
+            let ret = create(&raw mut (*header).pthread, ptr::null(), start, header as *mut c_void);
+            if ret != 0 {
+                stack.free();
+                return Err(ThreadError::CreationFailed(ret));
+            }
+        }
+
+        Ok(Self { stack })
+    }
+
+    fn spawn_raw(entry: ThreadStart, param: *mut c_void) -> Result<Self, ThreadError> {
+        unsafe extern "C" fn start(header: *mut Header) -> ! {
+            ((*header).entry)((*header).param);
+            exit_current()
+        }
+
+        let stack = ThreadStack::new(STACK_SIZE).map_err(ThreadError::CreationFailed)?;
+        let header = stack.header();
+        let stack_top = stack.top() as usize;
+
+        unsafe {
+            let mut tcb = [0; 7];
+            tcb[0] = header as usize;
+            tcb[2] = header as usize;
+
+            // Code built with a stack protector compares against fs:0x28.
+            let mut parent = 0usize;
+            syscall2(SYS_ARCH_PRCTL, ARCH_GET_FS, &raw mut parent as usize);
+            if parent != 0 {
+                tcb[5] = *((parent + 0x28) as *const usize);
+                tcb[6] = *((parent + 0x30) as *const usize);
+            }
+
+            header.write(Header {
+                tcb,
+                magic: MAGIC,
+                tls: AtomicPtr::new(ptr::null_mut()),
+                tid: AtomicU32::new(0),
+                pthread: 0,
+                entry,
+                param,
+            });
+        }
+
+        let tid = unsafe { &raw const (*header).tid } as usize;
+        let flags = CLONE_VM | CLONE_FS | CLONE_FILES | CLONE_SIGHAND | CLONE_THREAD
+            | CLONE_SYSVSEM | CLONE_SETTLS | CLONE_PARENT_SETTID | CLONE_CHILD_CLEARTID;
+
+        let ret: isize;
+        unsafe {
+            asm!(
+                "syscall",
+                "test rax, rax",
+                "jnz 2f",
+                "mov rdi, r13",
+                "call r12",
+                "ud2",
+                "2:",
+                inlateout("rax") SYS_CLONE as isize => ret,
+                in("rdi") flags,
+                in("rsi") stack_top,
+                in("rdx") tid,
+                in("r10") tid,
+                in("r8") header,
+                in("r12") start,
+                in("r13") header,
+                lateout("rcx") _,
+                lateout("r11") _,
+                options(nostack)
+            );
+        }
+
+        if let Err(code) = errno(ret) {
+            stack.free();
+            return Err(ThreadError::CreationFailed(code));
+        }
+
+        Ok(Self { stack })
+    }
+
+    fn tid(&self) -> *const AtomicU32 {
+        unsafe { &raw const (*self.stack.header()).tid }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        unsafe { (*self.tid()).load(Ordering::Acquire) == 0 }
+    }
+
+    pub fn join(self) -> Result<(), ThreadError> {
+        self.wait(None)?;
+        self.release();
+        Ok(())
+    }
+
+    /// On timeout the thread keeps running and its mapping is leaked.
+    pub fn join_timeout(self, timeout_ms: u64) -> Result<(), ThreadError> {
+        if let Err(err) = self.wait(Some(timeout_ms)) {
+            let pthread = unsafe { (*self.stack.header()).pthread };
+            if let Some(detach) = unsafe { pthread_detach }
+                && pthread != 0
+            {
+                unsafe { detach(pthread) };
+            }
+            return Err(err);
+        }
+
+        self.release();
+        Ok(())
+    }
+
+    fn release(self) {
+        let pthread = unsafe { (*self.stack.header()).pthread };
+        if let Some(join) = unsafe { pthread_join }
+            && pthread != 0
+        {
+            unsafe { join(pthread, ptr::null_mut()) };
+        }
+        self.stack.free();
+    }
+
+    /// `CLONE_CHILD_CLEARTID` wakes with a shared futex op, so this waits with
+    /// the shared op as well; a private waiter would never see the wake-up.
+    fn wait(&self, timeout_ms: Option<u64>) -> Result<(), ThreadError> {
+        let tid = self.tid();
+        let start = clock_gettime(CLOCK_MONOTONIC);
+
+        loop {
+            let current = unsafe { (*tid).load(Ordering::Acquire) };
+            if current == 0 {
+                return Ok(());
+            }
+
+            let timeout = match timeout_ms {
+                Some(timeout_ms) => {
+                    let now = clock_gettime(CLOCK_MONOTONIC);
+                    let elapsed_ms = ((now.tv_sec - start.tv_sec) * 1000 + (now.tv_nsec - start.tv_nsec) / 1_000_000) as u64;
+                    if elapsed_ms >= timeout_ms {
+                        return Err(ThreadError::Timeout);
+                    }
+                    Some(Timespec::from_millis(timeout_ms - elapsed_ms))
+                }
+                None => None,
+            };
+            let timeout_ptr = timeout.as_ref().map_or(ptr::null(), |ts| ts as *const Timespec);
+
+            let ret = unsafe { futex(tid, FUTEX_WAIT, current, timeout_ptr) };
+            match ret {
+                0 => {}
+                ret if ret == -EAGAIN || ret == -EINTR || ret == -ETIMEDOUT => {}
+                ret => return Err(ThreadError::WaitFailed((-ret) as i32)),
+            }
+        }
+    }
+}
+
+/// The calling thread's [`super::tls`] slot if it is a raw `clone` thread.
+pub(super) fn raw_tls_slot() -> Option<&'static AtomicPtr<c_void>> {
+    let mut fs = 0usize;
+    unsafe { syscall2(SYS_ARCH_PRCTL, ARCH_GET_FS, &raw mut fs as usize) };
+
+    let header = fs as *const Header;
+    if fs == 0 || unsafe { (*header).magic } != MAGIC {
+        return None;
+    }
+    // The header is freed only after the thread has exited.
+    Some(unsafe { &(*header).tls })
+}
+
+/// Exits a raw `clone` thread; libc threads return from their start routine.
+fn exit_current() -> ! {
+    super::tls::run_exit_hook();
+
+    unsafe {
+        syscall1(SYS_EXIT, 0);
+    }
+    unreachable!()
+}
@@ -0,0 +1,49 @@
+use crate::time::WINDOWS_EPOCH_TO_UNIX;
+
+use super::syscall::*;
+
+const CLOCK_REALTIME: usize = 0;
+pub const CLOCK_MONOTONIC: usize = 1;
+
+pub fn clock_gettime(clock: usize) -> Timespec {
+    let mut ts = Timespec::default();
+    unsafe { syscall2(SYS_CLOCK_GETTIME, clock, &mut ts as *mut Timespec as usize) };
+    ts
+}
+
+/// Current system time as FILETIME ticks (100ns intervals since 1601-01-01 UTC),
+/// so callers see the same representation on every platform.
+pub fn system_time() -> u64 {
+    let ts = clock_gettime(CLOCK_REALTIME);
+    // Clocks set before 1601 saturate to the FILETIME epoch.
+    let secs = ts.tv_sec.saturating_add(WINDOWS_EPOCH_TO_UNIX);
+    if secs < 0 {
+        return 0;
+    }
+    (secs as u64).saturating_mul(10_000_000).saturating_add(ts.tv_nsec as u64 / 100)
+}
+
+/// `CLOCK_MONOTONIC` in nanoseconds, so the frequency is fixed.
+pub fn monotonic_ticks() -> u64 {
+    let ts = clock_gettime(CLOCK_MONOTONIC);
+    ts.tv_sec as u64 * 1_000_000_000 + ts.tv_nsec as u64
+}
+
+pub const fn monotonic_frequency() -> u64 {
+    1_000_000_000
+}
+
+pub fn sleep(milliseconds: u32) {
+    let mut request = Timespec::from_millis(milliseconds as u64);
+    let mut remaining = Timespec::default();
+
+    loop {
+        let ret = unsafe {
+            syscall2(SYS_NANOSLEEP, &request as *const Timespec as usize, &mut remaining as *mut Timespec as usize)
+        };
+        if ret != -EINTR {
+            return;
+        }
+        request = remaining;
+    }
+}
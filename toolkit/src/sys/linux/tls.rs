@@ -0,0 +1,104 @@
+use core::ffi::c_void;
+use core::ptr;
+use core::sync::atomic::{AtomicPtr, AtomicU32, Ordering};
+
+use super::pthread::{self, pthread_getspecific, pthread_key_create, pthread_setspecific};
+use super::syscall::*;
+use super::thread::raw_tls_slot;
+
+const UNRESERVED: u32 = u32::MAX;
+const EXHAUSTED: u32 = u32::MAX - 1;
+
+/// pthread key of the per-thread pointer when libc is linked.
+static KEY: AtomicU32 = AtomicU32::new(UNRESERVED);
+
+/// Without libc only toolkit threads have a control block of their own,
+/// so the main thread keeps its pointer here.
+static MAIN: AtomicPtr<c_void> = AtomicPtr::new(ptr::null_mut());
+
+static EXIT_HOOK: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+/// Key destructor, run by libc for every exiting thread with a non-null
+/// pointer. libc clears the pointer first, so it is put back for the hook.
+unsafe extern "C" fn on_thread_exit(data: *mut c_void) {
+    set_current(data);
+    run_exit_hook();
+}
+
+fn key() -> Option<u32> {
+    match KEY.load(Ordering::Acquire) {
+        EXHAUSTED => return None,
+        UNRESERVED => {}
+        key => return Some(key),
+    }
+
+    let create = unsafe { pthread_key_create? };
+    let mut key = 0;
+    let key = match unsafe { create(&mut key, Some(on_thread_exit)) } {
+        0 => key,
+        _ => EXHAUSTED,
+    };
+
+    // A racing thread may have won; its key is kept and ours is never used.
+    match KEY.compare_exchange(UNRESERVED, key, Ordering::AcqRel, Ordering::Acquire) {
+        Ok(_) => Some(key).filter(|&key| key != EXHAUSTED),
+        Err(EXHAUSTED) => None,
+        Err(current) => Some(current),
+    }
+}
+
+fn is_main_thread() -> bool {
+    unsafe { syscall0(SYS_GETTID) == syscall0(SYS_GETPID) }
+}
+
+/// The calling thread's pointer, null until [`set_current`].
+pub fn current() -> *mut c_void {
+    if pthread::available() {
+        return match (key(), unsafe { pthread_getspecific }) {
+            (Some(key), Some(get)) => unsafe { get(key) },
+            _ => ptr::null_mut(),
+        };
+    }
+
+    match raw_tls_slot() {
+        Some(slot) => slot.load(Ordering::Relaxed),
+        None if is_main_thread() => MAIN.load(Ordering::Relaxed),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Returns `false` when no key is left, or without libc on a thread that
+/// toolkit did not create other than the main thread.
+///
+/// Values of the main thread are not dropped when the process exits.
+// `data` is only stored, never dereferenced.
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub fn set_current(data: *mut c_void) -> bool {
+    if pthread::available() {
+        return match (key(), unsafe { pthread_setspecific }) {
+            (Some(key), Some(set)) => unsafe { set(key, data) == 0 },
+            _ => false,
+        };
+    }
+
+    match raw_tls_slot() {
+        Some(slot) => slot.store(data, Ordering::Relaxed),
+        None if is_main_thread() => MAIN.store(data, Ordering::Relaxed),
+        None => return false,
+    }
+    true
+}
+
+/// Sets the function a thread with a pointer runs right before it exits:
+/// every thread but the main one when libc is linked, toolkit threads otherwise.
+pub fn set_exit_hook(hook: fn()) {
+    EXIT_HOOK.store(hook as *mut (), Ordering::Release);
+}
+
+pub fn run_exit_hook() {
+    let hook = EXIT_HOOK.load(Ordering::Acquire);
+    if !hook.is_null() {
+        let hook: fn() = unsafe { core::mem::transmute(hook) };
+        hook();
+    }
+}
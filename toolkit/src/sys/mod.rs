@@ -0,0 +1,11 @@
+#![allow(clippy::missing_safety_doc)]
+
+#[cfg(windows)]
+mod windows;
+#[cfg(target_os = "linux")]
+mod linux;
+
+#[cfg(windows)]
+pub use windows::*;
+#[cfg(target_os = "linux")]
+pub use linux::*;
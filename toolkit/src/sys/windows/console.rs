@@ -0,0 +1,7 @@
+use crate::NtConsole;
+
+pub const NEWLINE: &str = "\r\n";
+
+pub fn write(text: &str) -> Result<usize, i32> {
+    NtConsole::write(text).map(|written| written as usize)
+}
@@ -1,14 +1,20 @@
 use core::ptr::null_mut;
 use core::mem::size_of;
-use ntapi::ntioapi::{FILE_BASIC_INFORMATION, FILE_NON_DIRECTORY_FILE, FILE_POSITION_INFORMATION, FILE_STANDARD_INFORMATION, FILE_SYNCHRONOUS_IO_NONALERT, FileBasicInformation, FilePositionInformation, FileStandardInformation, IO_STATUS_BLOCK};
+use ntapi::ntioapi::{
+    FILE_BASIC_INFORMATION, FILE_DELETE_ON_CLOSE, FILE_NON_DIRECTORY_FILE, FILE_NO_INTERMEDIATE_BUFFERING,
+    FILE_OPEN_NO_RECALL, FILE_OPEN_REPARSE_POINT, FILE_POSITION_INFORMATION, FILE_RANDOM_ACCESS,
+    FILE_RENAME_INFORMATION, FILE_SEQUENTIAL_ONLY, FILE_STANDARD_INFORMATION, FILE_SYNCHRONOUS_IO_NONALERT,
+    FileBasicInformation, FilePositionInformation, FileRenameInformation, FileStandardInformation,
+    IO_STATUS_BLOCK, FILE_CREATE, FILE_OPEN,
+    FILE_OPEN_IF, FILE_OVERWRITE, FILE_OVERWRITE_IF,
+};
 use winapi::shared::ntdef::{HANDLE, OBJECT_ATTRIBUTES, OBJ_CASE_INSENSITIVE};
-use winapi::um::winnt::LARGE_INTEGER;
+use winapi::um::winnt::*;
 
 use crate::error::FileError;
-use crate::fs::options::FileOptions;
 use crate::syscalls::*;
 use crate::types::ToUnicode;
-use crate::{U16CStackString, fs::*};
+use crate::fs::*;
 use crate::io::*;
 
 pub struct File {
@@ -105,7 +111,7 @@ impl File {
         }
     }
 
-    pub fn open_with_flags<P: ToUnicode>(path: P, access: u32, share: u32) -> Result<Self, FileError> {
+    fn open_with_flags<P: ToUnicode>(path: P, access: u32, share: u32) -> Result<Self, FileError> {
         let mut opts = FileOptions::new();
         opts.access = access;
         opts.share = share;
@@ -293,4 +299,219 @@ impl Seek for File {
             }
         }
     }
+}
+
+/// Longest target name, in UTF-16 units, [`rename`] accepts.
+const MAX_RENAME_TARGET: usize = 512;
+
+#[repr(C)]
+struct RenameInformation {
+    info: FILE_RENAME_INFORMATION,
+    name: [u16; MAX_RENAME_TARGET],
+}
+
+/// Renames `from` to `to`, both NT paths. Fails with [`FileError::NameCollision`]
+/// if `to` exists and `replace` is unset.
+pub fn rename<P: ToUnicode, Q: ToUnicode>(from: P, to: Q, replace: bool) -> Result<(), FileError> {
+    let target = to.as_unicode();
+    let target_len = target.Length as usize / 2;
+
+    if target_len > MAX_RENAME_TARGET {
+        return Err(FileError::PathSyntaxBad);
+    }
+
+    let file = File::open_with_flags(from, DELETE | SYNCHRONIZE, FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE)?;
+    let mut rename: RenameInformation = unsafe { core::mem::zeroed() };
+    rename.info.ReplaceIfExists = replace as u8;
+    rename.info.FileNameLength = target.Length as u32;
+
+    // `FileName` runs on into `name`, the kernel reads `FileNameLength` bytes from its start.
+    let offset = core::mem::offset_of!(FILE_RENAME_INFORMATION, FileName);
+    unsafe {
+        let dst = (&raw mut rename).cast::<u8>().add(offset).cast::<u16>();
+        core::ptr::copy_nonoverlapping(target.Buffer, dst, target_len);
+    }
+
+    let mut status_block: IO_STATUS_BLOCK = unsafe { core::mem::zeroed() };
+    let status = unsafe {
+        NtSetInformationFile(
+            file.handle,
+            &mut status_block,
+            &mut rename as *mut _ as _,
+            (offset + target.Length as usize) as u32,
+            FileRenameInformation,
+        )
+    };
+
+    if status >= 0 {
+        Ok(())
+    } else {
+        Err(FileError::from(status))
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct FileOptions {
+    pub access: u32,
+    pub share: u32,
+    pub create_options: u32,
+    pub create_disposition: u32,
+    pub attributes: u32,
+}
+
+impl FileOptions {
+    pub fn new() -> Self {
+        Self {
+            access: FILE_READ_DATA | SYNCHRONIZE,
+            share: FILE_SHARE_READ,
+            create_options: 0,
+            create_disposition: FILE_OPEN,
+            attributes: 0,
+        }
+    }
+
+    pub fn read(&mut self) -> &mut Self {
+        self.access |= FILE_READ_DATA | FILE_READ_ATTRIBUTES | SYNCHRONIZE;
+        self
+    }
+
+    pub fn write(&mut self) -> &mut Self {
+        self.access |= FILE_WRITE_DATA | FILE_READ_ATTRIBUTES | SYNCHRONIZE;
+        self
+    }
+
+    pub fn read_write(&mut self) -> &mut Self {
+        self.access |= FILE_READ_DATA | FILE_WRITE_DATA | FILE_READ_ATTRIBUTES | SYNCHRONIZE;
+        self
+    }
+
+    pub fn append(&mut self) -> &mut Self {
+        self.access |= FILE_APPEND_DATA | SYNCHRONIZE;
+        self
+    }
+
+    pub fn share_read(&mut self) -> &mut Self {
+        self.share |= FILE_SHARE_READ;
+        self
+    }
+
+    pub fn share_write(&mut self) -> &mut Self {
+        self.share |= FILE_SHARE_WRITE;
+        self
+    }
+
+    pub fn share_delete(&mut self) -> &mut Self {
+        self.share |= FILE_SHARE_DELETE;
+        self
+    }
+
+    pub fn share_all(&mut self) -> &mut Self {
+        self.share |= FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE;
+        self
+    }
+
+    pub fn create(&mut self) -> &mut Self {
+        self.create_disposition = FILE_CREATE;
+        self
+    }
+
+    /// `CREATE_ALWAYS`: creates the file or truncates the existing one.
+    pub fn create_always(&mut self) -> &mut Self {
+        self.create_disposition = FILE_OVERWRITE_IF;
+        self
+    }
+
+    pub fn open_always(&mut self) -> &mut Self {
+        self.create_disposition = FILE_OPEN_IF;
+        self
+    }
+
+    pub fn truncate(&mut self) -> &mut Self {
+        self.create_disposition = FILE_OVERWRITE;
+        self
+    }
+
+    pub fn truncate_always(&mut self) -> &mut Self {
+        self.create_disposition = FILE_OVERWRITE_IF;
+        self
+    }
+
+    pub fn attributes(&mut self, attrs: u32) -> &mut Self {
+        self.attributes = attrs;
+        self
+    }
+
+    pub fn hidden(&mut self) -> &mut Self {
+        self.attributes |= FILE_ATTRIBUTE_HIDDEN;
+        self
+    }
+
+    pub fn readonly(&mut self) -> &mut Self {
+        self.attributes |= FILE_ATTRIBUTE_READONLY;
+        self
+    }
+
+    pub fn system(&mut self) -> &mut Self {
+        self.attributes |= FILE_ATTRIBUTE_SYSTEM;
+        self
+    }
+
+    pub fn temporary(&mut self) -> &mut Self {
+        self.attributes |= FILE_ATTRIBUTE_TEMPORARY;
+        self
+    }
+
+    pub fn archive(&mut self) -> &mut Self {
+        self.attributes |= FILE_ATTRIBUTE_ARCHIVE;
+        self
+    }
+
+    pub fn normal(&mut self) -> &mut Self {
+        self.attributes |= FILE_ATTRIBUTE_NORMAL;
+        self
+    }
+
+    pub fn synchronous(&mut self) -> &mut Self {
+        self.create_options |= FILE_SYNCHRONOUS_IO_NONALERT;
+        self
+    }
+
+    pub fn asynchronous(&mut self) -> &mut Self {
+        self.create_options &= !FILE_SYNCHRONOUS_IO_NONALERT;
+        self
+    }
+
+    pub fn no_intermediate_buffering(&mut self) -> &mut Self {
+        self.create_options |= FILE_NO_INTERMEDIATE_BUFFERING;
+        self
+    }
+
+    pub fn random_access(&mut self) -> &mut Self {
+        self.create_options |= FILE_RANDOM_ACCESS;
+        self
+    }
+
+    pub fn sequential_scan(&mut self) -> &mut Self {
+        self.create_options |= FILE_SEQUENTIAL_ONLY;
+        self
+    }
+
+    pub fn delete_on_close(&mut self) -> &mut Self {
+        self.create_options |= FILE_DELETE_ON_CLOSE;
+        self
+    }
+
+    pub fn open_reparse_point(&mut self) -> &mut Self {
+        self.create_options |= FILE_OPEN_REPARSE_POINT;
+        self
+    }
+
+    pub fn open_no_recall(&mut self) -> &mut Self {
+        self.create_options |= FILE_OPEN_NO_RECALL;
+        self
+    }
+
+    pub fn build(&self) -> (u32, u32, u32, u32, u32) {
+        (self.access, self.share, self.create_options, self.create_disposition, self.attributes)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,24 @@
+use core::ffi::c_void;
+use core::time::Duration;
+
+use winapi::um::synchapi::{WaitOnAddress, WakeByAddressAll, WakeByAddressSingle};
+
+use crate::futex::{INFINITE, dur2timeout};
+
+pub unsafe fn wait_on_address(
+    address: *const c_void,
+    compare: *const c_void,
+    size: usize,
+    timeout: Option<Duration>,
+) -> bool {
+    let timeout = timeout.map(dur2timeout).unwrap_or(INFINITE);
+    WaitOnAddress(address as _, compare as _, size, timeout) == 1
+}
+
+pub unsafe fn wake_by_address_single(address: *const c_void, _size: usize) {
+    WakeByAddressSingle(address as _);
+}
+
+pub unsafe fn wake_by_address_all(address: *const c_void) {
+    WakeByAddressAll(address as _);
+}
@@ -0,0 +1,57 @@
+use alloc::{string::String, vec::Vec};
+
+use winapi::{shared::ntdef::HANDLE, um::winnt::*};
+
+use crate::{MemoryInformation, MemoryRegionIterator, Protection, Region, RegionState, RegionType};
+
+/// Walks the address space of `process` with `NtQueryVirtualMemory`.
+pub fn regions(process: HANDLE) -> Vec<Region> {
+    MemoryRegionIterator::new(process).map(|info| region(&info)).collect()
+}
+
+fn region(info: &MemoryInformation) -> Region {
+    let state = match info.state() {
+        MEM_COMMIT => RegionState::Committed,
+        MEM_RESERVE => RegionState::Reserved,
+        _ => RegionState::Free,
+    };
+
+    let kind = match info.type_() {
+        MEM_PRIVATE => RegionType::Private,
+        MEM_MAPPED => RegionType::Mapped,
+        MEM_IMAGE => RegionType::Image,
+        _ => RegionType::None,
+    };
+
+    let file = (!info.mapped_name.is_empty()).then(|| info.mapped_name.chars().collect::<String>());
+
+    Region {
+        base: info.base_address(),
+        size: info.region_size(),
+        protection: protection(info.protect()),
+        state,
+        kind,
+        file,
+    }
+}
+
+fn protection(protect: u32) -> Protection {
+    let mut protection = match protect & 0xFF {
+        PAGE_READONLY => Protection::READ,
+        PAGE_READWRITE => Protection::READ | Protection::WRITE,
+        PAGE_WRITECOPY => Protection::READ | Protection::WRITE | Protection::COPY_ON_WRITE,
+        PAGE_EXECUTE => Protection::EXECUTE,
+        PAGE_EXECUTE_READ => Protection::READ | Protection::EXECUTE,
+        PAGE_EXECUTE_READWRITE => Protection::READ | Protection::WRITE | Protection::EXECUTE,
+        PAGE_EXECUTE_WRITECOPY => {
+            Protection::READ | Protection::WRITE | Protection::EXECUTE | Protection::COPY_ON_WRITE
+        }
+        _ => Protection::NONE,
+    };
+
+    if protect & PAGE_GUARD != 0 {
+        protection = protection | Protection::GUARD;
+    }
+
+    protection
+}
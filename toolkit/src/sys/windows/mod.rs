@@ -0,0 +1,11 @@
+pub mod console;
+pub mod fs;
+pub mod futex;
+#[cfg(feature = "alloc")]
+pub mod memory_map;
+pub mod rand;
+#[cfg(feature = "alloc")]
+pub mod thread;
+pub mod time;
+#[cfg(feature = "alloc")]
+pub mod tls;
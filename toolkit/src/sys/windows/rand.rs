@@ -0,0 +1,21 @@
+#[link(name = "bcryptprimitives", kind = "raw-dylib")]
+unsafe extern "system" {
+    pub fn ProcessPrng(pbData: *mut u8, cbData: usize) -> i32;
+}
+
+pub fn fill_bytes(buffer: &mut [u8]) {
+    unsafe { ProcessPrng(buffer.as_mut_ptr(), buffer.len()); }
+}
+
+/// `ProcessPrng` is documented to always return TRUE; a zero is reported as-is.
+pub fn try_fill_bytes(buffer: &mut [u8]) -> Result<(), i32> {
+    match unsafe { ProcessPrng(buffer.as_mut_ptr(), buffer.len()) } {
+        0 => Err(0),
+        _ => Ok(()),
+    }
+}
+
+/// Windows has no fork, so the state of a process is never duplicated.
+pub fn fork_id() -> u64 {
+    0
+}
@@ -0,0 +1,157 @@
+use core::ffi::c_void;
+use core::{mem, ptr};
+use ntapi::ntapi_base::CLIENT_ID;
+use ntapi::ntobapi::{NtClose, NtWaitForSingleObject};
+use ntapi::ntpsapi::{NtCreateThread, NtCreateThreadEx, NtCurrentProcess};
+use ntapi::ntrtl::RtlExitUserThread;
+use winapi::shared::ntdef::HANDLE;
+use winapi::shared::ntstatus::STATUS_SUCCESS;
+use winapi::um::winnt::{LARGE_INTEGER, THREAD_ALL_ACCESS};
+
+use crate::{ThreadContext, ThreadError, ThreadStack};
+
+/// Entry point of a spawned thread. It must never return, see [`exit_current`].
+pub type ThreadStart = unsafe extern "system" fn(param: *mut c_void);
+
+pub struct NativeThread {
+    pub handle: HANDLE,
+    pub stack: Option<ThreadStack>,
+}
+
+// A thread handle is valid in every thread of the process, and waiting on or
+// closing it does not have to happen on the creating thread. The stack is a
+// process-wide virtual allocation that is never freed from under the thread.
+unsafe impl Send for NativeThread {}
+
+impl NativeThread {
+    pub fn spawn(entry: ThreadStart, param: *mut c_void) -> Result<Self, ThreadError> {
+        let mut thread_handle: HANDLE = ptr::null_mut();
+        let mut context = ThreadContext::new();
+        let stack_size = 1024 * 1024;
+        let stack = ThreadStack::new(stack_size).unwrap();
+        let mut initial_teb = stack.to_initial_teb();
+        let rsp = stack.rsp();
+
+        let rip = entry as _;
+        let rcx = param as u64;
+        context.set_thread_context(rsp, rip, rcx);
+
+        let mut client_id: CLIENT_ID = unsafe { mem::zeroed() };
+
+        let status = unsafe {
+            NtCreateThread(
+                &mut thread_handle as _,
+                THREAD_ALL_ACCESS,
+                ptr::null_mut(),
+                NtCurrentProcess,
+                &mut client_id as _,
+                &mut *context,
+                &mut initial_teb as _,
+                0,
+            )
+        };
+
+        if status != STATUS_SUCCESS {
+            stack.free().unwrap();
+            return Err(ThreadError::CreationFailed(status));
+        }
+
+        Ok(Self {
+            handle: thread_handle,
+            stack: Some(stack),
+        })
+    }
+
+    pub fn spawn_ex(entry: unsafe extern "system" fn(param: *mut c_void) -> u32, param: *mut c_void) -> Result<Self, ThreadError> {
+        let mut handle: HANDLE = ptr::null_mut();
+
+        let status = unsafe {
+            NtCreateThreadEx(
+                &mut handle,
+                THREAD_ALL_ACCESS,
+                ptr::null_mut(),
+                NtCurrentProcess,
+                entry as *mut _,
+                param as *mut _,
+                0,
+                0,
+                0,
+                0,
+                ptr::null_mut(),
+            )
+        };
+
+        if status != STATUS_SUCCESS {
+            return Err(ThreadError::CreationFailed(status));
+        }
+
+        Ok(Self {
+            handle,
+            stack: None,
+        })
+    }
+
+    pub fn is_finished(&self) -> bool {
+        let status = unsafe {
+            let mut delay: LARGE_INTEGER = mem::zeroed();
+            *delay.QuadPart_mut() = 0;
+            NtWaitForSingleObject(
+                self.handle,
+                0,
+                &mut delay,
+            )
+        };
+        status == STATUS_SUCCESS
+    }
+
+    pub fn join(self) -> Result<(), ThreadError> {
+        unsafe {
+            let status = NtWaitForSingleObject(
+                self.handle,
+                0,
+                ptr::null_mut(),
+            );
+
+            if status != STATUS_SUCCESS {
+                return Err(ThreadError::WaitFailed(status));
+            }
+
+            NtClose(self.handle);
+            Ok(())
+        }
+    }
+
+    pub fn join_timeout(self, timeout_ms: u64) -> Result<(), ThreadError> {
+        if self.handle.is_null() {
+            return Err(ThreadError::InvalidHandle);
+        }
+
+        let status = unsafe {
+            let mut delay: LARGE_INTEGER = mem::zeroed();
+            *delay.QuadPart_mut() = -(timeout_ms as i64 * 10_000);
+            NtWaitForSingleObject(
+                self.handle,
+                0,
+                &mut delay,
+            )
+        };
+
+        match status {
+            STATUS_SUCCESS => {
+                unsafe {
+                    let _ = NtClose(self.handle);
+                }
+                Ok(())
+            }
+            status if status == 0x102 => Err(ThreadError::Timeout),
+            _ => Err(ThreadError::WaitFailed(status)),
+        }
+    }
+}
+
+pub fn exit_current() -> ! {
+    super::tls::run_exit_hook();
+
+    unsafe { RtlExitUserThread(0) };
+    unreachable!()
+}
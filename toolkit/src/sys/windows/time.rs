@@ -0,0 +1,73 @@
+use ntapi::ntapi_base::KSYSTEM_TIME;
+use ntapi::ntexapi::KUSER_SHARED_DATA;
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use winapi::um::profileapi::{QueryPerformanceCounter, QueryPerformanceFrequency};
+use winapi::um::winnt::LARGE_INTEGER;
+
+use crate::syscalls::NtDelayExecution;
+
+const KUSER: *const KUSER_SHARED_DATA = 0x7FFE0000 as *const KUSER_SHARED_DATA;
+
+static QPC_FREQUENCY: AtomicU64 = AtomicU64::new(0);
+
+/// Current system time as FILETIME ticks (100ns intervals since 1601-01-01 UTC).
+pub fn system_time() -> u64 {
+    unsafe { read_ksystem_time(&raw const (*KUSER).SystemTime) as u64 }
+}
+
+/// Current zone bias in 100ns ticks, with `UTC = local + bias`.
+pub fn time_zone_bias() -> i64 {
+    unsafe { read_ksystem_time(&raw const (*KUSER).TimeZoneBias) }
+}
+
+/// The kernel writes `High2Time`, then `LowPart`, then `High1Time`, so a
+/// read is consistent once both high parts agree.
+unsafe fn read_ksystem_time(time: *const KSYSTEM_TIME) -> i64 {
+    loop {
+        let high = (&raw const (*time).High1Time).read_volatile();
+        let low = (&raw const (*time).LowPart).read_volatile();
+        if high == (&raw const (*time).High2Time).read_volatile() {
+            return ((high as i64) << 32) | low as i64;
+        }
+    }
+}
+
+/// QueryPerformanceCounter ticks, at [`monotonic_frequency`] per second.
+pub fn monotonic_ticks() -> u64 {
+    let mut counter: LARGE_INTEGER = unsafe { core::mem::zeroed() };
+    unsafe {
+        QueryPerformanceCounter(&mut counter);
+        *counter.QuadPart() as u64
+    }
+}
+
+/// Fixed at boot, so it is queried once and cached.
+pub fn monotonic_frequency() -> u64 {
+    let cached = QPC_FREQUENCY.load(Ordering::Relaxed);
+    if cached != 0 {
+        return cached;
+    }
+
+    query_frequency()
+}
+
+#[cold]
+fn query_frequency() -> u64 {
+    let mut frequency: LARGE_INTEGER = unsafe { core::mem::zeroed() };
+    let value = unsafe {
+        QueryPerformanceFrequency(&mut frequency);
+        *frequency.QuadPart() as u64
+    };
+
+    QPC_FREQUENCY.store(value, Ordering::Relaxed);
+    value
+}
+
+pub fn sleep(milliseconds: u32) {
+    let mut delay: LARGE_INTEGER = unsafe { core::mem::zeroed() };
+    unsafe {
+        *delay.QuadPart_mut() = -(milliseconds as i64) * 10_000;
+        NtDelayExecution(0, &mut delay);
+    }
+}
@@ -0,0 +1,90 @@
+use core::ffi::c_void;
+use core::ptr;
+use core::sync::atomic::{AtomicPtr, AtomicU32, Ordering};
+
+use ntapi::ntpsapi::NtCurrentPeb;
+use ntapi::ntrtl::{RtlAcquirePebLock, RtlFindClearBitsAndSet, RtlReleasePebLock};
+use ntapi::winapi_local::um::winnt::NtCurrentTeb;
+
+const UNRESERVED: u32 = u32::MAX;
+const EXHAUSTED: u32 = u32::MAX - 1;
+
+const DLL_PROCESS_DETACH: u32 = 0;
+const DLL_THREAD_DETACH: u32 = 3;
+
+/// `TEB::TlsSlots` index taken from the PEB TLS bitmap, the same way `TlsAlloc` does.
+static SLOT: AtomicU32 = AtomicU32::new(UNRESERVED);
+
+static EXIT_HOOK: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+/// Only called by the loader when the image has a TLS directory, which the CRT
+/// provides; toolkit threads also run the hook from [`super::thread::exit_current`].
+#[unsafe(link_section = ".CRT$XLB")]
+#[used]
+static TLS_CALLBACK: unsafe extern "system" fn(*mut c_void, u32, *mut c_void) = on_tls_callback;
+
+unsafe extern "system" fn on_tls_callback(_module: *mut c_void, reason: u32, _reserved: *mut c_void) {
+    if reason == DLL_THREAD_DETACH || reason == DLL_PROCESS_DETACH {
+        run_exit_hook();
+    }
+}
+
+fn slot() -> Option<usize> {
+    match SLOT.load(Ordering::Acquire) {
+        EXHAUSTED => return None,
+        UNRESERVED => {}
+        slot => return Some(slot as usize),
+    }
+
+    unsafe {
+        RtlAcquirePebLock();
+
+        if SLOT.load(Ordering::Acquire) == UNRESERVED {
+            let peb = NtCurrentPeb();
+            let index = RtlFindClearBitsAndSet((*peb).TlsBitmap as _, 1, 0);
+            let slot = if (index as usize) < (*NtCurrentTeb()).TlsSlots.len() { index } else { EXHAUSTED };
+            SLOT.store(slot, Ordering::Release);
+        }
+
+        RtlReleasePebLock();
+    }
+
+    match SLOT.load(Ordering::Acquire) {
+        EXHAUSTED => None,
+        slot => Some(slot as usize),
+    }
+}
+
+/// The calling thread's pointer, null until [`set_current`].
+pub fn current() -> *mut c_void {
+    match slot() {
+        Some(slot) => unsafe { (*NtCurrentTeb()).TlsSlots[slot] as _ },
+        None => ptr::null_mut(),
+    }
+}
+
+/// Returns `false` when no TLS slot is left in the process.
+pub fn set_current(data: *mut c_void) -> bool {
+    match slot() {
+        Some(slot) => {
+            unsafe { (*NtCurrentTeb()).TlsSlots[slot] = data as _ };
+            true
+        }
+        None => false,
+    }
+}
+
+/// Sets the function run on thread detach and by toolkit threads right before they exit.
+pub fn set_exit_hook(hook: fn()) {
+    // Referencing the callback keeps the linker from discarding it.
+    unsafe { ptr::read_volatile(&TLS_CALLBACK) };
+    EXIT_HOOK.store(hook as *mut (), Ordering::Release);
+}
+
+pub fn run_exit_hook() {
+    let hook = EXIT_HOOK.load(Ordering::Acquire);
+    if !hook.is_null() {
+        let hook: fn() = unsafe { core::mem::transmute(hook) };
+        hook();
+    }
+}
@@ -0,0 +1,179 @@
+/// Loaded into `eax` when nothing was resolved; the kernel rejects it with
+/// `STATUS_INVALID_SYSTEM_SERVICE`.
+pub const UNRESOLVED: u32 = u32::MAX;
+
+/// Generates, from a [`for_each_syscall`](super::table::for_each_syscall) table:
+/// - `SyscallId`, one variant per syscall in table order
+/// - `NUMBERS`, the `[AtomicU32; SyscallId::COUNT]` the stubs read their number from
+/// - one naked `extern "system"` stub per syscall returning `NTSTATUS`
+macro_rules! define_syscalls {
+    ($($name:ident($($arg:ident: $ty:ty),* $(,)?) [$($feature:literal = $number:literal),* $(,)?];)*) => {
+        /// Named after the ntdll export.
+        #[repr(u32)]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[allow(clippy::enum_variant_names)]
+        pub enum SyscallId {
+            $($name,)*
+        }
+
+        impl SyscallId {
+            pub const ALL: [SyscallId; Self::COUNT] = [$(Self::$name,)*];
+            pub const NAMES: [&'static str; Self::COUNT] = [$(stringify!($name),)*];
+            pub const COUNT: usize = [$(stringify!($name),)*].len();
+
+            pub const fn name(self) -> &'static str {
+                Self::NAMES[self as usize]
+            }
+
+            pub fn from_name(name: &str) -> Option<Self> {
+                Self::NAMES.iter().position(|candidate| *candidate == name).map(|index| Self::ALL[index])
+            }
+
+            /// The number for the enabled Windows release feature, [`UNRESOLVED`](super::UNRESOLVED) if none.
+            #[allow(unreachable_code)]
+            pub const fn default_number(self) -> u32 {
+                match self {
+                    $(Self::$name => {
+                        $(
+                            #[cfg(feature = $feature)]
+                            return $number;
+                        )*
+                        $crate::syscalls::UNRESOLVED
+                    })*
+                }
+            }
+
+            /// The number the stub currently loads.
+            pub fn number(self) -> u32 {
+                NUMBERS[self as usize].load(core::sync::atomic::Ordering::Relaxed)
+            }
+
+            /// Overrides the number the stub loads, e.g. with one resolved at build time.
+            pub fn set_number(self, number: u32) {
+                NUMBERS[self as usize].store(number, core::sync::atomic::Ordering::Relaxed);
+            }
+        }
+
+        static NUMBERS: [core::sync::atomic::AtomicU32; SyscallId::COUNT] = {
+            let mut numbers = [const { core::sync::atomic::AtomicU32::new($crate::syscalls::UNRESOLVED) }; SyscallId::COUNT];
+            let mut index = 0;
+            while index < SyscallId::COUNT {
+                numbers[index] = core::sync::atomic::AtomicU32::new(SyscallId::ALL[index].default_number());
+                index += 1;
+            }
+            numbers
+        };
+
+        $(
+            #[unsafe(naked)]
+            pub extern "system" fn $name($($arg: $ty),*) -> NTSTATUS {
+                core::arch::naked_asm!(
+                    "mov r10, rcx",
+                    "mov eax, dword ptr [rip + {numbers} + {offset}]",
+                    "syscall",
+                    "ret",
+                    numbers = sym NUMBERS,
+                    offset = const SyscallId::$name as usize * size_of::<u32>(),
+                );
+            }
+        )*
+    };
+}
+
+pub(crate) use define_syscalls;
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    #![allow(clippy::upper_case_acronyms, non_camel_case_types)]
+
+    use core::ffi::c_void;
+
+    use iced_x86::{Decoder, DecoderOptions, Instruction, Mnemonic, OpKind, Register};
+
+    use crate::syscalls::table::for_each_syscall;
+
+    // Host stand-ins for the `ntapi` types, so the real table expands anywhere.
+    type NTSTATUS = i32;
+    type HANDLE = *mut c_void;
+    type PHANDLE = *mut HANDLE;
+    type PVOID = *mut c_void;
+    type ULONG = u32;
+    type PULONG = *mut u32;
+    type BOOLEAN = u8;
+    type SIZE_T = usize;
+    type PSIZE_T = *mut usize;
+    type ULONG_PTR = usize;
+    type ACCESS_MASK = u32;
+    type PLARGE_INTEGER = *mut i64;
+    type POBJECT_ATTRIBUTES = *mut c_void;
+    type PIO_STATUS_BLOCK = *mut c_void;
+    type PIO_APC_ROUTINE = *mut c_void;
+    type PPS_ATTRIBUTE_LIST = *mut c_void;
+    type FILE_INFORMATION_CLASS = u32;
+    type THREADINFOCLASS = u32;
+    type MEMORY_INFORMATION_CLASS = u32;
+
+    for_each_syscall!(define_syscalls);
+
+    macro_rules! stub_addresses {
+        ($($name:ident($($arg:ident: $ty:ty),* $(,)?) [$($feature:literal = $number:literal),* $(,)?];)*) => {
+            [$($name as *const () as usize,)*]
+        };
+    }
+
+    fn decode(address: usize) -> [Instruction; 4] {
+        let code = unsafe { core::slice::from_raw_parts(address as *const u8, 32) };
+        let mut decoder = Decoder::with_ip(64, code, address as u64, DecoderOptions::NONE);
+        core::array::from_fn(|_| decoder.decode())
+    }
+
+    #[test]
+    fn stubs_load_their_slot_and_syscall() {
+        let addresses: [usize; SyscallId::COUNT] = for_each_syscall!(stub_addresses);
+
+        for (id, address) in SyscallId::ALL.into_iter().zip(addresses) {
+            let [mov_r10, mov_eax, syscall, ret] = decode(address);
+
+            assert_eq!(mov_r10.mnemonic(), Mnemonic::Mov, "{}", id.name());
+            assert_eq!(mov_r10.op0_register(), Register::R10, "{}", id.name());
+            assert_eq!(mov_r10.op1_register(), Register::RCX, "{}", id.name());
+
+            assert_eq!(mov_eax.mnemonic(), Mnemonic::Mov, "{}", id.name());
+            assert_eq!(mov_eax.op0_register(), Register::EAX, "{}", id.name());
+            assert_eq!(mov_eax.op1_kind(), OpKind::Memory, "{}", id.name());
+            assert!(mov_eax.is_ip_rel_memory_operand(), "{}", id.name());
+            assert_eq!(mov_eax.ip_rel_memory_address(), &NUMBERS[id as usize] as *const _ as u64, "{}", id.name());
+
+            assert_eq!(syscall.mnemonic(), Mnemonic::Syscall, "{}", id.name());
+            assert_eq!(ret.mnemonic(), Mnemonic::Ret, "{}", id.name());
+        }
+    }
+
+    #[test]
+    fn ids_follow_the_table() {
+        assert_eq!(SyscallId::ALL[0], SyscallId::NtClose);
+        assert_eq!(SyscallId::NtClose.name(), "NtClose");
+        assert_eq!(SyscallId::from_name("NtSuspendThread"), Some(SyscallId::NtSuspendThread));
+        assert_eq!(SyscallId::from_name("NtMissing"), None);
+
+        for (index, id) in SyscallId::ALL.into_iter().enumerate() {
+            assert_eq!(id as usize, index);
+            assert_eq!(SyscallId::from_name(id.name()), Some(id));
+        }
+    }
+
+    #[test]
+    fn numbers_start_from_the_build_feature() {
+        #[cfg(feature = "win_25h2")]
+        {
+            assert_eq!(SyscallId::NtClose.number(), 0xF);
+            assert_eq!(SyscallId::NtSuspendThread.number(), 0x1CF);
+        }
+        #[cfg(not(feature = "win_25h2"))]
+        assert_eq!(SyscallId::NtClose.number(), super::UNRESOLVED);
+
+        SyscallId::NtDelayExecution.set_number(0x99);
+        assert_eq!(SyscallId::NtDelayExecution.number(), 0x99);
+        assert_eq!(NUMBERS[SyscallId::NtDelayExecution as usize].load(core::sync::atomic::Ordering::Relaxed), 0x99);
+    }
+}
@@ -0,0 +1,10 @@
+mod generate;
+mod resolve;
+mod table;
+#[cfg(windows)]
+mod stubs;
+
+pub use generate::*;
+pub use resolve::*;
+#[cfg(windows)]
+pub use stubs::*;
@@ -0,0 +1,290 @@
+use crate::pe::PeImage;
+use crate::{PeError, SyscallError};
+
+/// `mov r10, rcx; mov eax, imm32`, the start of every unpatched x64 stub.
+const STUB_PREFIX: [u8; 4] = [0x4C, 0x8B, 0xD1, 0xB8];
+
+const NOT_FOUND: u32 = u32::MAX;
+
+/// Syscall number of `name`, see [`resolve_numbers`].
+pub fn syscall_number(ntdll: &PeImage, name: &str) -> Result<u32, SyscallError> {
+    let mut numbers = [0];
+    resolve_numbers(ntdll, &[name], &mut numbers)?;
+    Ok(numbers[0])
+}
+
+/// Fills `numbers[i]` with the syscall number of `names[i]`, given as `NtX`
+/// or `ZwX`. ntdll lays its stubs out in syscall order, so the number is the
+/// rank of the stub's address among all `Zw*` exports. Only addresses are
+/// compared, which keeps hooked or patched stubs resolvable.
+pub fn resolve_numbers(ntdll: &PeImage, names: &[&str], numbers: &mut [u32]) -> Result<(), SyscallError> {
+    assert_eq!(names.len(), numbers.len(), "one number per name");
+
+    numbers.fill(NOT_FOUND);
+
+    for export in ntdll.exports()? {
+        let export = export?;
+        let Some(suffix) = export.name.strip_prefix("Zw") else {
+            continue;
+        };
+
+        for (name, rva) in names.iter().zip(numbers.iter_mut()) {
+            if syscall_suffix(name) == Some(suffix) {
+                *rva = export.rva;
+            }
+        }
+    }
+
+    if numbers.contains(&NOT_FOUND) {
+        return Err(SyscallError::NotExported);
+    }
+
+    // Every number still holds its stub's RVA until it is replaced by its rank.
+    for number in numbers.iter_mut() {
+        let rva = *number;
+        let mut rank = 0;
+
+        for export in ntdll.exports()? {
+            let export = export?;
+            if export.name.starts_with("Zw") && export.rva < rva {
+                rank += 1;
+            }
+        }
+
+        *number = rank;
+    }
+
+    Ok(())
+}
+
+/// Reads the `mov eax, imm32` of an unpatched stub. Fails with
+/// [`SyscallError::NotAStub`] when the export starts with anything else,
+/// e.g. the `jmp` of a hook.
+pub fn stub_number(ntdll: &PeImage, name: &str) -> Result<u32, SyscallError> {
+    let export = ntdll.export(name)?.ok_or(SyscallError::NotExported)?;
+    let code = ntdll.slice_at(export.rva, 8).ok_or(PeError::BadRva)?;
+
+    match code {
+        [prefix @ .., a, b, c, d] if prefix == STUB_PREFIX => Ok(u32::from_le_bytes([*a, *b, *c, *d])),
+        _ => Err(SyscallError::NotAStub),
+    }
+}
+
+fn syscall_suffix(name: &str) -> Option<&str> {
+    name.strip_prefix("Nt").or_else(|| name.strip_prefix("Zw"))
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::pe::Layout;
+    use std::string::String;
+    use std::vec::Vec;
+
+    const TEXT_RVA: u32 = 0x1000;
+    const TEXT_RAW: usize = 0x400;
+    const RDATA_RVA: u32 = 0x2000;
+    const RDATA_RAW: usize = 0x600;
+    const IMAGE_SIZE: usize = 0x2400;
+
+    /// Syscalls of the fixture in number order; the stub of `n` sits at `0x1020 + 0x20 * n`.
+    const SYSCALLS: [&str; 6] = [
+        "AccessCheck",
+        "WorkerFactoryWorkerReady",
+        "AcceptConnectPort",
+        "MapUserPhysicalPagesScatter",
+        "WaitForSingleObject",
+        "Close",
+    ];
+
+    /// Stub that starts with a `jmp` as if hooked.
+    const HOOKED: usize = 2;
+
+    fn put_u16(image: &mut [u8], offset: usize, value: u16) {
+        image[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn put_u32(image: &mut [u8], offset: usize, value: u32) {
+        image[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    /// File-layout PE32+ with a `.text` of stubs and an `.rdata` holding the
+    /// export directory. Section RVAs differ from their file offsets.
+    fn ntdll_fixture() -> Vec<u8> {
+        let mut image = std::vec![0u8; RDATA_RAW + 0x400];
+
+        image[..2].copy_from_slice(b"MZ");
+        put_u32(&mut image, 0x3C, 0x80);
+        image[0x80..0x84].copy_from_slice(b"PE\0\0");
+        put_u16(&mut image, 0x84, 0x8664);
+        put_u16(&mut image, 0x86, 2);
+        put_u16(&mut image, 0x94, 0xF0);
+
+        let optional = 0x98;
+        put_u16(&mut image, optional, 0x20B);
+        put_u32(&mut image, optional + 108, 16);
+
+        let sections = optional + 0xF0;
+        for (index, (name, rva, raw)) in [(b".text\0\0\0", TEXT_RVA, TEXT_RAW), (b".rdata\0\0", RDATA_RVA, RDATA_RAW)].into_iter().enumerate() {
+            let header = sections + index * 40;
+            image[header..header + 8].copy_from_slice(name);
+            put_u32(&mut image, header + 8, 0x200);
+            put_u32(&mut image, header + 12, rva);
+            put_u32(&mut image, header + 16, if index == 0 { 0x200 } else { 0x400 });
+            put_u32(&mut image, header + 20, raw as u32);
+        }
+
+        // `ret` of a non-syscall `Nt*` export placed before every stub.
+        image[TEXT_RAW] = 0xC3;
+
+        let mut exports: Vec<(String, u32)> = Vec::new();
+        exports.push(("NtdllDefWindowProc_A".into(), TEXT_RVA));
+
+        for (number, name) in SYSCALLS.iter().enumerate() {
+            let rva = TEXT_RVA + 0x20 + 0x20 * number as u32;
+            let stub = TEXT_RAW + (rva - TEXT_RVA) as usize;
+
+            if number == HOOKED {
+                image[stub] = 0xE9;
+            } else {
+                image[stub..stub + 4].copy_from_slice(&STUB_PREFIX);
+                put_u32(&mut image, stub + 4, number as u32);
+                image[stub + 8..stub + 11].copy_from_slice(&[0x0F, 0x05, 0xC3]);
+            }
+
+            exports.push((std::format!("Nt{}", name), rva));
+            exports.push((std::format!("Zw{}", name), rva));
+        }
+
+        let forwarder = RDATA_RVA + 0x380;
+        exports.push(("NtForwarded".into(), forwarder));
+        exports.sort();
+
+        let directory = RDATA_RAW;
+        let functions = RDATA_RVA + 0x40;
+        let names = RDATA_RVA + 0x100;
+        let ordinals = RDATA_RVA + 0x180;
+        let mut strings = RDATA_RVA + 0x200;
+
+        let offset = |rva: u32| RDATA_RAW + (rva - RDATA_RVA) as usize;
+
+        put_u32(&mut image, directory + 20, exports.len() as u32);
+        put_u32(&mut image, directory + 24, exports.len() as u32);
+        put_u32(&mut image, directory + 28, functions);
+        put_u32(&mut image, directory + 32, names);
+        put_u32(&mut image, directory + 36, ordinals);
+
+        for (index, (name, rva)) in exports.iter().enumerate() {
+            put_u32(&mut image, offset(functions) + index * 4, *rva);
+            put_u32(&mut image, offset(names) + index * 4, strings);
+            put_u16(&mut image, offset(ordinals) + index * 2, index as u16);
+
+            let start = offset(strings);
+            image[start..start + name.len()].copy_from_slice(name.as_bytes());
+            strings += name.len() as u32 + 1;
+        }
+
+        let target = b"other.Forwarded\0";
+        image[offset(forwarder)..offset(forwarder) + target.len()].copy_from_slice(target);
+
+        put_u32(&mut image, optional + 112, RDATA_RVA);
+        put_u32(&mut image, optional + 116, 0x3A0);
+
+        image
+    }
+
+    /// The fixture as the loader would map it.
+    fn mapped(file: &[u8]) -> Vec<u8> {
+        let mut image = std::vec![0u8; IMAGE_SIZE];
+        image[..TEXT_RAW].copy_from_slice(&file[..TEXT_RAW]);
+        image[TEXT_RVA as usize..TEXT_RVA as usize + 0x200].copy_from_slice(&file[TEXT_RAW..TEXT_RAW + 0x200]);
+        image[RDATA_RVA as usize..RDATA_RVA as usize + 0x400].copy_from_slice(&file[RDATA_RAW..RDATA_RAW + 0x400]);
+        image
+    }
+
+    #[test]
+    fn ranks_zw_exports_by_address() {
+        let file = ntdll_fixture();
+        let image = PeImage::parse(&file).unwrap();
+
+        for (number, name) in SYSCALLS.iter().enumerate() {
+            assert_eq!(syscall_number(&image, &std::format!("Nt{}", name)), Ok(number as u32));
+            assert_eq!(syscall_number(&image, &std::format!("Zw{}", name)), Ok(number as u32));
+        }
+    }
+
+    #[test]
+    fn resolves_a_table_in_one_call() {
+        let file = ntdll_fixture();
+        let image = PeImage::parse(&file).unwrap();
+
+        let mut numbers = [0; 3];
+        resolve_numbers(&image, &["NtClose", "NtAccessCheck", "NtWaitForSingleObject"], &mut numbers).unwrap();
+        assert_eq!(numbers, [5, 0, 4]);
+    }
+
+    #[test]
+    fn mapped_layout_matches_file_layout() {
+        let file = ntdll_fixture();
+        let memory = mapped(&file);
+        let image = PeImage::parse_mapped(&memory).unwrap();
+
+        assert_eq!(image.layout(), Layout::Mapped);
+        assert_eq!(syscall_number(&image, "NtClose"), Ok(5));
+        assert_eq!(stub_number(&image, "NtClose"), Ok(5));
+    }
+
+    #[test]
+    fn decodes_unpatched_stubs() {
+        let file = ntdll_fixture();
+        let image = PeImage::parse(&file).unwrap();
+
+        for (number, name) in SYSCALLS.iter().enumerate().filter(|(number, _)| *number != HOOKED) {
+            assert_eq!(stub_number(&image, &std::format!("Nt{}", name)), Ok(number as u32));
+        }
+    }
+
+    #[test]
+    fn hooked_stub_only_resolves_by_address() {
+        let file = ntdll_fixture();
+        let image = PeImage::parse(&file).unwrap();
+        let name = "NtAcceptConnectPort";
+
+        assert_eq!(stub_number(&image, name), Err(SyscallError::NotAStub));
+        assert_eq!(syscall_number(&image, name), Ok(HOOKED as u32));
+    }
+
+    #[test]
+    fn non_syscall_exports_are_not_resolved() {
+        let file = ntdll_fixture();
+        let image = PeImage::parse(&file).unwrap();
+
+        assert_eq!(syscall_number(&image, "NtdllDefWindowProc_A"), Err(SyscallError::NotExported));
+        assert_eq!(syscall_number(&image, "NtMissing"), Err(SyscallError::NotExported));
+        assert_eq!(stub_number(&image, "NtdllDefWindowProc_A"), Err(SyscallError::NotAStub));
+
+        let forwarded = image.export("NtForwarded").unwrap().unwrap();
+        assert!(forwarded.forwarded);
+        assert!(!image.export("NtClose").unwrap().unwrap().forwarded);
+    }
+
+    #[test]
+    fn rejects_malformed_images() {
+        let file = ntdll_fixture();
+
+        assert_eq!(PeImage::parse(&file[..1]).err(), Some(PeError::BadDosSignature));
+        assert_eq!(PeImage::parse(&file[..0x40]).err(), Some(PeError::BadNtSignature));
+        assert_eq!(PeImage::parse(&file[..0x100]).err(), Some(PeError::Truncated));
+
+        let mut pe32_rom = file.clone();
+        put_u16(&mut pe32_rom, 0x98, 0x107);
+        assert_eq!(PeImage::parse(&pe32_rom).err(), Some(PeError::UnsupportedFormat));
+
+        let mut bad_names = file.clone();
+        put_u32(&mut bad_names, RDATA_RAW + 32, 0x9000);
+        let image = PeImage::parse(&bad_names).unwrap();
+        assert_eq!(syscall_number(&image, "NtClose").err(), Some(SyscallError::Image(PeError::BadRva)));
+    }
+}
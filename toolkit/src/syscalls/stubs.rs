@@ -0,0 +1,39 @@
+use ntapi::{ntioapi::{FILE_INFORMATION_CLASS, PIO_APC_ROUTINE, PIO_STATUS_BLOCK}, ntmmapi::MEMORY_INFORMATION_CLASS, ntpsapi::{PPS_ATTRIBUTE_LIST, THREADINFOCLASS}};
+use ntapi::ntldr::LDR_DATA_TABLE_ENTRY;
+use ntapi::ntpsapi::NtCurrentPeb;
+use winapi::{shared::{basetsd::{PSIZE_T, SIZE_T, ULONG_PTR}, minwindef::{PULONG, ULONG}, ntdef::{BOOLEAN, HANDLE, NTSTATUS, PHANDLE, PLARGE_INTEGER, POBJECT_ATTRIBUTES, PVOID}}, um::winnt::ACCESS_MASK};
+
+use crate::SyscallError;
+use crate::pe::PeImage;
+use super::generate::define_syscalls;
+use super::resolve_numbers;
+use super::table::for_each_syscall;
+
+for_each_syscall!(define_syscalls);
+
+/// Resolves the number of every stub from the ntdll loaded in this process.
+pub fn init() -> Result<(), SyscallError> {
+    init_from(&unsafe { loaded_ntdll() }?)
+}
+
+/// Resolves the number of every stub from `ntdll`, e.g. a copy read from disk.
+pub fn init_from(ntdll: &PeImage) -> Result<(), SyscallError> {
+    let mut numbers = [0; SyscallId::COUNT];
+    resolve_numbers(ntdll, &SyscallId::NAMES, &mut numbers)?;
+
+    for (id, number) in SyscallId::ALL.into_iter().zip(numbers) {
+        id.set_number(number);
+    }
+
+    Ok(())
+}
+
+/// ntdll is always the second module in load order, after the executable.
+unsafe fn loaded_ntdll() -> Result<PeImage<'static>, SyscallError> {
+    let ldr = (*NtCurrentPeb()).Ldr;
+    let exe = (*ldr).InLoadOrderModuleList.Flink;
+    let ntdll = (*exe).Flink as *const LDR_DATA_TABLE_ENTRY;
+
+    let image = core::slice::from_raw_parts((*ntdll).DllBase as *const u8, (*ntdll).SizeOfImage as usize);
+    Ok(PeImage::parse_mapped(image)?)
+}
@@ -0,0 +1,38 @@
+/// The syscall table, one line per syscall:
+///
+/// `Name(Arg: Type, ...) ["feature" = number, ...];`
+///
+/// Types are resolved where the table is expanded and match `ntapi`. Each
+/// number is the one built in when that Cargo feature is enabled; without a
+/// matching feature the stub waits for [`init`](super::init) to resolve it.
+///
+/// Expands to `$generator! { ... }` with the table as input.
+macro_rules! for_each_syscall {
+    ($generator:ident) => {
+        $generator! {
+            NtClose(Handle: HANDLE) ["win_25h2" = 0xF];
+            NtQueryInformationFile(FileHandle: HANDLE, IoStatusBlock: PIO_STATUS_BLOCK, FileInformation: PVOID, Length: ULONG, FileInformationClass: FILE_INFORMATION_CLASS) ["win_25h2" = 0x11];
+            NtQueryInformationThread(ThreadHandle: HANDLE, ThreadInformationClass: THREADINFOCLASS, ThreadInformation: PVOID, ThreadInformationLength: ULONG, ReturnLength: PULONG) ["win_25h2" = 0x25];
+            NtSetInformationFile(FileHandle: HANDLE, IoStatusBlock: PIO_STATUS_BLOCK, FileInformation: PVOID, Length: ULONG, FileInformationClass: FILE_INFORMATION_CLASS) ["win_25h2" = 0x27];
+            NtCreateFile(FileHandle: PHANDLE, DesiredAccess: ACCESS_MASK, ObjectAttributes: POBJECT_ATTRIBUTES, IoStatusBlock: PIO_STATUS_BLOCK, AllocationSize: PLARGE_INTEGER, FileAttributes: ULONG, ShareAccess: ULONG, CreateDisposition: ULONG, CreateOptions: ULONG, EaBuffer: PVOID, EaLength: ULONG) ["win_25h2" = 0x55];
+            NtCreateNamedPipeFile(FileHandle: PHANDLE, DesiredAccess: ULONG, ObjectAttributes: POBJECT_ATTRIBUTES, IoStatusBlock: PIO_STATUS_BLOCK, ShareAccess: ULONG, CreateDisposition: ULONG, CreateOptions: ULONG, NamedPipeType: ULONG, ReadMode: ULONG, CompletionMode: ULONG, MaximumInstances: ULONG, InboundQuota: ULONG, OutboundQuota: ULONG, DefaultTimeout: PLARGE_INTEGER) ["win_25h2" = 0xBB];
+            NtOpenFile(FileHandle: PHANDLE, DesiredAccess: ACCESS_MASK, ObjectAttributes: POBJECT_ATTRIBUTES, IoStatusBlock: PIO_STATUS_BLOCK, ShareAccess: ULONG, OpenOptions: ULONG) ["win_25h2" = 0x33];
+            NtReadFile(FileHandle: HANDLE, Event: HANDLE, ApcRoutine: PIO_APC_ROUTINE, ApcContext: PVOID, IoStatusBlock: PIO_STATUS_BLOCK, Buffer: PVOID, Length: ULONG, ByteOffset: PLARGE_INTEGER, Key: PULONG) ["win_25h2" = 0x6];
+            NtWriteFile(FileHandle: HANDLE, Event: HANDLE, ApcRoutine: PIO_APC_ROUTINE, ApcContext: PVOID, IoStatusBlock: PIO_STATUS_BLOCK, Buffer: PVOID, Length: ULONG, ByteOffset: PLARGE_INTEGER, Key: PULONG) ["win_25h2" = 0x8];
+            NtDeviceIoControlFile(FileHandle: HANDLE, Event: HANDLE, ApcRoutine: PIO_APC_ROUTINE, ApcContext: PVOID, IoStatusBlock: PIO_STATUS_BLOCK, IoControlCode: ULONG, InputBuffer: PVOID, InputBufferLength: ULONG, OutputBuffer: PVOID, OutputBufferLength: ULONG) ["win_25h2" = 0x7];
+            NtDeleteFile(ObjectAttributes: POBJECT_ATTRIBUTES) ["win_25h2" = 0xDB];
+            NtQueryVirtualMemory(ProcessHandle: HANDLE, BaseAddress: PVOID, MemoryInformationClass: MEMORY_INFORMATION_CLASS, MemoryInformation: PVOID, MemoryInformationLength: SIZE_T, ReturnLength: PSIZE_T) ["win_25h2" = 0x23];
+            NtAllocateVirtualMemory(ProcessHandle: HANDLE, BaseAddress: *mut PVOID, ZeroBits: ULONG_PTR, RegionSize: PSIZE_T, AllocationType: ULONG, Protect: ULONG) ["win_25h2" = 0x18];
+            NtWriteVirtualMemory(ProcessHandle: HANDLE, BaseAddress: PVOID, Buffer: PVOID, BufferSize: SIZE_T, NumberOfBytesWritten: PSIZE_T) ["win_25h2" = 0x3A];
+            NtReadVirtualMemory(ProcessHandle: HANDLE, BaseAddress: PVOID, Buffer: PVOID, BufferSize: SIZE_T, NumberOfBytesRead: PSIZE_T) ["win_25h2" = 0x3F];
+            NtProtectVirtualMemory(ProcessHandle: HANDLE, BaseAddress: *mut PVOID, RegionSize: PSIZE_T, NewProtect: ULONG, OldProtect: PULONG) ["win_25h2" = 0x50];
+            NtTerminateProcess(ProcessHandle: HANDLE, ExitStatus: NTSTATUS) ["win_25h2" = 0x2C];
+            NtDelayExecution(Alertable: BOOLEAN, DelayInterval: PLARGE_INTEGER) ["win_25h2" = 0x34];
+            NtCreateThreadEx(ThreadHandle: PHANDLE, DesiredAccess: ACCESS_MASK, ObjectAttributes: POBJECT_ATTRIBUTES, ProcessHandle: HANDLE, StartRoutine: PVOID, Argument: PVOID, CreateFlags: ULONG, ZeroBits: SIZE_T, StackSize: SIZE_T, MaximumStackSize: SIZE_T, AttributeList: PPS_ATTRIBUTE_LIST) ["win_25h2" = 0xC9];
+            NtWaitForSingleObject(Handle: HANDLE, Alertable: BOOLEAN, Timeout: PLARGE_INTEGER) ["win_25h2" = 0x4];
+            NtSuspendThread(ThreadHandle: HANDLE, PreviousSuspendCount: PULONG) ["win_25h2" = 0x1CF];
+        }
+    };
+}
+
+pub(crate) use for_each_syscall;
@@ -0,0 +1,319 @@
+use core::cell::{Cell, RefCell};
+use core::ffi::c_void;
+use core::ptr;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use alloc::boxed::Box;
+
+use crate::AccessError;
+use crate::sys::tls;
+
+/// Keys a process can create. Every thread that touches a key gets a table
+/// with this many entries.
+pub const MAX_KEYS: usize = 128;
+
+static NEXT_KEY: AtomicUsize = AtomicUsize::new(0);
+
+/// Marks a thread whose destructors are running.
+const DESTROYED: *mut c_void = ptr::without_provenance_mut(1);
+
+/// Declares [`LocalKey`] statics. Each thread gets its own value, created on
+/// first access and dropped when a toolkit thread exits. Other threads drop
+/// theirs on exit when libc is linked on Linux, or on thread detach when the
+/// image has a TLS directory on Windows.
+///
+/// ```
+/// use core::cell::Cell;
+///
+/// toolkit::thread_local! {
+///     static COUNTER: Cell<u32> = Cell::new(0);
+/// }
+///
+/// COUNTER.set(COUNTER.get() + 1);
+/// assert_eq!(COUNTER.get(), 1);
+/// ```
+#[macro_export]
+macro_rules! thread_local {
+    () => {};
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $ty:ty = $init:expr; $($rest:tt)*) => {
+        $crate::thread_local!($(#[$attr])* $vis static $name: $ty = $init);
+        $crate::thread_local!($($rest)*);
+    };
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $ty:ty = $init:expr) => {
+        $(#[$attr])*
+        $vis static $name: $crate::thread::LocalKey<$ty> = {
+            fn init() -> $ty {
+                $init
+            }
+            $crate::thread::LocalKey::new(init)
+        };
+    };
+}
+
+struct Value {
+    ptr: *mut u8,
+    drop: unsafe fn(*mut u8),
+}
+
+/// Values of one thread, indexed by key.
+struct Table {
+    values: [Option<Value>; MAX_KEYS],
+}
+
+unsafe fn drop_value<T>(ptr: *mut u8) {
+    drop(Box::from_raw(ptr as *mut T));
+}
+
+/// Exit hook: drops the calling thread's values in key order.
+fn run_destructors() {
+    let table = tls::current();
+    if table.is_null() || table == DESTROYED {
+        return;
+    }
+
+    tls::set_current(DESTROYED);
+
+    let table = unsafe { Box::from_raw(table as *mut Table) };
+    for value in table.values.into_iter().flatten() {
+        unsafe { (value.drop)(value.ptr) };
+    }
+
+    tls::set_current(ptr::null_mut());
+}
+
+fn current_table() -> Result<*mut Table, AccessError> {
+    let table = tls::current();
+    if table == DESTROYED {
+        return Err(AccessError::Destroyed);
+    }
+    if !table.is_null() {
+        return Ok(table as *mut Table);
+    }
+
+    let table = Box::into_raw(Box::new(Table { values: [const { None }; MAX_KEYS] }));
+    if !tls::set_current(table as *mut c_void) {
+        drop(unsafe { Box::from_raw(table) });
+        return Err(AccessError::NoSlot);
+    }
+
+    tls::set_exit_hook(run_destructors);
+    Ok(table)
+}
+
+/// Handle to a per-thread value, declared with [`thread_local!`](crate::thread_local).
+pub struct LocalKey<T: 'static> {
+    /// Index into the thread tables plus one, zero until first use.
+    key: AtomicUsize,
+    init: fn() -> T,
+}
+
+impl<T: 'static> LocalKey<T> {
+    #[doc(hidden)]
+    pub const fn new(init: fn() -> T) -> Self {
+        Self { key: AtomicUsize::new(0), init }
+    }
+
+    /// Panics if the value cannot be reached, see [`LocalKey::try_with`].
+    pub fn with<F, R>(&'static self, f: F) -> R
+    where
+        F: FnOnce(&T) -> R,
+    {
+        match self.try_with(f) {
+            Ok(result) => result,
+            Err(err) => panic!("cannot access a thread local: {}", err),
+        }
+    }
+
+    /// Fails from inside a thread-local destructor, or when no key or slot is left.
+    pub fn try_with<F, R>(&'static self, f: F) -> Result<R, AccessError>
+    where
+        F: FnOnce(&T) -> R,
+    {
+        let value = self.value()?;
+        Ok(f(unsafe { &*value }))
+    }
+
+    fn key(&self) -> Result<usize, AccessError> {
+        let key = self.key.load(Ordering::Acquire);
+        if key != 0 {
+            return Ok(key - 1);
+        }
+
+        let new = NEXT_KEY.fetch_add(1, Ordering::Relaxed);
+        if new >= MAX_KEYS {
+            return Err(AccessError::KeysExhausted);
+        }
+
+        // A racing thread may have won; its index is kept and ours is wasted.
+        match self.key.compare_exchange(0, new + 1, Ordering::AcqRel, Ordering::Acquire) {
+            Ok(_) => Ok(new),
+            Err(key) => Ok(key - 1),
+        }
+    }
+
+    fn value(&self) -> Result<*const T, AccessError> {
+        let key = self.key()?;
+        let table = current_table()?;
+
+        unsafe {
+            if let Some(value) = &(*table).values[key] {
+                return Ok(value.ptr as *const T);
+            }
+
+            // `init` may reach other keys of this thread, so no borrow of the
+            // table is held across it. If it initialized this key, that value wins.
+            let value = (self.init)();
+            if let Some(existing) = &(*table).values[key] {
+                return Ok(existing.ptr as *const T);
+            }
+
+            let ptr = Box::into_raw(Box::new(value));
+            (*table).values[key] = Some(Value { ptr: ptr as *mut u8, drop: drop_value::<T> });
+            Ok(ptr)
+        }
+    }
+}
+
+impl<T: 'static> LocalKey<Cell<T>> {
+    pub fn set(&'static self, value: T) {
+        self.with(|cell| cell.set(value));
+    }
+
+    pub fn get(&'static self) -> T
+    where
+        T: Copy,
+    {
+        self.with(Cell::get)
+    }
+
+    pub fn take(&'static self) -> T
+    where
+        T: Default,
+    {
+        self.with(Cell::take)
+    }
+
+    pub fn replace(&'static self, value: T) -> T {
+        self.with(|cell| cell.replace(value))
+    }
+}
+
+impl<T: 'static> LocalKey<RefCell<T>> {
+    /// Panics if the value is mutably borrowed.
+    pub fn with_borrow<F, R>(&'static self, f: F) -> R
+    where
+        F: FnOnce(&T) -> R,
+    {
+        self.with(|cell| f(&cell.borrow()))
+    }
+
+    /// Panics if the value is already borrowed.
+    pub fn with_borrow_mut<F, R>(&'static self, f: F) -> R
+    where
+        F: FnOnce(&mut T) -> R,
+    {
+        self.with(|cell| f(&mut cell.borrow_mut()))
+    }
+
+    pub fn set(&'static self, value: T) {
+        self.with_borrow_mut(|current| *current = value);
+    }
+
+    pub fn take(&'static self) -> T
+    where
+        T: Default,
+    {
+        self.with(RefCell::take)
+    }
+
+    pub fn replace(&'static self, value: T) -> T {
+        self.with(|cell| cell.replace(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::thread::Thread;
+    use alloc::vec::Vec;
+
+    struct CountDrop(&'static AtomicUsize);
+
+    impl Drop for CountDrop {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    crate::thread_local! {
+        static ID: Cell<usize> = Cell::new(0);
+        static GUARD: RefCell<Option<CountDrop>> = RefCell::new(None);
+    }
+
+    #[test]
+    fn values_are_per_thread() {
+        ID.set(100);
+
+        let handles: Vec<_> = (1..=4)
+            .map(|id| {
+                Thread::spawn(move || {
+                    assert_eq!(ID.get(), 0);
+                    ID.set(id);
+                    for _ in 0..1000 {
+                        assert_eq!(ID.get(), id);
+                    }
+                    ID.get()
+                })
+                .unwrap()
+            })
+            .collect();
+
+        let ids: Vec<_> = handles.into_iter().map(|handle| handle.join().unwrap()).collect();
+        assert_eq!(ids, [1, 2, 3, 4]);
+        assert_eq!(ID.get(), 100);
+    }
+
+    #[test]
+    fn destructors_run_on_thread_exit() {
+        static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| Thread::spawn(|| GUARD.set(Some(CountDrop(&DROPPED)))).unwrap())
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(DROPPED.load(Ordering::Relaxed), 4);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn destructors_run_on_foreign_threads() {
+        static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+        std::thread::spawn(|| GUARD.set(Some(CountDrop(&DROPPED)))).join().unwrap();
+        assert_eq!(DROPPED.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn destroyed_value_cannot_be_reached() {
+        static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+        struct Probe;
+
+        impl Drop for Probe {
+            fn drop(&mut self) {
+                assert!(matches!(ID.try_with(|_| ()), Err(AccessError::Destroyed)));
+                DROPPED.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        crate::thread_local! {
+            static PROBE: RefCell<Option<Probe>> = RefCell::new(None);
+        }
+
+        Thread::spawn(|| PROBE.set(Some(Probe))).unwrap().join().unwrap();
+        assert_eq!(DROPPED.load(Ordering::Relaxed), 1);
+    }
+}
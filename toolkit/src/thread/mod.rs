@@ -1,13 +1,25 @@
 mod join;
+mod local;
 mod thread;
 mod packet;
+mod pool;
+mod scope;
+#[cfg(windows)]
 mod stack;
+#[cfg(windows)]
 mod context;
+#[cfg(windows)]
 mod suspended;
 
 pub use join::*;
+pub use local::*;
 pub use thread::*;
 pub use packet::*;
+pub use pool::*;
+pub use scope::*;
+#[cfg(windows)]
 pub use stack::*;
+#[cfg(windows)]
 pub use context::*;
-pub use suspended::*;
\ No newline at end of file
+#[cfg(windows)]
+pub use suspended::*;
@@ -1,20 +1,22 @@
-use core::{cell::UnsafeCell, ptr};
+use core::{cell::UnsafeCell, intrinsics, ptr};
 use core::sync::atomic::{AtomicBool, Ordering};
 use alloc::boxed::Box;
-use ntapi::ntobapi::{NtClose, NtWaitForSingleObject};
-use ntapi::ntpsapi::NtCurrentProcess;
-use winapi::shared::ntdef::{NTSTATUS, HANDLE};
-use winapi::shared::ntstatus::STATUS_SUCCESS;
-use winapi::um::winnt::{THREAD_ALL_ACCESS, LARGE_INTEGER};
-use crate::ArcInner;
+use crate::{ArcInner, ThreadError};
 use crate::arc::Arc;
 
 struct PacketInner<T> {
     finished: AtomicBool,
+    panicked: AtomicBool,
     result: UnsafeCell<Option<T>>,
     func: UnsafeCell<Option<Box<dyn FnOnce() -> T + Send>>>,
 }
 
+// The closure is taken and the result written only by the spawned thread;
+// the result is read after `finished` or the join, both of which the write
+// happens-before.
+unsafe impl<T: Send> Send for PacketInner<T> {}
+unsafe impl<T: Send> Sync for PacketInner<T> {}
+
 pub struct Packet<T>(Arc<PacketInner<T>>);
 
 impl<T> Clone for Packet<T> {
@@ -27,11 +29,28 @@ impl<T> Packet<T> {
     pub fn new(func: impl FnOnce() -> T + Send + 'static) -> Self {
         Self(Arc::new(PacketInner {
             finished: AtomicBool::new(false),
+            panicked: AtomicBool::new(false),
             result: UnsafeCell::new(None),
             func: UnsafeCell::new(Some(Box::new(func))),
         }))
     }
 
+    /// # Safety
+    ///
+    /// `func` must not be called or dropped after anything it borrows is gone;
+    /// the caller has to join the thread before `'a` ends.
+    pub unsafe fn new_unchecked<'a>(func: impl FnOnce() -> T + Send + 'a) -> Self {
+        let func: Box<dyn FnOnce() -> T + Send + 'a> = Box::new(func);
+        let func: Box<dyn FnOnce() -> T + Send> = unsafe { core::mem::transmute(func) };
+
+        Self(Arc::new(PacketInner {
+            finished: AtomicBool::new(false),
+            panicked: AtomicBool::new(false),
+            result: UnsafeCell::new(None),
+            func: UnsafeCell::new(Some(func)),
+        }))
+    }
+
     pub fn from_ptr(ptr: *mut Self) -> Self {
         unsafe { ptr.read() }
     }
@@ -45,6 +64,24 @@ impl<T> Packet<T> {
         }
     }
 
+    /// Runs the closure and stores its result. A panic is caught here rather
+    /// than unwinding out of the thread's entry point, and leaves no result.
+    /// The panic payload is leaked, only the panic runtime knows how to free it.
+    pub fn run(&self) {
+        fn call<T>(packet: *mut u8) {
+            let packet = unsafe { &*(packet as *const Packet<T>) };
+            packet.set_result(packet.execute());
+        }
+
+        fn caught(_packet: *mut u8, _payload: *mut u8) {}
+
+        let data = self as *const Self as *mut u8;
+        if unsafe { intrinsics::catch_unwind(call::<T>, data, caught) } != 0 {
+            self.0.panicked.store(true, Ordering::Relaxed);
+            self.0.finished.store(true, Ordering::Release);
+        }
+    }
+
     pub fn set_result(&self, result: T) {
         unsafe {
             let inner = &*self.0;
@@ -57,11 +94,13 @@ impl<T> Packet<T> {
         (*self.0).finished.load(Ordering::Acquire)
     }
 
-    pub fn take_result(&self) -> Option<T> {
-        unsafe {
-            let inner = &*self.0;
-            (*inner.result.get()).take()
+    /// [`ThreadError::ThreadTerminated`] when the closure panicked.
+    pub fn take_result(&self) -> Result<T, ThreadError> {
+        let inner = &*self.0;
+        if inner.panicked.load(Ordering::Relaxed) {
+            return Err(ThreadError::ThreadTerminated);
         }
+        unsafe { (*inner.result.get()).take() }.ok_or(ThreadError::NoResult)
     }
 
     pub fn drop(self: *mut Self) {
@@ -0,0 +1,223 @@
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+
+use crate::{Arc, Condvar, JoinHandle, Mutex, Thread, ThreadError};
+
+type Job = Box<dyn FnOnce() + Send>;
+
+struct State {
+    jobs: VecDeque<Job>,
+    running: usize,
+    shutdown: bool,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    work: Condvar,
+    idle: Condvar,
+    /// Includes workers that died with a panicking job, until they are joined.
+    workers: Mutex<Vec<JoinHandle<()>>>,
+}
+
+/// Fixed set of worker threads taking jobs from a shared FIFO queue.
+/// Dropping the pool finishes the queued jobs and joins the workers.
+///
+/// A panicking job takes its worker down and a new worker replaces it.
+pub struct ThreadPool {
+    shared: Arc<Shared>,
+    size: usize,
+}
+
+impl ThreadPool {
+    pub fn new(workers: usize) -> Result<Self, ThreadError> {
+        if workers == 0 {
+            return Err(ThreadError::InvalidArgument);
+        }
+
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State { jobs: VecDeque::new(), running: 0, shutdown: false }),
+            work: Condvar::new(),
+            idle: Condvar::new(),
+            workers: Mutex::new(Vec::with_capacity(workers)),
+        });
+        let pool = Self { shared, size: workers };
+
+        for _ in 0..workers {
+            spawn_worker(&pool.shared)?;
+        }
+
+        Ok(pool)
+    }
+
+    pub fn workers(&self) -> usize {
+        self.size
+    }
+
+    /// Jobs waiting for a free worker.
+    pub fn queued(&self) -> usize {
+        self.shared.state.lock().jobs.len()
+    }
+
+    pub fn execute<F>(&self, job: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.shared.state.lock().jobs.push_back(Box::new(job));
+        self.shared.work.notify_one();
+    }
+
+    /// Blocks until the queue is empty and no job is running.
+    pub fn join_all(&self) {
+        let state = self.shared.state.lock();
+        let _state = self.shared.idle.wait_while(state, |state| !state.jobs.is_empty() || state.running > 0);
+    }
+
+    /// Lets the workers finish the queued jobs, then joins them.
+    /// [`ThreadError::ThreadTerminated`] if any job panicked.
+    pub fn shutdown(mut self) -> Result<(), ThreadError> {
+        self.stop()
+    }
+
+    fn stop(&mut self) -> Result<(), ThreadError> {
+        self.shared.state.lock().shutdown = true;
+        self.shared.work.notify_all();
+
+        // Replacements for dying workers may be pushed while this joins.
+        let mut result = Ok(());
+        loop {
+            let Some(worker) = self.shared.workers.lock().pop() else {
+                break;
+            };
+            let joined = worker.join();
+            if result.is_ok() {
+                result = joined;
+            }
+        }
+
+        result
+    }
+}
+
+impl Drop for ThreadPool {
+    fn drop(&mut self) {
+        let _ = self.stop();
+    }
+}
+
+fn spawn_worker(shared: &Arc<Shared>) -> Result<(), ThreadError> {
+    let shared_clone = shared.clone();
+    let handle = Thread::spawn(move || worker(&shared_clone))?;
+    shared.workers.lock().push(handle);
+    Ok(())
+}
+
+/// Only dropped while a job unwinds: the job no longer counts as running,
+/// and a new worker takes over the queue from the dying one.
+struct Sentinel<'a>(&'a Arc<Shared>);
+
+impl Drop for Sentinel<'_> {
+    fn drop(&mut self) {
+        let shared = self.0;
+        finish_job(shared);
+        let _ = spawn_worker(shared);
+    }
+}
+
+fn finish_job(shared: &Shared) {
+    let mut state = shared.state.lock();
+    state.running -= 1;
+    let idle = state.running == 0 && state.jobs.is_empty();
+    drop(state);
+
+    if idle {
+        shared.idle.notify_all();
+    }
+}
+
+fn worker(shared: &Arc<Shared>) {
+    loop {
+        let mut state = shared.state.lock();
+        let job = loop {
+            if let Some(job) = state.jobs.pop_front() {
+                break job;
+            }
+            if state.shutdown {
+                return;
+            }
+            state = shared.work.wait(state);
+        };
+        state.running += 1;
+        drop(state);
+
+        let sentinel = Sentinel(shared);
+        job();
+        core::mem::forget(sentinel);
+
+        finish_job(shared);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn join_all_waits_for_every_job() {
+        let pool = ThreadPool::new(4).unwrap();
+        let done = Arc::new(AtomicUsize::new(0));
+
+        for index in 0..100 {
+            let done = done.clone();
+            pool.execute(move || {
+                let buffer: Vec<usize> = (0..index).collect();
+                done.fetch_add(buffer.len(), Ordering::Relaxed);
+            });
+        }
+
+        pool.join_all();
+        assert_eq!(done.load(Ordering::Relaxed), (0..100).sum());
+        assert_eq!(pool.queued(), 0);
+        assert_eq!(pool.workers(), 4);
+    }
+
+    #[test]
+    fn shutdown_finishes_queued_jobs() {
+        let pool = ThreadPool::new(1).unwrap();
+        let done = Arc::new(AtomicUsize::new(0));
+
+        for _ in 0..20 {
+            let done = done.clone();
+            pool.execute(move || {
+                done.fetch_add(1, Ordering::Relaxed);
+            });
+        }
+
+        pool.shutdown().unwrap();
+        assert_eq!(done.load(Ordering::Relaxed), 20);
+    }
+
+    #[test]
+    fn panicking_job_replaces_its_worker() {
+        let pool = ThreadPool::new(2).unwrap();
+        let done = Arc::new(AtomicUsize::new(0));
+
+        pool.execute(|| panic!("job panicked"));
+        for _ in 0..20 {
+            let done = done.clone();
+            pool.execute(move || {
+                done.fetch_add(1, Ordering::Relaxed);
+            });
+        }
+
+        pool.join_all();
+        assert_eq!(done.load(Ordering::Relaxed), 20);
+        assert!(matches!(pool.shutdown(), Err(ThreadError::ThreadTerminated)));
+    }
+
+    #[test]
+    fn zero_workers() {
+        assert!(matches!(ThreadPool::new(0), Err(ThreadError::InvalidArgument)));
+    }
+}
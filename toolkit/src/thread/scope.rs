@@ -0,0 +1,157 @@
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use alloc::vec::Vec;
+
+use crate::sys::thread::NativeThread;
+use crate::{Mutex, Packet, Thread, ThreadError};
+
+/// Threads spawned here may borrow anything that outlives the `scope` call.
+pub struct Scope<'scope, 'env: 'scope> {
+    /// Threads nobody has joined yet, indexed by [`ScopedJoinHandle::index`].
+    threads: Mutex<Vec<Option<NativeThread>>>,
+    /// Threads that panicked and whose handle has not reported it.
+    panicked: AtomicUsize,
+    scope: PhantomData<&'scope mut &'scope ()>,
+    env: PhantomData<&'env mut &'env ()>,
+}
+
+pub struct ScopedJoinHandle<'scope, T> {
+    scope: &'scope Mutex<Vec<Option<NativeThread>>>,
+    panicked: &'scope AtomicUsize,
+    index: usize,
+    packet: Packet<T>,
+}
+
+/// Runs `f` and joins every thread it spawned and did not join itself before returning.
+///
+/// Panics if one of the threads joined here panicked.
+pub fn scope<'env, F, T>(f: F) -> T
+where
+    F: for<'scope> FnOnce(&'scope Scope<'scope, 'env>) -> T,
+{
+    let scope = Scope {
+        threads: Mutex::new(Vec::new()),
+        panicked: AtomicUsize::new(0),
+        scope: PhantomData,
+        env: PhantomData,
+    };
+
+    // Joins even if `f` unwinds, the threads may still be using the borrows.
+    struct JoinRemaining<'a>(&'a Mutex<Vec<Option<NativeThread>>>);
+
+    impl Drop for JoinRemaining<'_> {
+        fn drop(&mut self) {
+            let threads = core::mem::take(&mut *self.0.lock());
+            for native in threads.into_iter().flatten() {
+                let _ = native.join();
+            }
+        }
+    }
+
+    let guard = JoinRemaining(&scope.threads);
+    let result = f(&scope);
+    drop(guard);
+
+    if scope.panicked.load(Ordering::Acquire) > 0 {
+        panic!("a scoped thread panicked");
+    }
+    result
+}
+
+/// Counts the thread as panicked unless forgotten after the closure returns.
+struct CountPanic<'a>(&'a AtomicUsize);
+
+impl Drop for CountPanic<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::Release);
+    }
+}
+
+impl<'scope, 'env> Scope<'scope, 'env> {
+    pub fn spawn<F, T>(&'scope self, func: F) -> Result<ScopedJoinHandle<'scope, T>, ThreadError>
+    where
+        F: FnOnce() -> T + Send + 'scope,
+        T: Send + 'scope,
+    {
+        let panicked = &self.panicked;
+        let func = move || {
+            let count = CountPanic(panicked);
+            let result = func();
+            core::mem::forget(count);
+            result
+        };
+
+        // SAFETY: `scope` joins the thread before `'scope` ends.
+        let handle = unsafe { Thread::spawn_unchecked(func)? };
+
+        let mut threads = self.threads.lock();
+        threads.push(Some(handle.native));
+
+        Ok(ScopedJoinHandle {
+            scope: &self.threads,
+            panicked: &self.panicked,
+            index: threads.len() - 1,
+            packet: handle.packet,
+        })
+    }
+}
+
+impl<T> ScopedJoinHandle<'_, T> {
+    pub fn is_finished(&self) -> bool {
+        self.packet.is_finished()
+    }
+
+    pub fn join(self) -> Result<T, ThreadError> {
+        let native = self.scope.lock()[self.index].take().ok_or(ThreadError::NotJoinable)?;
+        native.join()?;
+
+        let result = self.packet.take_result();
+        if let Err(ThreadError::ThreadTerminated) = result {
+            self.panicked.fetch_sub(1, Ordering::Relaxed);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn threads_borrow_from_the_stack() {
+        let mut counts = vec![0usize; 4];
+        let words = ["alpha", "beta", "gamma", "delta"];
+
+        let total = scope(|scope| {
+            for (count, word) in counts.iter_mut().zip(words) {
+                scope.spawn(move || *count = word.len()).unwrap();
+            }
+
+            let handle = scope.spawn(|| words.iter().map(|word| word.len()).sum::<usize>()).unwrap();
+            handle.join().unwrap()
+        });
+
+        assert_eq!(counts, [5, 4, 5, 5]);
+        assert_eq!(total, 19);
+    }
+
+    #[test]
+    fn joined_panic_is_reported_by_the_handle() {
+        let finished = scope(|scope| {
+            let handle = scope.spawn(|| -> usize { panic!("scoped thread panicked") }).unwrap();
+            let result = handle.join();
+            assert!(matches!(result, Err(ThreadError::ThreadTerminated)));
+            true
+        });
+        assert!(finished);
+    }
+
+    #[test]
+    #[should_panic(expected = "a scoped thread panicked")]
+    fn unjoined_panic_propagates() {
+        scope(|scope| {
+            scope.spawn(|| panic!("scoped thread panicked")).unwrap();
+        });
+    }
+}
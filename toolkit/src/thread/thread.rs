@@ -1,13 +1,8 @@
-use core::ptr::null_mut;
-use core::{mem, ptr};
-use ntapi::ntapi_base::CLIENT_ID;
-use ntapi::ntpsapi::{NtCreateThread, NtCreateThreadEx, NtCurrentProcess, NtQueryInformationThread, THREAD_BASIC_INFORMATION, ThreadBasicInformation};
-use ntapi::ntrtl::RtlExitUserThread;
-use winapi::shared::{ntdef::HANDLE, ntstatus::STATUS_SUCCESS};
-use winapi::um::winbase::CREATE_SUSPENDED;
-use winapi::um::winnt::THREAD_ALL_ACCESS;
+use core::ffi::c_void;
 use crate::*;
-use crate::arc::Arc;
+use crate::sys::thread::NativeThread;
+#[cfg(windows)]
+use crate::sys::thread::exit_current;
 
 pub struct Thread;
 
@@ -19,57 +14,42 @@ impl Thread {
         F: Send + 'static,
         T: Send + 'static,
     {
-        let packet = Packet::new(func);
+        unsafe { Self::spawn_unchecked(func) }
+    }
+
+    /// [`Thread::spawn`] without the `'static` bounds.
+    ///
+    /// # Safety
+    ///
+    /// The thread must be joined before `'a` ends.
+    pub unsafe fn spawn_unchecked<'a, F, T>(func: F) -> Result<JoinHandle<T>, ThreadError>
+    where
+        F: FnOnce() -> T,
+        F: Send + 'a,
+        T: Send + 'a,
+    {
+        let packet = unsafe { Packet::new_unchecked(func) };
+
+        unsafe extern "system" fn thread_entry<T>(param: *mut c_void) {
+            Packet::<T>::from_ptr(param as _).run();
 
-        unsafe extern "system" fn thread_entry<T>(param: *mut winapi::ctypes::c_void) {
-            
-            let packet = Packet::<T>::from_ptr(param as _);
-            let result = packet.execute();
-            packet.set_result(result);
-            
-            unsafe { RtlExitUserThread(0) };
+            #[cfg(windows)]
+            exit_current();
         }
 
         let packet_clone = packet.into_raw();
-        let param_ptr: *mut winapi::ctypes::c_void = packet_clone as _;
-        let mut thread_handle: HANDLE = ptr::null_mut();
-        let mut context = ThreadContext::new();
-        let stack_size = 1024 * 1024;
-        let stack = ThreadStack::new(stack_size).unwrap();
-        let mut initial_teb = stack.to_initial_teb();
-        let rsp = stack.rsp();
-
-        let rip = thread_entry::<T> as _;
-        let rcx = param_ptr as u64;
-        context.set_thread_context(rsp, rip, rcx);
-
-        let mut client_id: CLIENT_ID = unsafe { mem::zeroed() };
-
-        let status = unsafe {
-            NtCreateThread(
-                &mut thread_handle as _,
-                THREAD_ALL_ACCESS,
-                ptr::null_mut(),
-                NtCurrentProcess,
-                &mut client_id as _,
-                &mut *context,
-                &mut initial_teb as _,
-                0,
-            )
-        };
-        
-        if status != STATUS_SUCCESS {
-            stack.free().unwrap();
-            return Err(ThreadError::CreationFailed(status));
-        }
+        let param_ptr: *mut c_void = packet_clone as _;
 
-        Ok(JoinHandle {
-            handle: thread_handle,
-            packet,
-            stack: Some(stack)
-        })
+        match NativeThread::spawn(thread_entry::<T>, param_ptr) {
+            Ok(native) => Ok(JoinHandle { native, packet }),
+            Err(err) => {
+                packet_clone.drop();
+                Err(err)
+            }
+        }
     }
 
+    #[cfg(windows)]
     pub fn spawn_ex<F, T>(func: F) -> Result<JoinHandle<T>, ThreadError>
     where
         F: FnOnce() -> T,
@@ -78,43 +58,71 @@ impl Thread {
     {
         let packet = Packet::new(func);
         
-        unsafe extern "system" fn thread_entry<T>(param: *mut winapi::ctypes::c_void) -> u32 {
-            let packet = Packet::<T>::from_ptr(param as _);
-            let result = packet.execute();
-            packet.set_result(result);
-            
+        unsafe extern "system" fn thread_entry<T>(param: *mut c_void) -> u32 {
+            Packet::<T>::from_ptr(param as _).run();
+            crate::sys::tls::run_exit_hook();
+
             0
         }
 
         let packet_clone = packet.into_raw();
-        let param_ptr: *mut winapi::ctypes::c_void = packet_clone as _;
-        let mut handle: HANDLE = ptr::null_mut();
-
-        let status = unsafe {
-            NtCreateThreadEx(
-                &mut handle,
-                THREAD_ALL_ACCESS,
-                ptr::null_mut(),
-                NtCurrentProcess,
-                thread_entry::<T> as *mut _,
-                param_ptr,
-                0,
-                0,
-                0,
-                0,
-                ptr::null_mut(),
-            )
-        };
-
-        if status != STATUS_SUCCESS {
-            packet_clone.drop();
-            return Err(ThreadError::CreationFailed(status));
+        let param_ptr: *mut c_void = packet_clone as _;
+
+        match NativeThread::spawn_ex(thread_entry::<T>, param_ptr) {
+            Ok(native) => Ok(JoinHandle { native, packet }),
+            Err(err) => {
+                packet_clone.drop();
+                Err(err)
+            }
         }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+    use core::sync::atomic::{AtomicBool, Ordering};
 
-        Ok(JoinHandle {
-            handle,
-            packet,
-            stack: None,
+    #[test]
+    fn threads_allocate_concurrently() {
+        let handles: Vec<_> = (0..4)
+            .map(|seed| {
+                Thread::spawn(move || {
+                    let mut total = 0;
+                    for round in 0..2000 {
+                        let buffer: Vec<usize> = (0..(round % 64) + 1).map(|value| value * seed).collect();
+                        total += buffer.iter().sum::<usize>();
+                    }
+                    total
+                })
+                .unwrap()
+            })
+            .collect();
+
+        let expected: Vec<usize> = (0..4)
+            .map(|seed| (0..2000).map(|round| ((round % 64) + 1) * (round % 64) / 2 * seed).sum())
+            .collect();
+
+        let totals: Vec<_> = handles.into_iter().map(|handle| handle.join().unwrap()).collect();
+        assert_eq!(totals, expected);
+    }
+
+    #[test]
+    fn join_timeout_leaves_the_thread_running() {
+        let release = Arc::new(AtomicBool::new(false));
+        let handle = Thread::spawn({
+            let release = release.clone();
+            move || {
+                while !release.load(Ordering::Acquire) {
+                    core::hint::spin_loop();
+                }
+            }
         })
+        .unwrap();
+
+        assert!(!handle.is_finished());
+        assert!(matches!(handle.join_timeout(20), Err(ThreadError::Timeout)));
+        release.store(true, Ordering::Release);
     }
-}
\ No newline at end of file
+}
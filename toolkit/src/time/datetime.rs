@@ -0,0 +1,849 @@
+//! Proleptic Gregorian calendar over FILETIME and Unix timestamps.
+//!
+//! Day numbers are converted with Howard Hinnant's `civil_from_days` /
+//! `days_from_civil`, so no conversion loops over years or months.
+
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops::{Add, AddAssign, Sub, SubAssign};
+use core::str::FromStr;
+use core::time::Duration;
+
+use crate::{DateTimeError, sys};
+
+/// Seconds from 1601-01-01 (the FILETIME epoch) to 1970-01-01.
+pub const WINDOWS_EPOCH_TO_UNIX: i64 = 11_644_473_600;
+
+const TICKS_PER_SEC: u64 = 10_000_000;
+const NANOS_PER_TICK: u32 = 100;
+const NANOS_PER_SEC: i128 = 1_000_000_000;
+const SECS_PER_DAY: i64 = 86_400;
+
+const MIN_YEAR: i32 = -9999;
+const MAX_YEAR: i32 = 9999;
+
+/// 1970-01-01 was a Thursday.
+const EPOCH_WEEKDAY: i64 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Weekday {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Weekday::Monday => "Monday",
+            Weekday::Tuesday => "Tuesday",
+            Weekday::Wednesday => "Wednesday",
+            Weekday::Thursday => "Thursday",
+            Weekday::Friday => "Friday",
+            Weekday::Saturday => "Saturday",
+            Weekday::Sunday => "Sunday",
+        }
+    }
+
+    /// Zero for Monday, as in ISO 8601.
+    pub const fn days_from_monday(&self) -> u8 {
+        *self as u8
+    }
+}
+
+/// Calendar date in the proleptic Gregorian calendar, years `-9999..=9999`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    year: i32,
+    month: u8,
+    day: u8,
+}
+
+impl Date {
+    pub const UNIX_EPOCH: Date = Date { year: 1970, month: 1, day: 1 };
+    pub const FILETIME_EPOCH: Date = Date { year: 1601, month: 1, day: 1 };
+    pub const MIN: Date = Date { year: MIN_YEAR, month: 1, day: 1 };
+    pub const MAX: Date = Date { year: MAX_YEAR, month: 12, day: 31 };
+
+    pub const fn new(year: i32, month: u8, day: u8) -> Result<Self, DateTimeError> {
+        if year < MIN_YEAR || year > MAX_YEAR || month < 1 || month > 12 {
+            return Err(DateTimeError::InvalidDate);
+        }
+
+        if day < 1 || day > Self::days_in_month(year, month) {
+            return Err(DateTimeError::InvalidDate);
+        }
+
+        Ok(Self { year, month, day })
+    }
+
+    pub const fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub const fn month(&self) -> u8 {
+        self.month
+    }
+
+    pub const fn day(&self) -> u8 {
+        self.day
+    }
+
+    pub const fn is_leap_year(year: i32) -> bool {
+        (year % 4 == 0 && year % 100 != 0) || (year % 400 == 0)
+    }
+
+    /// Zero for a month outside `1..=12`.
+    pub const fn days_in_month(year: i32, month: u8) -> u8 {
+        match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if Self::is_leap_year(year) => 29,
+            2 => 28,
+            _ => 0,
+        }
+    }
+
+    /// `civil_from_days`: shifts the year to start in March so the leap day
+    /// is last, then splits the day number into 400-year eras.
+    pub const fn from_days_since_epoch(days: i64) -> Result<Self, DateTimeError> {
+        if days < Self::MIN.days_since_epoch() || days > Self::MAX.days_since_epoch() {
+            return Err(DateTimeError::OutOfRange);
+        }
+
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + (month <= 2) as i64;
+
+        Ok(Self { year: year as i32, month: month as u8, day: day as u8 })
+    }
+
+    /// `days_from_civil`, the inverse of [`Date::from_days_since_epoch`].
+    pub const fn days_since_epoch(&self) -> i64 {
+        let month = self.month as i64;
+        let year = self.year as i64 - (month <= 2) as i64;
+        let era = year.div_euclid(400);
+        let yoe = year.rem_euclid(400);
+        let mp = if month > 2 { month - 3 } else { month + 9 };
+        let doy = (153 * mp + 2) / 5 + self.day as i64 - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+
+        era * 146_097 + doe - 719_468
+    }
+
+    pub const fn weekday(&self) -> Weekday {
+        match (self.days_since_epoch() + EPOCH_WEEKDAY).rem_euclid(7) {
+            0 => Weekday::Monday,
+            1 => Weekday::Tuesday,
+            2 => Weekday::Wednesday,
+            3 => Weekday::Thursday,
+            4 => Weekday::Friday,
+            5 => Weekday::Saturday,
+            _ => Weekday::Sunday,
+        }
+    }
+
+    /// Day of the year, starting at 1.
+    pub const fn ordinal(&self) -> u16 {
+        let jan_1 = Date { year: self.year, month: 1, day: 1 };
+        (self.days_since_epoch() - jan_1.days_since_epoch() + 1) as u16
+    }
+
+    pub const fn checked_add_days(self, days: i64) -> Option<Self> {
+        match self.days_since_epoch().checked_add(days) {
+            Some(days) => match Self::from_days_since_epoch(days) {
+                Ok(date) => Some(date),
+                Err(_) => None,
+            },
+            None => None,
+        }
+    }
+}
+
+/// Time of day with nanosecond precision. Leap seconds are not represented.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Time {
+    hour: u8,
+    minute: u8,
+    second: u8,
+    nanosecond: u32,
+}
+
+impl Time {
+    pub const MIDNIGHT: Time = Time { hour: 0, minute: 0, second: 0, nanosecond: 0 };
+
+    pub const fn new(hour: u8, minute: u8, second: u8, nanosecond: u32) -> Result<Self, DateTimeError> {
+        if hour > 23 || minute > 59 || second > 59 || nanosecond >= NANOS_PER_SEC as u32 {
+            return Err(DateTimeError::InvalidTime);
+        }
+
+        Ok(Self { hour, minute, second, nanosecond })
+    }
+
+    pub const fn from_seconds_of_day(seconds: u32, nanosecond: u32) -> Result<Self, DateTimeError> {
+        if seconds >= SECS_PER_DAY as u32 {
+            return Err(DateTimeError::InvalidTime);
+        }
+
+        Self::new((seconds / 3600) as u8, (seconds % 3600 / 60) as u8, (seconds % 60) as u8, nanosecond)
+    }
+
+    pub const fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    pub const fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    pub const fn second(&self) -> u8 {
+        self.second
+    }
+
+    pub const fn nanosecond(&self) -> u32 {
+        self.nanosecond
+    }
+
+    pub const fn millisecond(&self) -> u16 {
+        (self.nanosecond / 1_000_000) as u16
+    }
+
+    pub const fn seconds_of_day(&self) -> u32 {
+        self.hour as u32 * 3600 + self.minute as u32 * 60 + self.second as u32
+    }
+}
+
+/// Offset from UTC in whole minutes, strictly less than a day either way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UtcOffset {
+    minutes: i16,
+}
+
+impl UtcOffset {
+    pub const UTC: UtcOffset = UtcOffset { minutes: 0 };
+
+    pub const fn from_minutes(minutes: i32) -> Result<Self, DateTimeError> {
+        if minutes <= -24 * 60 || minutes >= 24 * 60 {
+            return Err(DateTimeError::InvalidOffset);
+        }
+
+        Ok(Self { minutes: minutes as i16 })
+    }
+
+    /// Windows stores the bias in 100ns ticks with `UTC = local + bias`, so
+    /// the offset is its negation.
+    pub const fn from_time_zone_bias(bias: i64) -> Result<Self, DateTimeError> {
+        const TICKS_PER_MINUTE: i64 = 60 * TICKS_PER_SEC as i64;
+
+        if bias % TICKS_PER_MINUTE != 0 || bias.unsigned_abs() >= 24 * 60 * TICKS_PER_MINUTE as u64 {
+            return Err(DateTimeError::InvalidOffset);
+        }
+
+        Self::from_minutes(-(bias / TICKS_PER_MINUTE) as i32)
+    }
+
+    /// The current zone from `KUSER_SHARED_DATA.TimeZoneBias`, which the
+    /// kernel updates when daylight saving time starts or ends.
+    #[cfg(windows)]
+    pub fn local() -> Self {
+        Self::from_time_zone_bias(sys::time::time_zone_bias()).unwrap_or(Self::UTC)
+    }
+
+    pub const fn minutes(&self) -> i32 {
+        self.minutes as i32
+    }
+
+    pub const fn seconds(&self) -> i64 {
+        self.minutes as i64 * 60
+    }
+
+    pub const fn is_utc(&self) -> bool {
+        self.minutes == 0
+    }
+}
+
+/// Date and time at a fixed UTC offset. Equality, ordering and hashing
+/// compare the instant, so `12:00Z` equals `14:00+02:00`.
+#[derive(Debug, Clone, Copy)]
+pub struct DateTime {
+    date: Date,
+    time: Time,
+    offset: UtcOffset,
+}
+
+impl DateTime {
+    pub const UNIX_EPOCH: DateTime = DateTime { date: Date::UNIX_EPOCH, time: Time::MIDNIGHT, offset: UtcOffset::UTC };
+
+    pub const fn new(date: Date, time: Time, offset: UtcOffset) -> Self {
+        Self { date, time, offset }
+    }
+
+    pub fn now_utc() -> Self {
+        Self::from_filetime(sys::time::system_time()).expect("system time out of range")
+    }
+
+    #[cfg(windows)]
+    pub fn now_local() -> Self {
+        Self::now_utc().to_offset(UtcOffset::local()).expect("system time out of range")
+    }
+
+    pub const fn date(&self) -> Date {
+        self.date
+    }
+
+    pub const fn time(&self) -> Time {
+        self.time
+    }
+
+    pub const fn offset(&self) -> UtcOffset {
+        self.offset
+    }
+
+    pub fn from_unix(seconds: i64, nanosecond: u32, offset: UtcOffset) -> Result<Self, DateTimeError> {
+        if nanosecond >= NANOS_PER_SEC as u32 {
+            return Err(DateTimeError::InvalidTime);
+        }
+
+        let local = seconds.checked_add(offset.seconds()).ok_or(DateTimeError::OutOfRange)?;
+        let date = Date::from_days_since_epoch(local.div_euclid(SECS_PER_DAY))?;
+        let time = Time::from_seconds_of_day(local.rem_euclid(SECS_PER_DAY) as u32, nanosecond)?;
+
+        Ok(Self { date, time, offset })
+    }
+
+    pub fn from_unix_seconds(seconds: i64) -> Result<Self, DateTimeError> {
+        Self::from_unix(seconds, 0, UtcOffset::UTC)
+    }
+
+    pub fn from_unix_nanos(nanos: i128, offset: UtcOffset) -> Result<Self, DateTimeError> {
+        let seconds = i64::try_from(nanos.div_euclid(NANOS_PER_SEC)).map_err(|_| DateTimeError::OutOfRange)?;
+        Self::from_unix(seconds, nanos.rem_euclid(NANOS_PER_SEC) as u32, offset)
+    }
+
+    /// FILETIME ticks (100ns intervals since 1601-01-01 UTC), as returned by
+    /// [`SystemTime::as_u64`](super::SystemTime::as_u64). The result is in UTC.
+    pub fn from_filetime(ticks: u64) -> Result<Self, DateTimeError> {
+        let seconds = (ticks / TICKS_PER_SEC) as i64 - WINDOWS_EPOCH_TO_UNIX;
+        let nanosecond = (ticks % TICKS_PER_SEC) as u32 * NANOS_PER_TICK;
+        Self::from_unix(seconds, nanosecond, UtcOffset::UTC)
+    }
+
+    /// Fails for instants before 1601. Sub-tick precision is truncated.
+    pub fn to_filetime(&self) -> Result<u64, DateTimeError> {
+        let seconds = self.unix_timestamp() + WINDOWS_EPOCH_TO_UNIX;
+        let seconds = u64::try_from(seconds).map_err(|_| DateTimeError::OutOfRange)?;
+        Ok(seconds * TICKS_PER_SEC + (self.time.nanosecond / NANOS_PER_TICK) as u64)
+    }
+
+    pub const fn unix_timestamp(&self) -> i64 {
+        self.date.days_since_epoch() * SECS_PER_DAY + self.time.seconds_of_day() as i64 - self.offset.seconds()
+    }
+
+    pub const fn unix_timestamp_nanos(&self) -> i128 {
+        self.unix_timestamp() as i128 * NANOS_PER_SEC + self.time.nanosecond as i128
+    }
+
+    /// The same instant seen from another offset.
+    pub fn to_offset(self, offset: UtcOffset) -> Result<Self, DateTimeError> {
+        Self::from_unix(self.unix_timestamp(), self.time.nanosecond, offset)
+    }
+
+    pub fn to_utc(self) -> Result<Self, DateTimeError> {
+        self.to_offset(UtcOffset::UTC)
+    }
+
+    pub fn checked_add(self, duration: Duration) -> Option<Self> {
+        let nanos = self.unix_timestamp_nanos().checked_add(duration.as_nanos().try_into().ok()?)?;
+        Self::from_unix_nanos(nanos, self.offset).ok()
+    }
+
+    pub fn checked_sub(self, duration: Duration) -> Option<Self> {
+        let nanos = self.unix_timestamp_nanos().checked_sub(duration.as_nanos().try_into().ok()?)?;
+        Self::from_unix_nanos(nanos, self.offset).ok()
+    }
+
+    /// `None` when `earlier` is later than `self`.
+    pub fn duration_since(&self, earlier: &DateTime) -> Option<Duration> {
+        let nanos = self.unix_timestamp_nanos() - earlier.unix_timestamp_nanos();
+        let nanos = u128::try_from(nanos).ok()?;
+        Some(Duration::new((nanos / NANOS_PER_SEC as u128) as u64, (nanos % NANOS_PER_SEC as u128) as u32))
+    }
+
+    /// Accepts `T`, `t` or a space between date and time, `Z` or `z` for UTC
+    /// and any number of fractional digits (beyond nine are truncated).
+    pub fn parse_rfc3339(input: &str) -> Result<Self, DateTimeError> {
+        let mut cursor = Cursor::new(input);
+        let date = cursor.date()?;
+        match cursor.next() {
+            Some(b'T' | b't' | b' ') => {}
+            _ => return Err(DateTimeError::Parse),
+        }
+        let time = cursor.time()?;
+        let offset = cursor.offset()?;
+        cursor.finish()?;
+
+        Ok(Self { date, time, offset })
+    }
+}
+
+impl PartialEq for DateTime {
+    fn eq(&self, other: &Self) -> bool {
+        self.unix_timestamp_nanos() == other.unix_timestamp_nanos()
+    }
+}
+
+impl Eq for DateTime {}
+
+impl PartialOrd for DateTime {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DateTime {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.unix_timestamp_nanos().cmp(&other.unix_timestamp_nanos())
+    }
+}
+
+impl Hash for DateTime {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.unix_timestamp_nanos().hash(state);
+    }
+}
+
+impl Add<Duration> for DateTime {
+    type Output = DateTime;
+
+    fn add(self, duration: Duration) -> DateTime {
+        self.checked_add(duration).expect("overflow when adding duration to date-time")
+    }
+}
+
+impl AddAssign<Duration> for DateTime {
+    fn add_assign(&mut self, duration: Duration) {
+        *self = *self + duration;
+    }
+}
+
+impl Sub<Duration> for DateTime {
+    type Output = DateTime;
+
+    fn sub(self, duration: Duration) -> DateTime {
+        self.checked_sub(duration).expect("overflow when subtracting duration from date-time")
+    }
+}
+
+impl SubAssign<Duration> for DateTime {
+    fn sub_assign(&mut self, duration: Duration) {
+        *self = *self - duration;
+    }
+}
+
+impl fmt::Display for Weekday {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if (0..=9999).contains(&self.year) {
+            write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+        } else {
+            write!(f, "{:+05}-{:02}-{:02}", self.year, self.month, self.day)
+        }
+    }
+}
+
+/// Prints as many fractional digits as needed: none, 3, 6 or 9.
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}:{:02}", self.hour, self.minute, self.second)?;
+
+        let nanos = self.nanosecond;
+        if nanos == 0 {
+            Ok(())
+        } else if nanos.is_multiple_of(1_000_000) {
+            write!(f, ".{:03}", nanos / 1_000_000)
+        } else if nanos.is_multiple_of(1_000) {
+            write!(f, ".{:06}", nanos / 1_000)
+        } else {
+            write!(f, ".{:09}", nanos)
+        }
+    }
+}
+
+impl fmt::Display for UtcOffset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.minutes == 0 {
+            return f.write_str("Z");
+        }
+
+        let sign = if self.minutes < 0 { '-' } else { '+' };
+        let minutes = self.minutes.unsigned_abs();
+        write!(f, "{}{:02}:{:02}", sign, minutes / 60, minutes % 60)
+    }
+}
+
+/// RFC 3339 `date-time`, e.g. `2024-02-29T13:45:00.250+01:00`.
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}T{}{}", self.date, self.time, self.offset)
+    }
+}
+
+impl FromStr for Date {
+    type Err = DateTimeError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut cursor = Cursor::new(input);
+        let date = cursor.date()?;
+        cursor.finish()?;
+        Ok(date)
+    }
+}
+
+impl FromStr for Time {
+    type Err = DateTimeError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut cursor = Cursor::new(input);
+        let time = cursor.time()?;
+        cursor.finish()?;
+        Ok(time)
+    }
+}
+
+impl FromStr for UtcOffset {
+    type Err = DateTimeError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut cursor = Cursor::new(input);
+        let offset = cursor.offset()?;
+        cursor.finish()?;
+        Ok(offset)
+    }
+}
+
+impl FromStr for DateTime {
+    type Err = DateTimeError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Self::parse_rfc3339(input)
+    }
+}
+
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(input: &'a str) -> Self {
+        Self { bytes: input.as_bytes(), pos: 0 }
+    }
+
+    fn next(&mut self) -> Option<u8> {
+        let byte = *self.bytes.get(self.pos)?;
+        self.pos += 1;
+        Some(byte)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, expected: u8) -> Result<(), DateTimeError> {
+        match self.next() {
+            Some(byte) if byte == expected => Ok(()),
+            _ => Err(DateTimeError::Parse),
+        }
+    }
+
+    fn digits(&mut self, count: usize) -> Result<u32, DateTimeError> {
+        let mut value = 0;
+
+        for _ in 0..count {
+            match self.next() {
+                Some(byte @ b'0'..=b'9') => value = value * 10 + (byte - b'0') as u32,
+                _ => return Err(DateTimeError::Parse),
+            }
+        }
+
+        Ok(value)
+    }
+
+    fn finish(&self) -> Result<(), DateTimeError> {
+        if self.pos == self.bytes.len() { Ok(()) } else { Err(DateTimeError::Parse) }
+    }
+
+    /// Takes the signed years that `Date`'s `Display` writes before year 0.
+    fn date(&mut self) -> Result<Date, DateTimeError> {
+        let negative = self.peek() == Some(b'-');
+        if matches!(self.peek(), Some(b'+' | b'-')) {
+            self.pos += 1;
+        }
+        let year = self.digits(4)? as i32;
+        let year = if negative { -year } else { year };
+        self.expect(b'-')?;
+        let month = self.digits(2)?;
+        self.expect(b'-')?;
+        let day = self.digits(2)?;
+
+        Date::new(year, month as u8, day as u8)
+    }
+
+    fn time(&mut self) -> Result<Time, DateTimeError> {
+        let hour = self.digits(2)?;
+        self.expect(b':')?;
+        let minute = self.digits(2)?;
+        self.expect(b':')?;
+        let second = self.digits(2)?;
+
+        let mut nanosecond = 0;
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            let mut scale = 100_000_000;
+            let start = self.pos;
+
+            while let Some(byte @ b'0'..=b'9') = self.peek() {
+                nanosecond += (byte - b'0') as u32 * scale;
+                scale /= 10;
+                self.pos += 1;
+            }
+
+            if self.pos == start {
+                return Err(DateTimeError::Parse);
+            }
+        }
+
+        Time::new(hour as u8, minute as u8, second as u8, nanosecond)
+    }
+
+    fn offset(&mut self) -> Result<UtcOffset, DateTimeError> {
+        let sign = match self.next() {
+            Some(b'Z' | b'z') => return Ok(UtcOffset::UTC),
+            Some(b'+') => 1,
+            Some(b'-') => -1,
+            _ => return Err(DateTimeError::Parse),
+        };
+
+        let hours = self.digits(2)?;
+        self.expect(b':')?;
+        let minutes = self.digits(2)?;
+        if minutes > 59 {
+            return Err(DateTimeError::InvalidOffset);
+        }
+
+        UtcOffset::from_minutes(sign * (hours * 60 + minutes) as i32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::time::SystemTime;
+    use std::string::ToString;
+
+    fn date(year: i32, month: u8, day: u8) -> Date {
+        Date::new(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn civil_from_days_round_trips() {
+        let first = Date::MIN.days_since_epoch();
+        let last = Date::MAX.days_since_epoch();
+        let mut previous = Date::MIN;
+        assert_eq!(Date::from_days_since_epoch(first), Ok(Date::MIN));
+
+        // Every day follows the one before it, across the whole range.
+        for days in first + 1..=last {
+            let current = Date::from_days_since_epoch(days).unwrap();
+            assert_eq!(current.days_since_epoch(), days);
+
+            let next_day = previous.day < Date::days_in_month(previous.year, previous.month);
+            let expected = if next_day {
+                Date { day: previous.day + 1, ..previous }
+            } else if previous.month < 12 {
+                Date { month: previous.month + 1, day: 1, ..previous }
+            } else {
+                Date { year: previous.year + 1, month: 1, day: 1 }
+            };
+            assert_eq!(current, expected);
+            previous = current;
+        }
+
+        assert_eq!(previous, Date::MAX);
+        assert_eq!(Date::from_days_since_epoch(first - 1), Err(DateTimeError::OutOfRange));
+        assert_eq!(Date::from_days_since_epoch(last + 1), Err(DateTimeError::OutOfRange));
+    }
+
+    #[test]
+    fn known_days() {
+        assert_eq!(Date::UNIX_EPOCH.days_since_epoch(), 0);
+        assert_eq!(date(2000, 3, 1).days_since_epoch(), 11_017);
+        assert_eq!(Date::FILETIME_EPOCH.days_since_epoch(), -WINDOWS_EPOCH_TO_UNIX / SECS_PER_DAY);
+        // Julian day 0.
+        assert_eq!(date(-4713, 11, 24).days_since_epoch(), -2_440_588);
+        // Year 0 is a leap year in the proleptic calendar.
+        assert_eq!(date(0, 3, 1).days_since_epoch() - date(0, 2, 28).days_since_epoch(), 2);
+        assert_eq!(date(-1, 12, 31).checked_add_days(1), Some(date(0, 1, 1)));
+
+        assert_eq!(Date::UNIX_EPOCH.weekday(), Weekday::Thursday);
+        assert_eq!(date(2024, 2, 29).weekday(), Weekday::Thursday);
+        assert_eq!(date(1, 1, 1).weekday(), Weekday::Monday);
+        assert_eq!(date(-1, 1, 1).weekday(), Weekday::Friday);
+        assert_eq!(date(2024, 12, 31).ordinal(), 366);
+        assert_eq!(Date::MAX.checked_add_days(1), None);
+    }
+
+    #[test]
+    fn leap_years() {
+        for (year, leap) in [(1900, false), (2000, true), (2023, false), (2024, true), (0, true), (-4, true), (-100, false), (-400, true)] {
+            assert_eq!(Date::is_leap_year(year), leap, "{}", year);
+        }
+
+        assert!(SystemTime::is_leap_year(2000));
+        assert!(!SystemTime::is_leap_year(2100));
+        assert!(SystemTime::is_leap_year(400_000_000_000_000_000));
+        assert!(!SystemTime::is_leap_year(100_000_000_000_000_100));
+    }
+
+    #[test]
+    fn rfc3339_round_trips() {
+        for input in [
+            "2024-02-29T13:45:00.250+01:00",
+            "1969-12-31T23:59:59.999999999Z",
+            "1970-01-01T00:00:00.000001-00:30",
+            "0000-01-01T00:00:00Z",
+            "1601-01-01T00:00:00Z",
+            "9999-12-31T23:59:59+23:59",
+            "-0001-01-01T00:00:00Z",
+            "-9999-01-01T12:00:00-01:00",
+        ] {
+            let parsed: DateTime = input.parse().unwrap();
+            assert_eq!(parsed.to_string(), input);
+            assert_eq!(parsed.to_string().parse::<DateTime>().unwrap(), parsed);
+        }
+
+        let relaxed = DateTime::parse_rfc3339("2024-02-29t13:45:00.1234567891z").unwrap();
+        assert_eq!(relaxed.to_string(), "2024-02-29T13:45:00.123456789Z");
+        assert_eq!(DateTime::parse_rfc3339("2024-02-29 13:45:00Z").unwrap().time(), Time::new(13, 45, 0, 0).unwrap());
+
+        // Equality compares the instant.
+        let utc: DateTime = "2024-06-01T12:00:00Z".parse().unwrap();
+        let plus_two: DateTime = "2024-06-01T14:00:00+02:00".parse().unwrap();
+        assert_eq!(utc, plus_two);
+        assert_eq!(plus_two.to_utc().unwrap().to_string(), "2024-06-01T12:00:00Z");
+        assert_eq!(utc.to_offset(UtcOffset::from_minutes(-330).unwrap()).unwrap().to_string(), "2024-06-01T06:30:00-05:30");
+    }
+
+    #[test]
+    fn rejects_invalid_dates() {
+        for (input, error) in [
+            ("2023-02-29T00:00:00Z", DateTimeError::InvalidDate),
+            ("2024-13-01T00:00:00Z", DateTimeError::InvalidDate),
+            ("2024-04-31T00:00:00Z", DateTimeError::InvalidDate),
+            ("2024-00-10T00:00:00Z", DateTimeError::InvalidDate),
+            ("2024-01-01T24:00:00Z", DateTimeError::InvalidTime),
+            ("2024-01-01T23:60:00Z", DateTimeError::InvalidTime),
+            ("2024-01-01T23:59:60Z", DateTimeError::InvalidTime),
+            ("2024-01-01T00:00:00+24:00", DateTimeError::InvalidOffset),
+            ("2024-01-01T00:00:00+01:60", DateTimeError::InvalidOffset),
+            ("2024-01-01T00:00:00+0100", DateTimeError::Parse),
+            ("2024-01-01T00:00:00", DateTimeError::Parse),
+            ("2024-01-01T00:00:00.Z", DateTimeError::Parse),
+            ("2024-01-01X00:00:00Z", DateTimeError::Parse),
+            ("2024-01-01T00:00:00Z ", DateTimeError::Parse),
+            ("24-01-01T00:00:00Z", DateTimeError::Parse),
+            ("--2024-01-01T00:00:00Z", DateTimeError::Parse),
+            ("+10000-01-01T00:00:00Z", DateTimeError::Parse),
+        ] {
+            assert_eq!(DateTime::parse_rfc3339(input), Err(error), "{}", input);
+        }
+
+        assert_eq!(Date::new(-10000, 1, 1), Err(DateTimeError::InvalidDate));
+        assert_eq!(Time::new(0, 0, 0, 1_000_000_000), Err(DateTimeError::InvalidTime));
+        assert_eq!("2024-02-30".parse::<Date>(), Err(DateTimeError::InvalidDate));
+    }
+
+    #[test]
+    fn rejects_invalid_offsets() {
+        assert_eq!(UtcOffset::from_minutes(24 * 60), Err(DateTimeError::InvalidOffset));
+        assert_eq!(UtcOffset::from_minutes(-24 * 60), Err(DateTimeError::InvalidOffset));
+        assert_eq!(UtcOffset::from_minutes(24 * 60 - 1).unwrap().to_string(), "+23:59");
+        assert_eq!("-00:00".parse::<UtcOffset>(), Ok(UtcOffset::UTC));
+
+        const MINUTE: i64 = 60 * TICKS_PER_SEC as i64;
+        assert_eq!(UtcOffset::from_time_zone_bias(-60 * MINUTE).unwrap().minutes(), 60);
+        assert_eq!(UtcOffset::from_time_zone_bias(330 * MINUTE).unwrap().minutes(), -330);
+        assert_eq!(UtcOffset::from_time_zone_bias(MINUTE + 1), Err(DateTimeError::InvalidOffset));
+        assert_eq!(UtcOffset::from_time_zone_bias(24 * 60 * MINUTE), Err(DateTimeError::InvalidOffset));
+    }
+
+    #[test]
+    fn filetime_epoch() {
+        const UNIX_EPOCH_TICKS: u64 = 116_444_736_000_000_000;
+
+        let epoch = DateTime::from_filetime(0).unwrap();
+        assert_eq!(epoch.date(), Date::FILETIME_EPOCH);
+        assert_eq!(epoch.time(), Time::MIDNIGHT);
+        assert_eq!(epoch.unix_timestamp(), -WINDOWS_EPOCH_TO_UNIX);
+        assert_eq!(epoch.to_filetime(), Ok(0));
+
+        assert_eq!(DateTime::from_filetime(UNIX_EPOCH_TICKS), Ok(DateTime::UNIX_EPOCH));
+        assert_eq!(DateTime::UNIX_EPOCH.to_filetime(), Ok(UNIX_EPOCH_TICKS));
+
+        let ticks = 133_500_000_001_234_567;
+        let moment = DateTime::from_filetime(ticks).unwrap();
+        assert_eq!(moment.time().nanosecond(), 123_456_700);
+        assert_eq!(moment.to_filetime(), Ok(ticks));
+
+        let before = DateTime::from_unix_seconds(-WINDOWS_EPOCH_TO_UNIX - 1).unwrap();
+        assert_eq!(before.to_filetime(), Err(DateTimeError::OutOfRange));
+
+        let time = SystemTime::from_ticks(UNIX_EPOCH_TICKS + 90 * TICKS_PER_SEC);
+        assert_eq!(time.to_unix_seconds(), 90);
+        assert_eq!(time.to_string(), "1970-01-01 00:01:30.000");
+        assert_eq!(time.to_datetime().unwrap().to_string(), "1970-01-01T00:01:30Z");
+        let offset = UtcOffset::from_minutes(-60).unwrap();
+        assert_eq!(time.to_local_datetime(offset).unwrap().to_string(), "1969-12-31T23:01:30-01:00");
+
+        // FILETIME reaches past the last representable date.
+        let last = DateTime::new(Date::MAX, Time::new(23, 59, 59, 0).unwrap(), UtcOffset::UTC);
+        let last = SystemTime::from_ticks(last.to_filetime().unwrap());
+        assert_eq!(last.to_datetime().unwrap().date(), Date::MAX);
+        assert_eq!(last.to_local_datetime(UtcOffset::from_minutes(60).unwrap()), Err(DateTimeError::OutOfRange));
+        assert_eq!(SystemTime::from_ticks(last.as_u64() + TICKS_PER_SEC).to_datetime(), Err(DateTimeError::OutOfRange));
+        assert_eq!(SystemTime::from_ticks(u64::MAX).to_datetime(), Err(DateTimeError::OutOfRange));
+        assert_eq!(SystemTime::from_ticks(u64::MAX).to_date_components().0, 9999);
+    }
+
+    #[test]
+    fn arithmetic() {
+        let start: DateTime = "2024-02-28T23:59:59.5+01:00".parse().unwrap();
+        let later = start + Duration::from_millis(1500);
+        assert_eq!(later.to_string(), "2024-02-29T00:00:01+01:00");
+        assert_eq!(later.duration_since(&start), Some(Duration::from_millis(1500)));
+        assert_eq!(start.duration_since(&later), None);
+        assert_eq!(later - Duration::from_millis(1500), start);
+
+        let last = DateTime::new(Date::MAX, Time::new(23, 59, 59, 999_999_999).unwrap(), UtcOffset::UTC);
+        assert_eq!(last.checked_add(Duration::from_nanos(1)), None);
+        assert_eq!(DateTime::from_unix_nanos(-1, UtcOffset::UTC).unwrap().to_string(), "1969-12-31T23:59:59.999999999Z");
+    }
+}
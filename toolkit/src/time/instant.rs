@@ -0,0 +1,208 @@
+use core::ops::{Add, AddAssign, Sub, SubAssign};
+use core::time::Duration;
+
+use crate::sys;
+
+const NANOS_PER_SEC: u64 = 1_000_000_000;
+
+/// Reading of the monotonic clock: QueryPerformanceCounter on Windows,
+/// `CLOCK_MONOTONIC` on Linux. Stored as nanoseconds since an unspecified
+/// start so `Duration` arithmetic is exact.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Hash)]
+pub struct Instant(Duration);
+
+impl Instant {
+    pub fn now() -> Self {
+        let ticks = sys::time::monotonic_ticks();
+        let frequency = sys::time::monotonic_frequency();
+        let nanos = ticks as u128 * NANOS_PER_SEC as u128 / frequency as u128;
+
+        Self(Duration::from_nanos_u128(nanos))
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        Self::now().saturating_duration_since(*self)
+    }
+
+    /// `None` if `earlier` is later than `self`.
+    pub fn checked_duration_since(&self, earlier: Instant) -> Option<Duration> {
+        self.0.checked_sub(earlier.0)
+    }
+
+    /// Zero if `earlier` is later than `self`.
+    pub fn saturating_duration_since(&self, earlier: Instant) -> Duration {
+        self.0.saturating_sub(earlier.0)
+    }
+
+    pub fn duration_since(&self, earlier: Instant) -> Duration {
+        self.saturating_duration_since(earlier)
+    }
+
+    pub fn checked_add(&self, duration: Duration) -> Option<Instant> {
+        self.0.checked_add(duration).map(Instant)
+    }
+
+    pub fn checked_sub(&self, duration: Duration) -> Option<Instant> {
+        self.0.checked_sub(duration).map(Instant)
+    }
+}
+
+impl Add<Duration> for Instant {
+    type Output = Instant;
+
+    fn add(self, other: Duration) -> Instant {
+        self.checked_add(other).expect("overflow when adding duration to instant")
+    }
+}
+
+impl AddAssign<Duration> for Instant {
+    fn add_assign(&mut self, other: Duration) {
+        *self = *self + other;
+    }
+}
+
+impl Sub<Duration> for Instant {
+    type Output = Instant;
+
+    fn sub(self, other: Duration) -> Instant {
+        self.checked_sub(other).expect("overflow when subtracting duration from instant")
+    }
+}
+
+impl SubAssign<Duration> for Instant {
+    fn sub_assign(&mut self, other: Duration) {
+        *self = *self - other;
+    }
+}
+
+/// Saturates to zero, like `std::time::Instant`.
+impl Sub<Instant> for Instant {
+    type Output = Duration;
+
+    fn sub(self, other: Instant) -> Duration {
+        self.saturating_duration_since(other)
+    }
+}
+
+/// Point on the monotonic clock to give up at, or never.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub struct Deadline(Option<Instant>);
+
+impl Deadline {
+    pub const NEVER: Deadline = Deadline(None);
+
+    pub const fn at(instant: Instant) -> Self {
+        Self(Some(instant))
+    }
+
+    /// A timeout too large to represent never expires.
+    pub fn after(timeout: Duration) -> Self {
+        Self(Instant::now().checked_add(timeout))
+    }
+
+    pub const fn instant(&self) -> Option<Instant> {
+        self.0
+    }
+
+    pub fn has_expired(&self) -> bool {
+        self.0.is_some_and(|at| Instant::now() >= at)
+    }
+
+    /// `None` for [`Deadline::NEVER`], zero once expired.
+    pub fn remaining(&self) -> Option<Duration> {
+        self.0.map(|at| at.saturating_duration_since(Instant::now()))
+    }
+}
+
+impl From<Instant> for Deadline {
+    fn from(instant: Instant) -> Self {
+        Self::at(instant)
+    }
+}
+
+impl From<Option<Duration>> for Deadline {
+    fn from(timeout: Option<Duration>) -> Self {
+        timeout.map_or(Self::NEVER, Self::after)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+
+    fn at(secs: u64) -> Instant {
+        Instant(Duration::from_secs(secs))
+    }
+
+    #[test]
+    fn arithmetic() {
+        let mut a = at(10);
+        assert_eq!(a + Duration::from_millis(1500), Instant(Duration::from_millis(11_500)));
+        assert_eq!(a - Duration::from_secs(4), at(6));
+        assert_eq!(at(12) - a, Duration::from_secs(2));
+        assert_eq!(at(12).checked_duration_since(a), Some(Duration::from_secs(2)));
+        assert_eq!(at(12).duration_since(a), Duration::from_secs(2));
+
+        a += Duration::from_secs(5);
+        assert_eq!(a, at(15));
+        a -= Duration::from_secs(15);
+        assert_eq!(a, at(0));
+    }
+
+    #[test]
+    fn saturation() {
+        assert_eq!(at(3) - at(5), Duration::ZERO);
+        assert_eq!(at(3).saturating_duration_since(at(5)), Duration::ZERO);
+        assert_eq!(at(3).duration_since(at(5)), Duration::ZERO);
+        assert_eq!(at(3).checked_duration_since(at(5)), None);
+
+        assert_eq!(at(3).checked_sub(Duration::from_secs(4)), None);
+        assert_eq!(at(3).checked_sub(Duration::from_secs(3)), Some(at(0)));
+        assert_eq!(at(1).checked_add(Duration::MAX), None);
+        assert_eq!(at(0).checked_add(Duration::MAX), Some(Instant(Duration::MAX)));
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow when adding duration to instant")]
+    fn add_overflow_panics() {
+        let _ = at(1) + Duration::MAX;
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow when subtracting duration from instant")]
+    fn sub_overflow_panics() {
+        let _ = at(1) - Duration::from_secs(2);
+    }
+
+    #[test]
+    fn monotonic() {
+        let a = Instant::now();
+        std::thread::sleep(Duration::from_millis(10));
+        let b = Instant::now();
+
+        assert!(b > a);
+        assert!(b - a >= Duration::from_millis(10));
+        assert!(a.elapsed() >= b - a);
+    }
+
+    #[test]
+    fn deadline() {
+        assert_eq!(Deadline::NEVER.remaining(), None);
+        assert!(!Deadline::NEVER.has_expired());
+        assert_eq!(Deadline::from(None::<Duration>), Deadline::NEVER);
+        // Too far out to represent, so it never expires.
+        assert_eq!(Deadline::after(Duration::MAX), Deadline::NEVER);
+
+        let past = Deadline::from(at(0));
+        assert_eq!(past.instant(), Some(at(0)));
+        assert!(past.has_expired());
+        assert_eq!(past.remaining(), Some(Duration::ZERO));
+
+        let soon = Deadline::from(Some(Duration::from_secs(60)));
+        assert!(!soon.has_expired());
+        let remaining = soon.remaining().unwrap();
+        assert!(remaining > Duration::from_secs(59) && remaining <= Duration::from_secs(60));
+    }
+}
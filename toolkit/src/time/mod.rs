@@ -0,0 +1,103 @@
+use core::fmt;
+use core::fmt::Write;
+
+use crate::{DateTimeError, sys};
+
+mod datetime;
+mod instant;
+
+pub use datetime::*;
+pub use instant::*;
+
+pub struct Sleeper;
+
+impl Sleeper {
+    pub fn sleep(milliseconds: u32) {
+        sys::time::sleep(milliseconds);
+    }
+}
+
+/// Wall-clock time stored as FILETIME ticks (100ns intervals since 1601-01-01 UTC).
+pub struct SystemTime(u64);
+
+impl SystemTime {
+    pub fn now() -> Self {
+        Self(sys::time::system_time())
+    }
+
+    pub const fn from_ticks(ticks: u64) -> Self {
+        Self(ticks)
+    }
+
+    pub const fn as_u64(&self) -> u64 {
+        self.0
+    }
+
+    pub const fn to_seconds(&self) -> u64 {
+        self.as_u64() / 10_000_000
+    }
+
+    pub const fn to_millis(&self) -> u64 {
+        self.as_u64() / 10_000
+    }
+
+    pub const fn to_unix_seconds(&self) -> u64 {
+        self.to_seconds() - WINDOWS_EPOCH_TO_UNIX as u64
+    }
+
+    /// Fails with [`DateTimeError::OutOfRange`] past 9999-12-31, which FILETIME can still hold.
+    pub fn to_datetime(&self) -> Result<DateTime, DateTimeError> {
+        DateTime::from_filetime(self.as_u64())
+    }
+
+    pub fn to_local_datetime(&self, offset: UtcOffset) -> Result<DateTime, DateTimeError> {
+        self.to_datetime()?.to_offset(offset)
+    }
+
+    pub const fn to_date_components(&self) -> (u16, u8, u8, u8, u8, u8, u32) {
+        let secs = self.to_seconds() as i64 - WINDOWS_EPOCH_TO_UNIX;
+        let secs_in_day = secs.rem_euclid(86400);
+
+        let hour = (secs_in_day / 3600) as u8;
+        let minute = ((secs_in_day % 3600) / 60) as u8;
+        let second = (secs_in_day % 60) as u8;
+        let millis = (self.as_u64() % 10_000_000) / 10_000;
+
+        let date = match Date::from_days_since_epoch(secs.div_euclid(86400)) {
+            Ok(date) => date,
+            Err(_) => Date::MAX,
+        };
+        (date.year() as u16, date.month(), date.day(), hour, minute, second, millis as u32)
+    }
+
+    pub fn to_hh_mm_ss(&self) -> heapless::String<8> {
+        let (_, _, _, hour, minute, second, _) = self.to_date_components();
+        let mut s = heapless::String::new();
+        let _ = write!(&mut s, "{:02}{:02}{:02}", hour, minute, second);
+        s
+    }
+
+    /// Days since 1970-01-01 to `(year, month, day)`.
+    pub const fn days_to_date(days: u64) -> (u16, u8, u8) {
+        match Date::from_days_since_epoch(days as i64) {
+            Ok(date) => (date.year() as u16, date.month(), date.day()),
+            Err(_) => (Date::MAX.year() as u16, Date::MAX.month(), Date::MAX.day()),
+        }
+    }
+
+    pub const fn is_leap_year(year: u64) -> bool {
+        // The leap year rule repeats every 400 years.
+        Date::is_leap_year((year % 400) as i32)
+    }
+}
+
+impl fmt::Display for SystemTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (year, month, day, hour, minute, second, millis) = self.to_date_components();
+        write!(
+            f,
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}.{:03}",
+            year, month, day, hour, minute, second, millis
+        )
+    }
+}
\ No newline at end of file
@@ -1,10 +1,32 @@
 mod byte_block; 
+pub(crate) mod text;
+#[cfg(windows)]
 mod heap;
 
 pub use byte_block::*;
+#[cfg(windows)]
 pub use heap::*;
+#[cfg(windows)]
 use winapi::shared::ntdef::UNICODE_STRING;
 
+#[cfg(windows)]
 pub trait ToUnicode {
     fn as_unicode(&self) -> UNICODE_STRING;
-}
\ No newline at end of file
+}
+
+/// Longest path, including the nul terminator, that Linux syscalls accept.
+#[cfg(target_os = "linux")]
+pub const PATH_MAX: usize = 4096;
+
+#[cfg(target_os = "linux")]
+pub trait ToPath {
+    /// Returns the path as nul-terminated UTF-8, or `None` if it does not fit.
+    fn as_path(&self) -> Option<crate::U8CStackString<PATH_MAX>>;
+}
+
+#[cfg(target_os = "linux")]
+impl ToPath for &str {
+    fn as_path(&self) -> Option<crate::U8CStackString<PATH_MAX>> {
+        crate::U8CStackString::from_str(self)
+    }
+}
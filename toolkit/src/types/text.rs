@@ -0,0 +1,237 @@
+//! Search and case folding shared by the stack strings.
+
+/// Two-way (Crochemore-Perrin) search: linear time and no scratch space, so
+/// a periodic needle like `aaab` in `aaaa…` does not go quadratic.
+pub(crate) fn find<T: Copy + Ord>(haystack: &[T], needle: &[T]) -> Option<usize> {
+    two_way(haystack.len(), |i| haystack[i], needle.len(), |i| needle[i])
+}
+
+/// [`find`] over both slices reversed.
+pub(crate) fn rfind<T: Copy + Ord>(haystack: &[T], needle: &[T]) -> Option<usize> {
+    let (h, n) = (haystack.len(), needle.len());
+    let position = two_way(h, |i| haystack[h - 1 - i], n, |i| needle[n - 1 - i])?;
+    Some(h - position - n)
+}
+
+/// Units are read through `haystack` and `needle` so [`rfind`] can run the
+/// same search over reversed slices.
+fn two_way<T: Copy + Ord>(
+    haystack_len: usize,
+    haystack: impl Fn(usize) -> T,
+    needle_len: usize,
+    needle: impl Fn(usize) -> T,
+) -> Option<usize> {
+    if needle_len == 0 {
+        return Some(0);
+    }
+
+    // Critical factorization: the later of the two maximal suffixes.
+    let (crit_less, period_less) = maximal_suffix(needle_len, &needle, false);
+    let (crit_greater, period_greater) = maximal_suffix(needle_len, &needle, true);
+    let (crit_pos, period) = if crit_less > crit_greater {
+        (crit_less, period_less)
+    } else {
+        (crit_greater, period_greater)
+    };
+
+    // A needle with period `period` can keep what the last attempt matched
+    // of its prefix; otherwise any shift past the critical position is safe.
+    let periodic = (0..crit_pos).all(|i| needle(i) == needle(i + period));
+    let period = if periodic { period } else { crit_pos.max(needle_len - crit_pos) + 1 };
+
+    let mut position = 0;
+    let mut memory = 0;
+
+    'search: while position + needle_len <= haystack_len {
+        let start = if periodic { crit_pos.max(memory) } else { crit_pos };
+        for i in start..needle_len {
+            if needle(i) != haystack(position + i) {
+                position += i - crit_pos + 1;
+                memory = 0;
+                continue 'search;
+            }
+        }
+
+        let start = if periodic { memory } else { 0 };
+        for i in (start..crit_pos).rev() {
+            if needle(i) != haystack(position + i) {
+                position += period;
+                if periodic {
+                    memory = needle_len - period;
+                }
+                continue 'search;
+            }
+        }
+
+        return Some(position);
+    }
+
+    None
+}
+
+/// Start and period of the lexicographically largest suffix, under the
+/// reversed order when `reversed` is set.
+fn maximal_suffix<T: Copy + Ord>(len: usize, needle: &impl Fn(usize) -> T, reversed: bool) -> (usize, usize) {
+    let mut left = 0;
+    let mut right = 1;
+    let mut offset = 0;
+    let mut period = 1;
+
+    while right + offset < len {
+        let a = needle(right + offset);
+        let b = needle(left + offset);
+
+        if (a < b && !reversed) || (a > b && reversed) {
+            right += offset + 1;
+            offset = 0;
+            period = right - left;
+        } else if a == b {
+            if offset + 1 == period {
+                right += offset + 1;
+                offset = 0;
+            } else {
+                offset += 1;
+            }
+        } else {
+            left = right;
+            right += 1;
+            offset = 0;
+            period = 1;
+        }
+    }
+
+    (left, period)
+}
+
+/// Simple (one to one) case folding: `lower(upper(c))` wherever both mappings
+/// are a single char, so `ς`, `σ` and `Σ` fold together while `ß` stays itself.
+pub(crate) fn fold_case(c: char) -> char {
+    let upper = single(c.to_uppercase()).unwrap_or(c);
+    single(upper.to_lowercase()).unwrap_or(upper)
+}
+
+pub(crate) fn eq_ignore_case(a: impl Iterator<Item = char>, b: impl Iterator<Item = char>) -> bool {
+    a.map(fold_case).eq(b.map(fold_case))
+}
+
+fn single(mut chars: impl Iterator<Item = char>) -> Option<char> {
+    let c = chars.next()?;
+    chars.next().is_none().then_some(c)
+}
+
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::U16CStackString;
+    use std::string::String;
+
+    #[test]
+    fn slice_search() {
+        assert_eq!(find(b"abcabc", b"bc"), Some(1));
+        assert_eq!(rfind(b"abcabc", b"bc"), Some(4));
+        assert_eq!(find(b"abcabc", b""), Some(0));
+        assert_eq!(rfind(b"abcabc", b""), Some(6));
+        assert_eq!(find(b"aab", b"ab"), Some(1));
+        assert_eq!(rfind(b"abb", b"ab"), Some(0));
+        assert_eq!(find(b"ab", b"abc"), None);
+        assert_eq!(rfind(b"abcabc", b"cb"), None);
+    }
+
+    fn naive(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+        (0..=haystack.len().checked_sub(needle.len())?).find(|&i| haystack[i..].starts_with(needle))
+    }
+
+    fn naive_rev(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+        (0..=haystack.len().checked_sub(needle.len())?).rev().find(|&i| haystack[i..].starts_with(needle))
+    }
+
+    /// Every string over `alphabet` of each length up to `max_len`.
+    fn words(alphabet: &[u8], max_len: usize) -> std::vec::Vec<std::vec::Vec<u8>> {
+        let mut words = std::vec![std::vec![]];
+        let mut last = words.clone();
+        for _ in 0..max_len {
+            last = last
+                .iter()
+                .flat_map(|word| alphabet.iter().map(move |&unit| [word.as_slice(), &[unit]].concat()))
+                .collect();
+            words.extend(last.iter().cloned());
+        }
+        words
+    }
+
+    #[test]
+    fn two_way_matches_naive_search() {
+        let haystacks = words(b"ab", 9);
+        let needles = words(b"ab", 5);
+
+        for haystack in &haystacks {
+            for needle in &needles {
+                assert_eq!(find(haystack, needle), naive(haystack, needle), "{haystack:?} {needle:?}");
+                assert_eq!(rfind(haystack, needle), naive_rev(haystack, needle), "{haystack:?} {needle:?}");
+            }
+        }
+
+        for haystack in &words(b"abc", 6) {
+            for needle in [&b"abc"[..], b"cab", b"aca", b"bcb", b"cc", b"acab"] {
+                assert_eq!(find(haystack, needle), naive(haystack, needle), "{haystack:?} {needle:?}");
+                assert_eq!(rfind(haystack, needle), naive_rev(haystack, needle), "{haystack:?} {needle:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn periodic_needles() {
+        let mut haystack = [b'a'; 4096];
+        assert_eq!(find(&haystack, &[b'a'; 100]), Some(0));
+        assert_eq!(rfind(&haystack, &[b'a'; 100]), Some(3996));
+        assert_eq!(find(&haystack, b"aaaaaaaab"), None);
+        assert_eq!(rfind(&haystack, b"baaaaaaaa"), None);
+
+        haystack[4000] = b'b';
+        assert_eq!(find(&haystack, b"aaaaaaaab"), Some(3992));
+        assert_eq!(rfind(&haystack, b"baaaaaaaa"), Some(4000));
+        assert_eq!(find(&haystack, b"abaa"), Some(3999));
+
+        let units: std::vec::Vec<u16> = "ababababc abababab".encode_utf16().collect();
+        let needle: std::vec::Vec<u16> = "abababab".encode_utf16().collect();
+        assert_eq!(find(&units, &needle), Some(0));
+        assert_eq!(rfind(&units, &needle), Some(10));
+    }
+
+    #[test]
+    fn case_folding() {
+        assert_eq!(fold_case('Σ'), 'σ');
+        assert_eq!(fold_case('ς'), 'σ');
+        assert_eq!(fold_case('ß'), 'ß');
+        assert_eq!(fold_case('İ'), 'İ');
+        assert!(eq_ignore_case("ŻÓŁW".chars(), "żółw".chars()));
+        assert!(!eq_ignore_case("żółw".chars(), "żółwie".chars()));
+        assert!(!eq_ignore_case("STRASSE".chars(), "straße".chars()));
+    }
+
+    #[test]
+    fn long_utf16_patterns() {
+        let mut text = String::new();
+        for _ in 0..50 {
+            text.push_str("żółw ");
+        }
+        let pattern = &text[..text.len() - 1];
+        let mut haystack = String::from("> ");
+        haystack.push_str(&text);
+
+        let string = U16CStackString::<512>::from_str(&haystack).unwrap();
+        assert!(pattern.encode_utf16().count() > 128);
+        assert_eq!(string.find(pattern), Some(2));
+        assert_eq!(string.rfind(pattern), Some(2));
+        assert!(string.contains(pattern));
+
+        let (before, after) = string.split_once(pattern).unwrap();
+        assert_eq!(before.chars().collect::<String>(), "> ");
+        assert_eq!(after.chars().collect::<String>(), " ");
+        assert_eq!(string.replace(pattern, "x").unwrap().chars().collect::<String>(), "> x ");
+        assert_eq!(string.split(pattern).count(), 2);
+    }
+}
@@ -1,22 +1,41 @@
 use core::fmt;
 
+#[cfg(windows)]
 use winapi::shared::ntdef::UNICODE_STRING;
 
-use crate::{U8CStackString, println, types::ToUnicode};
-
-const MAX_PAT_LEN: usize = 128;
+use crate::U8CStackString;
+use crate::types::text;
+#[cfg(windows)]
+use crate::types::ToUnicode;
+#[cfg(target_os = "linux")]
+use crate::types::{PATH_MAX, ToPath};
 
 pub struct U16CStackString<const N: usize> {
     buf: [u16; N],
     len: usize,
 }
 
+#[cfg(windows)]
 impl<const N: usize> ToUnicode for U16CStackString<N> {
     fn as_unicode(&self) -> UNICODE_STRING {
         self.to_unicode_string()
     }
 }
 
+#[cfg(target_os = "linux")]
+impl<const N: usize> ToPath for U16CStackString<N> {
+    fn as_path(&self) -> Option<U8CStackString<PATH_MAX>> {
+        let mut path = U8CStackString::new();
+        for ch in char::decode_utf16(self.as_slice().iter().copied()) {
+            let mut buf = [0u8; 4];
+            if !path.push_str(ch.ok()?.encode_utf8(&mut buf)) {
+                return None;
+            }
+        }
+        Some(path)
+    }
+}
+
 impl<const N: usize> Clone for U16CStackString<N> {
     fn clone(&self) -> Self {
         let mut buf = [0u16; N];
@@ -304,39 +323,7 @@ impl<const N: usize> U16CStackString<N> {
             return false;
         }
 
-        let mut pat_utf16 = [0u16; MAX_PAT_LEN];
-        let mut pat_len = 0;
-        
-        for ch in pat.encode_utf16() {
-            if pat_len >= MAX_PAT_LEN {
-                return false;
-            }
-            pat_utf16[pat_len] = ch;
-            pat_len += 1;
-        }
-        
-        if pat_len == 0 {
-            return false;
-        }
-
-        let slice = self.as_slice();
-        if slice.len() < pat_len {
-            return false;
-        }
-
-        for i in 0..=slice.len() - pat_len {
-            let mut found = true;
-            for j in 0..pat_len {
-                if slice[i + j] != pat_utf16[j] {
-                    found = false;
-                    break;
-                }
-            }
-            if found {
-                return true;
-            }
-        }
-        false
+        self.find(pat).is_some()
     }
 
     pub fn contains_str(&self, pat: &str) -> bool {
@@ -348,30 +335,14 @@ impl<const N: usize> U16CStackString<N> {
             return false;
         }
 
-        let slice = self.as_slice();
-        if slice.len() < pat.len() {
-            return false;
-        }
-
-        for i in 0..=slice.len() - pat.len() {
-            let mut found = true;
-            for j in 0..pat.len() {
-                if slice[i + j] != pat[j] {
-                    found = false;
-                    break;
-                }
-            }
-            if found {
-                return true;
-            }
-        }
-        false
+        text::find(self.as_slice(), pat).is_some()
     }
 
     pub fn contains_u16(&self, ch: u16) -> bool {
         self.as_slice().contains(&ch)
     }
 
+    #[cfg(windows)]
     pub fn to_unicode_string(&self) -> UNICODE_STRING {
         let len = (self.len * 2) as u16;
         UNICODE_STRING {
@@ -456,3 +427,297 @@ impl<const N: usize> U16CStackString<N> {
         Some(result)
     }
 }
+
+fn is_whitespace_unit(unit: u16) -> bool {
+    char::from_u32(unit as u32).is_some_and(char::is_whitespace)
+}
+
+impl<const N: usize> U16CStackString<N> {
+    /// Copies a slice already known to fit, e.g. a piece of another `Self`.
+    fn from_units(units: &[u16]) -> Self {
+        let mut buf = [0u16; N];
+        buf[..units.len()].copy_from_slice(units);
+        Self { buf, len: units.len() }
+    }
+
+    /// Decodes surrogate pairs; unpaired surrogates become U+FFFD.
+    pub fn chars(&self) -> impl Iterator<Item = char> + '_ {
+        char::decode_utf16(self.as_slice().iter().copied()).map(|ch| ch.unwrap_or(char::REPLACEMENT_CHARACTER))
+    }
+
+    pub fn push_char(&mut self, ch: char) -> bool {
+        let mut units = [0u16; 2];
+        let units = ch.encode_utf16(&mut units);
+
+        if self.len + units.len() >= N {
+            return false;
+        }
+
+        self.buf[self.len..self.len + units.len()].copy_from_slice(units);
+        self.len += units.len();
+        self.buf[self.len] = 0;
+        true
+    }
+
+    /// Index in UTF-16 units of the first match.
+    pub fn find(&self, pat: &str) -> Option<usize> {
+        text::find(self.as_slice(), Self::pattern(pat)?.as_slice())
+    }
+
+    pub fn rfind(&self, pat: &str) -> Option<usize> {
+        text::rfind(self.as_slice(), Self::pattern(pat)?.as_slice())
+    }
+
+    /// `pat` encoded to UTF-16. `None` means it is longer than any contents
+    /// and so cannot match.
+    fn pattern(pat: &str) -> Option<Self> {
+        Self::from_str(pat)
+    }
+
+    pub fn find_u16_slice(&self, pat: &[u16]) -> Option<usize> {
+        text::find(self.as_slice(), pat)
+    }
+
+    pub fn rfind_u16_slice(&self, pat: &[u16]) -> Option<usize> {
+        text::rfind(self.as_slice(), pat)
+    }
+
+    /// An empty separator yields the whole string once.
+    pub fn split(&self, sep: &str) -> U16Split<'_, N> {
+        U16Split {
+            rest: Some(self.as_slice()),
+            sep: Self::pattern(sep).filter(|sep| !sep.is_empty()),
+        }
+    }
+
+    pub fn split_once(&self, sep: &str) -> Option<(Self, Self)> {
+        let slice = self.as_slice();
+        let sep = Self::pattern(sep)?;
+        let index = text::find(slice, sep.as_slice())?;
+        let end = index + sep.len();
+        Some((Self::from_units(&slice[..index]), Self::from_units(&slice[end..])))
+    }
+
+    pub fn rsplit_once(&self, sep: &str) -> Option<(Self, Self)> {
+        let slice = self.as_slice();
+        let sep = Self::pattern(sep)?;
+        let index = text::rfind(slice, sep.as_slice())?;
+        let end = index + sep.len();
+        Some((Self::from_units(&slice[..index]), Self::from_units(&slice[end..])))
+    }
+
+    pub fn trim(&self) -> Self {
+        let slice = self.as_slice();
+        let start = slice.iter().position(|&unit| !is_whitespace_unit(unit)).unwrap_or(slice.len());
+        let end = slice.iter().rposition(|&unit| !is_whitespace_unit(unit)).map_or(start, |index| index + 1);
+        Self::from_units(&slice[start..end])
+    }
+
+    pub fn trim_start(&self) -> Self {
+        let slice = self.as_slice();
+        let start = slice.iter().position(|&unit| !is_whitespace_unit(unit)).unwrap_or(slice.len());
+        Self::from_units(&slice[start..])
+    }
+
+    pub fn trim_end(&self) -> Self {
+        let slice = self.as_slice();
+        let end = slice.iter().rposition(|&unit| !is_whitespace_unit(unit)).map_or(0, |index| index + 1);
+        Self::from_units(&slice[..end])
+    }
+
+    /// `None` if the result does not fit. An empty `from` returns a copy.
+    pub fn replace(&self, from: &str, to: &str) -> Option<Self> {
+        let Some(from) = Self::pattern(from).filter(|from| !from.is_empty()) else {
+            return Some(self.clone());
+        };
+
+        let mut rest = self.as_slice();
+        let mut result = Self::new();
+
+        while let Some(index) = text::find(rest, from.as_slice()) {
+            if !result.push_units(&rest[..index]) || !result.push_str(to) {
+                return None;
+            }
+            rest = &rest[index + from.len()..];
+        }
+
+        result.push_units(rest).then_some(result)
+    }
+
+    fn push_units(&mut self, units: &[u16]) -> bool {
+        if self.len + units.len() >= N {
+            return false;
+        }
+
+        self.buf[self.len..self.len + units.len()].copy_from_slice(units);
+        self.len += units.len();
+        self.buf[self.len] = 0;
+        true
+    }
+
+    /// Full Unicode mapping, so the result can be longer; `None` if it does not fit.
+    pub fn to_unicode_lowercase(&self) -> Option<Self> {
+        self.map_chars(char::to_lowercase)
+    }
+
+    pub fn to_unicode_uppercase(&self) -> Option<Self> {
+        self.map_chars(char::to_uppercase)
+    }
+
+    /// Unpaired surrogates are copied through unchanged.
+    fn map_chars<I: Iterator<Item = char>>(&self, map: impl Fn(char) -> I) -> Option<Self> {
+        let mut result = Self::new();
+
+        for ch in char::decode_utf16(self.as_slice().iter().copied()) {
+            let pushed = match ch {
+                Ok(ch) => map(ch).all(|mapped| result.push_char(mapped)),
+                Err(err) => result.push(err.unpaired_surrogate()),
+            };
+
+            if !pushed {
+                return None;
+            }
+        }
+
+        Some(result)
+    }
+
+    /// Compares under simple Unicode case folding, e.g. `"ŻÓŁW.EXE"` equals `"żółw.exe"`.
+    pub fn eq_ignore_case(&self, other: &str) -> bool {
+        text::eq_ignore_case(self.chars(), other.chars())
+    }
+
+    pub fn eq_ignore_case_u16(&self, other: &[u16]) -> bool {
+        let other = char::decode_utf16(other.iter().copied()).map(|ch| ch.unwrap_or(char::REPLACEMENT_CHARACTER));
+        text::eq_ignore_case(self.chars(), other)
+    }
+}
+
+/// Pieces of a [`U16CStackString`] between separators, see [`U16CStackString::split`].
+pub struct U16Split<'a, const N: usize> {
+    rest: Option<&'a [u16]>,
+    /// `None` for an empty separator or one too long to ever match.
+    sep: Option<U16CStackString<N>>,
+}
+
+impl<const N: usize> Iterator for U16Split<'_, N> {
+    type Item = U16CStackString<N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = self.rest?;
+        let found = self.sep.as_ref().and_then(|sep| Some((text::find(rest, sep.as_slice())?, sep.len())));
+
+        match found {
+            Some((index, sep_len)) => {
+                self.rest = Some(&rest[index + sep_len..]);
+                Some(U16CStackString::from_units(&rest[..index]))
+            }
+            None => {
+                self.rest = None;
+                Some(U16CStackString::from_units(rest))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::string::String;
+
+    type S = U16CStackString<16>;
+
+    fn string<const N: usize>(s: &U16CStackString<N>) -> String {
+        s.chars().collect()
+    }
+
+    #[test]
+    fn surrogate_pairs() {
+        let s = S::from_str("a😀b").unwrap();
+        assert_eq!(s.len(), 4);
+        assert_eq!(s.chars().collect::<std::vec::Vec<_>>(), ['a', '😀', 'b']);
+
+        let unpaired = S::from_u16_slice(&[0x61, 0xD83D, 0x62, 0xDE00]).unwrap();
+        assert_eq!(string(&unpaired), "a\u{FFFD}b\u{FFFD}");
+        assert_eq!(string(&unpaired.to_unicode_uppercase().unwrap()), "A\u{FFFD}B\u{FFFD}");
+        assert_eq!(unpaired.to_unicode_uppercase().unwrap().as_slice(), [0x41, 0xD83D, 0x42, 0xDE00]);
+    }
+
+    #[test]
+    fn push_char_overflow() {
+        let mut s = U16CStackString::<4>::new();
+        assert!(s.push_char('a'));
+        assert!(s.push_char('b'));
+        // Needs two units with room for one.
+        assert!(!s.push_char('😀'));
+        assert_eq!(s.len(), 2);
+        assert!(s.push_char('ż'));
+        assert!(!s.push_char('c'));
+        assert_eq!(string(&s), "abż");
+    }
+
+    #[test]
+    fn search() {
+        let s = S::from_str("łódź/łódź").unwrap();
+        assert_eq!(s.find("ódź"), Some(1));
+        assert_eq!(s.rfind("ódź"), Some(6));
+        assert_eq!(s.find(""), Some(0));
+        assert_eq!(s.rfind(""), Some(9));
+        assert_eq!(s.find("x"), None);
+        // Longer than the capacity, so it cannot match.
+        assert_eq!(s.find("łódź/łódź/łódź/łódź"), None);
+        assert!(s.contains("ź/ł"));
+        assert!(!s.contains(""));
+    }
+
+    #[test]
+    fn split_and_trim() {
+        let s = S::from_str(" a,ż,,c ").unwrap();
+        let pieces: std::vec::Vec<String> = s.split(",").map(|piece| string(&piece)).collect();
+        assert_eq!(pieces, [" a", "ż", "", "c "]);
+        assert_eq!(s.split("").count(), 1);
+        assert_eq!(s.split("not there at all, longer than N").count(), 1);
+
+        let (head, tail) = s.split_once(",").unwrap();
+        assert_eq!((string(&head), string(&tail)), (" a".into(), "ż,,c ".into()));
+        let (head, tail) = s.rsplit_once(",").unwrap();
+        assert_eq!((string(&head), string(&tail)), (" a,ż,".into(), "c ".into()));
+        assert!(s.split_once(";").is_none());
+
+        let padded = S::from_str("\u{3000} ż \t").unwrap();
+        assert_eq!(string(&padded.trim()), "ż");
+        assert_eq!(string(&padded.trim_start()), "ż \t");
+        assert_eq!(string(&padded.trim_end()), "\u{3000} ż");
+        assert!(S::from_str(" \t ").unwrap().trim().is_empty());
+    }
+
+    #[test]
+    fn replace() {
+        let s = S::from_str("a-b-c").unwrap();
+        assert_eq!(string(&s.replace("-", "–").unwrap()), "a–b–c");
+        assert_eq!(string(&s.replace("-", "").unwrap()), "abc");
+        assert_eq!(string(&s.replace("", "x").unwrap()), "a-b-c");
+        assert_eq!(string(&s.replace("zz", "x").unwrap()), "a-b-c");
+        assert!(s.replace("-", "-------").is_none());
+    }
+
+    #[test]
+    fn case_mapping() {
+        let s = S::from_str("Żółw.EXE").unwrap();
+        assert_eq!(string(&s.to_unicode_lowercase().unwrap()), "żółw.exe");
+        assert_eq!(string(&s.to_unicode_uppercase().unwrap()), "ŻÓŁW.EXE");
+
+        // `ß` uppercases to two chars.
+        let s = U16CStackString::<4>::from_str("ßß").unwrap();
+        assert!(s.to_unicode_uppercase().is_none());
+        assert_eq!(string(&U16CStackString::<8>::from_str("ßß").unwrap().to_unicode_uppercase().unwrap()), "SSSS");
+
+        let s = S::from_str("ŻÓŁW.exe").unwrap();
+        assert!(s.eq_ignore_case("żółw.EXE"));
+        assert!(!s.eq_ignore_case("żółw.ex"));
+        let other: std::vec::Vec<u16> = "żÓłW.ExE".encode_utf16().collect();
+        assert!(s.eq_ignore_case_u16(&other));
+    }
+}
@@ -1,5 +1,10 @@
 use core::fmt;
 
+use crate::types::text;
+
+#[cfg(target_os = "linux")]
+use crate::types::{PATH_MAX, ToPath};
+
 pub struct HexDisplay<'a>(pub &'a [u8]);
 
 impl<'a> fmt::Display for HexDisplay<'a> {
@@ -78,6 +83,13 @@ impl<const N: usize> fmt::Debug for U8CStackString<N> {
     }
 }
 
+#[cfg(target_os = "linux")]
+impl<const N: usize> ToPath for U8CStackString<N> {
+    fn as_path(&self) -> Option<U8CStackString<PATH_MAX>> {
+        U8CStackString::from_bytes(self.as_slice())
+    }
+}
+
 impl<const N: usize> Default for U8CStackString<N> {
     fn default() -> Self {
         let mut buf = [0u8; N];
@@ -221,6 +233,10 @@ impl<const N: usize> U8CStackString<N> {
         &self.buf[..self.len]
     }
 
+    pub fn as_bytes_with_nul(&self) -> &[u8] {
+        &self.buf[..self.len + 1]
+    }
+
     pub fn substring<const M: usize>(&self, start: usize) -> Option<U8CStackString<M>> {
         let slice = self.as_slice();
         if start >= slice.len() {
@@ -292,11 +308,12 @@ impl<const N: usize> U8CStackString<N> {
         &slice[slice.len() - suffix.len()..] == suffix
     }
 
-    pub fn as_str(&self) -> &str {
-        core::str::from_utf8(self.as_slice()).unwrap_or("")
+    /// `None` if the contents are not UTF-8.
+    pub fn as_str(&self) -> Option<&str> {
+        core::str::from_utf8(self.as_slice()).ok()
     }
 
-    pub fn to_lowercase(&self) -> Self {
+    pub fn to_ascii_lowercase(&self) -> Self {
         let slice = self.as_slice();
         let mut result = Self::new();
         
@@ -307,7 +324,7 @@ impl<const N: usize> U8CStackString<N> {
         result
     }
 
-    pub fn to_uppercase(&self) -> Self {
+    pub fn to_ascii_uppercase(&self) -> Self {
         let slice = self.as_slice();
         let mut result = Self::new();
         
@@ -318,4 +335,295 @@ impl<const N: usize> U8CStackString<N> {
         result
     }
 
-}
\ No newline at end of file
+    #[deprecated(note = "ASCII only, use `to_ascii_lowercase` or `to_unicode_lowercase`")]
+    pub fn to_lowercase(&self) -> Self {
+        self.to_ascii_lowercase()
+    }
+
+    #[deprecated(note = "ASCII only, use `to_ascii_uppercase` or `to_unicode_uppercase`")]
+    pub fn to_uppercase(&self) -> Self {
+        self.to_ascii_uppercase()
+    }
+
+    /// Invalid UTF-8 sequences become U+FFFD.
+    pub fn chars(&self) -> impl Iterator<Item = char> + '_ {
+        self.as_slice().utf8_chunks().flat_map(|chunk| {
+            let invalid = (!chunk.invalid().is_empty()).then_some(char::REPLACEMENT_CHARACTER);
+            chunk.valid().chars().chain(invalid)
+        })
+    }
+
+    pub fn push_char(&mut self, ch: char) -> bool {
+        let mut buf = [0u8; 4];
+        let encoded = ch.encode_utf8(&mut buf);
+
+        if self.len + encoded.len() >= N {
+            return false;
+        }
+
+        self.push_str(encoded)
+    }
+
+    /// Byte index of the first match.
+    pub fn find(&self, pat: &str) -> Option<usize> {
+        text::find(self.as_slice(), pat.as_bytes())
+    }
+
+    pub fn rfind(&self, pat: &str) -> Option<usize> {
+        text::rfind(self.as_slice(), pat.as_bytes())
+    }
+
+    /// An empty separator yields the whole string once.
+    pub fn split<'a>(&'a self, sep: &'a str) -> U8Split<'a, N> {
+        U8Split {
+            rest: Some(self.as_slice()),
+            sep: sep.as_bytes(),
+        }
+    }
+
+    pub fn split_once(&self, sep: &str) -> Option<(Self, Self)> {
+        let slice = self.as_slice();
+        let index = self.find(sep)?;
+        Some((Self::from_piece(&slice[..index]), Self::from_piece(&slice[index + sep.len()..])))
+    }
+
+    pub fn rsplit_once(&self, sep: &str) -> Option<(Self, Self)> {
+        let slice = self.as_slice();
+        let index = self.rfind(sep)?;
+        Some((Self::from_piece(&slice[..index]), Self::from_piece(&slice[index + sep.len()..])))
+    }
+
+    /// Trims Unicode whitespace; invalid UTF-8 is kept and stops the trim.
+    pub fn trim(&self) -> Self {
+        let slice = self.as_slice();
+        let start = leading_whitespace(slice);
+        let end = slice.len() - trailing_whitespace(&slice[start..]);
+        Self::from_piece(&slice[start..end])
+    }
+
+    pub fn trim_start(&self) -> Self {
+        let slice = self.as_slice();
+        Self::from_piece(&slice[leading_whitespace(slice)..])
+    }
+
+    pub fn trim_end(&self) -> Self {
+        let slice = self.as_slice();
+        Self::from_piece(&slice[..slice.len() - trailing_whitespace(slice)])
+    }
+
+    /// Copies a slice already known to fit, e.g. a piece of another `Self`.
+    fn from_piece(bytes: &[u8]) -> Self {
+        let mut result = Self::new();
+        result.push_bytes(bytes);
+        result
+    }
+
+    /// `None` if the result does not fit. An empty `from` returns a copy.
+    pub fn replace(&self, from: &str, to: &str) -> Option<Self> {
+        let mut rest = self.as_slice();
+
+        if from.is_empty() {
+            return Self::from_bytes(rest);
+        }
+
+        let mut result = Self::new();
+
+        while let Some(index) = text::find(rest, from.as_bytes()) {
+            if !result.push_bytes(&rest[..index]) || !result.push_str(to) {
+                return None;
+            }
+            rest = &rest[index + from.len()..];
+        }
+
+        result.push_bytes(rest).then_some(result)
+    }
+
+    fn push_bytes(&mut self, bytes: &[u8]) -> bool {
+        if self.len + bytes.len() >= N {
+            return false;
+        }
+
+        self.buf[self.len..self.len + bytes.len()].copy_from_slice(bytes);
+        self.len += bytes.len();
+        self.buf[self.len] = 0;
+        true
+    }
+
+    /// Full Unicode mapping, so the result can be longer; `None` if it does not
+    /// fit or the contents are not UTF-8.
+    pub fn to_unicode_lowercase(&self) -> Option<Self> {
+        self.map_chars(char::to_lowercase)
+    }
+
+    pub fn to_unicode_uppercase(&self) -> Option<Self> {
+        self.map_chars(char::to_uppercase)
+    }
+
+    fn map_chars<I: Iterator<Item = char>>(&self, map: impl Fn(char) -> I) -> Option<Self> {
+        let value = core::str::from_utf8(self.as_slice()).ok()?;
+        let mut result = Self::new();
+
+        for ch in value.chars() {
+            if !map(ch).all(|mapped| result.push_char(mapped)) {
+                return None;
+            }
+        }
+
+        Some(result)
+    }
+
+    /// Compares under simple Unicode case folding, e.g. `"ŻÓŁW.EXE"` equals `"żółw.exe"`.
+    pub fn eq_ignore_case(&self, other: &str) -> bool {
+        text::eq_ignore_case(self.chars(), other.chars())
+    }
+
+}
+
+/// Bytes of whitespace before the first non-whitespace char or invalid sequence.
+fn leading_whitespace(bytes: &[u8]) -> usize {
+    let valid = bytes.utf8_chunks().next().map_or("", |chunk| chunk.valid());
+    valid.len() - valid.trim_start().len()
+}
+
+/// Bytes of whitespace after the last non-whitespace char or invalid sequence.
+fn trailing_whitespace(bytes: &[u8]) -> usize {
+    match bytes.utf8_chunks().last() {
+        Some(chunk) if chunk.invalid().is_empty() => chunk.valid().len() - chunk.valid().trim_end().len(),
+        _ => 0,
+    }
+}
+
+/// Pieces of a [`U8CStackString`] between separators, see [`U8CStackString::split`].
+pub struct U8Split<'a, const N: usize> {
+    rest: Option<&'a [u8]>,
+    sep: &'a [u8],
+}
+
+impl<const N: usize> Iterator for U8Split<'_, N> {
+    type Item = U8CStackString<N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = self.rest?;
+
+        match text::find(rest, self.sep).filter(|_| !self.sep.is_empty()) {
+            Some(index) => {
+                self.rest = Some(&rest[index + self.sep.len()..]);
+                Some(U8CStackString::from_piece(&rest[..index]))
+            }
+            None => {
+                self.rest = None;
+                Some(U8CStackString::from_piece(rest))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::string::String;
+    use std::vec::Vec;
+
+    type S = U8CStackString<16>;
+
+    fn s(value: &str) -> S {
+        S::from_str(value).unwrap()
+    }
+
+    fn invalid() -> S {
+        S::from_bytes(b" a\xFFb ").unwrap()
+    }
+
+    #[test]
+    fn as_str_and_chars() {
+        assert_eq!(s("żółw").as_str(), Some("żółw"));
+        assert_eq!(invalid().as_str(), None);
+        assert_eq!(invalid().chars().collect::<String>(), " a\u{FFFD}b ");
+        assert_eq!(s("a😀").chars().collect::<Vec<_>>(), ['a', '😀']);
+    }
+
+    #[test]
+    fn push_char_overflow() {
+        let mut value = U8CStackString::<4>::new();
+        assert!(!value.push_char('😀'));
+        assert!(value.push_char('a'));
+        assert!(value.push_char('ż'));
+        assert!(!value.push_char('b'));
+        assert_eq!(value.as_str(), Some("aż"));
+    }
+
+    #[test]
+    fn search() {
+        let value = s("łódź/łódź");
+        assert_eq!(value.find("ódź"), Some(2));
+        assert_eq!(value.rfind("ódź"), Some(10));
+        assert_eq!(value.find(""), Some(0));
+        assert_eq!(value.rfind(""), Some(value.len()));
+        assert_eq!(value.find("x"), None);
+        assert_eq!(invalid().find("b"), Some(3));
+    }
+
+    #[test]
+    fn split() {
+        let value = s(" a,ż,,c ");
+        let pieces: Vec<S> = value.split(",").collect();
+        let pieces: Vec<&str> = pieces.iter().map(|piece| piece.as_str().unwrap()).collect();
+        assert_eq!(pieces, [" a", "ż", "", "c "]);
+        assert_eq!(value.split("").count(), 1);
+
+        let (head, tail) = value.split_once(",").unwrap();
+        assert_eq!((head.as_str(), tail.as_str()), (Some(" a"), Some("ż,,c ")));
+        let (head, tail) = value.rsplit_once(",").unwrap();
+        assert_eq!((head.as_str(), tail.as_str()), (Some(" a,ż,"), Some("c ")));
+        assert!(value.split_once(";").is_none());
+
+        // Invalid UTF-8 is split like any other bytes.
+        let (head, tail) = invalid().split_once("a").unwrap();
+        assert_eq!((head.as_slice(), tail.as_slice()), (&b" "[..], &b"\xFFb "[..]));
+    }
+
+    #[test]
+    fn trim() {
+        let padded = s("\u{3000} ż \t");
+        assert_eq!(padded.trim().as_str(), Some("ż"));
+        assert_eq!(padded.trim_start().as_str(), Some("ż \t"));
+        assert_eq!(padded.trim_end().as_str(), Some("\u{3000} ż"));
+        assert!(s(" \t ").trim().is_empty());
+
+        // Invalid bytes are kept, whitespace around them still goes.
+        assert_eq!(invalid().trim().as_slice(), b"a\xFFb");
+        assert_eq!(invalid().trim_start().as_slice(), b"a\xFFb ");
+        assert_eq!(invalid().trim_end().as_slice(), b" a\xFFb");
+        let edges = S::from_bytes(b"\xFF \xFF").unwrap();
+        assert_eq!(edges.trim().as_slice(), b"\xFF \xFF");
+    }
+
+    #[test]
+    fn replace() {
+        let value = s("a-b-c");
+        assert_eq!(value.replace("-", "–").unwrap().as_str(), Some("a–b–c"));
+        assert_eq!(value.replace("-", "").unwrap().as_str(), Some("abc"));
+        assert_eq!(value.replace("", "x").unwrap().as_str(), Some("a-b-c"));
+        assert!(value.replace("-", "-------").is_none());
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn case_mapping() {
+        let value = s("Żółw.EXE");
+        assert_eq!(value.to_unicode_lowercase().unwrap().as_str(), Some("żółw.exe"));
+        assert_eq!(value.to_unicode_uppercase().unwrap().as_str(), Some("ŻÓŁW.EXE"));
+        assert_eq!(value.to_ascii_lowercase().as_str(), Some("Żółw.exe"));
+        assert_eq!(value.to_lowercase().as_str(), Some("Żółw.exe"));
+        assert_eq!(value.to_uppercase().as_str(), Some("ŻółW.EXE"));
+        assert!(invalid().to_unicode_lowercase().is_none());
+        // `İ` lowercases to `i` and a combining dot, one byte longer.
+        assert!(U8CStackString::<5>::from_str("İİ").unwrap().to_unicode_lowercase().is_none());
+
+        assert!(value.eq_ignore_case("żÓŁw.exe"));
+        assert!(!value.eq_ignore_case("żółw.ex"));
+        assert!(!invalid().eq_ignore_case(" a b "));
+    }
+}